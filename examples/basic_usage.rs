@@ -14,6 +14,7 @@ async fn main() -> Result<()> {
     let config = ClientConfig {
         base_url: "http://localhost:11434".to_string(),
         timeout: std::time::Duration::from_secs(30),
+        ..ClientConfig::default()
     };
     let client = OllamaClient::new(config)?;
 
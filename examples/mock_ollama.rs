@@ -0,0 +1,28 @@
+// examples/mock_ollama.rs
+//
+// This example runs the Ollama client against a fake server with canned
+// responses, so it works without a local Ollama installation.
+//
+// Run with: cargo run --example mock_ollama --features testing
+
+use projets_indexer::{error::Result, testing::MockOllama};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Start a fake Ollama that knows one model and always answers with the same tags
+    let mut ollama = MockOllama::start().await;
+    ollama.mock_models(&["mistral:latest"]).await;
+    ollama.mock_generate("rust, cli, indexing").await;
+
+    let client = ollama.client()?;
+
+    let models = client.list_models().await?;
+    println!("Installed models: {:?}", models);
+
+    // Generate tags for a project
+    let project_path = "/path/to/your/project";
+    let tags = client.generate_tags(project_path).await?;
+    println!("Generated tags for {}: {:?}", project_path, tags);
+
+    Ok(())
+}
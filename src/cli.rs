@@ -3,8 +3,18 @@
 //! This module provides the CLI interface using clap, including argument parsing
 //! and command-line options.
 
-use clap::{Parser, Subcommand};
-use std::path::PathBuf;
+use crate::config::{IndexerConfig, DEFAULT_CONFIG_FILE};
+use crate::error::Result;
+use crate::indexer::{maintenance::TagOrder, project_indexer::MtimeSource, OutputFormat};
+use crate::ollama::{ApiStyle, TagDelimiter};
+use clap::{
+    error::ErrorKind, parser::ValueSource, ArgMatches, Args, CommandFactory, FromArgMatches,
+    Parser, Subcommand, ValueEnum,
+};
+use std::collections::HashMap;
+#[cfg(feature = "server")]
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
 
 /// A powerful tool for indexing and organizing your projects
 #[derive(Parser)]
@@ -14,24 +24,246 @@ pub struct Cli {
     #[arg(short, long)]
     pub verbose: bool,
 
-    /// Disable color output in terminal
-    #[arg(short, long)]
+    /// Print command results as JSON
+    #[arg(
+        long,
+        global = true,
+        help = "Print the result of index, search or stats as JSON on stdout instead of formatted text"
+    )]
+    pub json: bool,
+
+    /// Only print results, warnings and errors
+    #[arg(
+        short,
+        long,
+        global = true,
+        help = "Hide the banner, section headers, info messages, per-project details and progress spinners"
+    )]
+    pub quiet: bool,
+
+    /// Disable color output in terminal (deprecated: use `--color never`)
+    #[arg(short, long, global = true)]
     pub no_color: bool,
 
+    /// When to use colors in terminal output
+    #[arg(long, value_enum, global = true, default_value_t = ColorChoice::Auto)]
+    pub color: ColorChoice,
+
     /// Enable Ollama for tag generation
     #[arg(short, long)]
     pub ollama: bool,
 
-    /// Ollama API URL
-    #[arg(long, default_value = "http://localhost:11434")]
-    pub ollama_url: String,
+    /// Ollama API URLs, tag requests are spread across all of them
+    #[arg(
+        long,
+        global = true,
+        env = "OLLAMA_HOST",
+        value_delimiter = ',',
+        default_value = "http://localhost:11434",
+        help = "Ollama server URL; repeat (or separate with commas) to spread tag requests across several servers"
+    )]
+    pub ollama_url: Vec<String>,
+
+    /// Time limit for each Ollama request
+    #[arg(
+        long,
+        global = true,
+        value_name = "SECS",
+        env = "OLLAMA_TIMEOUT",
+        default_value_t = crate::ollama::DEFAULT_TIMEOUT_SECS,
+        help = "Give up on an Ollama request after SECS seconds, e.g. longer for slow remote servers"
+    )]
+    pub ollama_timeout_secs: u64,
+
+    /// Retries of a generate request failing with a transient error
+    #[arg(
+        long,
+        global = true,
+        value_name = "N",
+        default_value_t = crate::ollama::DEFAULT_MAX_RETRIES,
+        help = "Retry a tagging request failing with a connection error, timeout or server error up to N times"
+    )]
+    pub ollama_retries: u32,
+
+    /// Delay before the first retry of a generate request
+    #[arg(
+        long,
+        global = true,
+        value_name = "MS",
+        default_value_t = crate::ollama::DEFAULT_RETRY_BASE_DELAY_MS,
+        help = "Wait MS milliseconds before the first retry, doubling the wait for each further retry up to 30 seconds"
+    )]
+    pub ollama_retry_delay_ms: u64,
+
+    /// API key for an Ollama server behind an authenticating proxy
+    #[arg(
+        long,
+        env = "OLLAMA_API_KEY",
+        hide_env_values = true,
+        help = "Send this key as a bearer token with every Ollama request"
+    )]
+    pub ollama_api_key: Option<String>,
+
+    /// API flavor spoken by the server at --ollama-url
+    #[arg(
+        long,
+        value_enum,
+        global = true,
+        default_value_t = ApiStyle::Ollama,
+        help = "Server API: ollama, or openai for OpenAI-compatible servers like llama.cpp"
+    )]
+    pub api_style: ApiStyle,
+
+    /// Load the model before the first request
+    #[arg(
+        long,
+        global = true,
+        help = "Send a warm-up request so the model is loaded before tagging starts"
+    )]
+    pub warm_model: bool,
+
+    /// Model used for tag generation
+    #[arg(
+        long,
+        global = true,
+        env = "OLLAMA_MODEL",
+        default_value = crate::ollama::DEFAULT_MODEL,
+        help = "Model used to generate tags; pulled automatically from a local Ollama if missing"
+    )]
+    pub model: String,
+
+    /// Configuration file providing default paths and settings
+    #[arg(
+        long,
+        global = true,
+        value_name = "FILE",
+        help = "TOML configuration file [default: ./projets-indexer.toml if present]"
+    )]
+    pub config: Option<PathBuf>,
 
     /// The command to execute
     #[command(subcommand)]
     pub command: Commands,
 }
 
+/// When to color terminal output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ColorChoice {
+    /// Color output when writing to a terminal
+    Auto,
+    /// Always color output
+    Always,
+    /// Never color output
+    Never,
+}
+
+/// Output format of the `stats --timeline` report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum TimelineFormat {
+    /// A formatted chart
+    Text,
+    /// `year-month,count` rows with a header
+    Csv,
+}
+
+/// Output format of the `diff` command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DiffFormat {
+    /// A readable list of changes
+    Text,
+    /// A JSON object with `added`, `removed` and `changed` projects
+    Json,
+}
+
+/// Output shape of the `generate-tags` command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum TagsFormat {
+    /// A JSON array of tags
+    JsonArray,
+    /// A JSON object with `project`, `path` and `tags` fields
+    JsonObject,
+    /// One tag per line
+    Lines,
+    /// Tags separated by commas
+    Comma,
+}
+
+/// Tag generation settings shared by `index` and `retag`
+#[derive(Args)]
+pub struct TaggingArgs {
+    /// Prefer tags from `.tags` files over generated ones
+    #[arg(
+        long,
+        help = "Use a project's .tags file instead of generating tags, rather than merging both"
+    )]
+    pub prefer_file_tags: bool,
+
+    /// File of example projects and tags to include in the prompt
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Few-shot examples for tag generation, one `project: tag1, tag2` per line"
+    )]
+    pub tag_examples: Option<PathBuf>,
+
+    /// System prompt sent to Ollama when generating tags
+    #[arg(
+        long,
+        value_name = "TEXT|@FILE",
+        help = "Replace the default system prompt for tag generation; prefix a path with @ to read it from a file"
+    )]
+    pub system_prompt: Option<String>,
+
+    /// How tags are separated in model responses
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = TagDelimiter::Auto,
+        help = "Tag separator in model responses: auto-detect, or force comma, newline or semicolon"
+    )]
+    pub tag_delimiter: TagDelimiter,
+
+    /// Minimum confidence for generated tags
+    #[arg(
+        long,
+        value_name = "CONFIDENCE",
+        value_parser = parse_confidence,
+        help = "Ask the model to rate each tag from 0 to 1 and drop tags rated below CONFIDENCE (ignored for models that don't return ratings)"
+    )]
+    pub tag_min_confidence: Option<f32>,
+
+    /// Number of recent commit messages to tag from
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Show the model the subjects of each project's last N commits (at most 20) when generating tags"
+    )]
+    pub tag_from_commits: Option<usize>,
+
+    /// Fallback tags for projects tagging fails for
+    #[arg(
+        long,
+        value_name = "TAGS",
+        value_delimiter = ',',
+        help = "Comma-separated tags for projects whose tag generation fails or returns nothing (default: leave them untagged)"
+    )]
+    pub default_tags: Vec<String>,
+
+    /// Put all projects in one category
+    #[arg(
+        long,
+        help = "Put every project in the single category 'all', to organize projects by tags only"
+    )]
+    pub flatten_categories: bool,
+
+    /// Display names for categories, from the configuration file
+    #[arg(skip)]
+    pub category_aliases: HashMap<String, String>,
+}
+
 /// Available commands for the project indexer
+// Parsed once per run, so the size of the `Index` variant doesn't matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 pub enum Commands {
     /// Index projects in the specified directory
@@ -49,6 +281,7 @@ pub enum Commands {
             short = 'd',
             long,
             default_value = "~/projects",
+            env = "PROJECTS_DIR",
             help = "Directory containing projects to index"
         )]
         projects_dir: PathBuf,
@@ -58,10 +291,19 @@ pub enum Commands {
             short,
             long,
             default_value = "projects_index.json",
-            help = "JSON file to store the project index"
+            env = "INDEX_FILE",
+            help = "JSON file to store the project index (use a .jsonl extension for one project per line)"
         )]
         output: PathBuf,
 
+        /// Format of the index file
+        #[arg(
+            long,
+            value_enum,
+            help = "Format of the index file [default: from the output file extension (.json, .jsonl, .yaml, .yml, .toml, .md, .csv), else json]"
+        )]
+        format: Option<OutputFormat>,
+
         /// Maximum directory depth to traverse
         #[arg(
             short = 'x',
@@ -80,14 +322,255 @@ pub enum Commands {
         )]
         min_depth: u32,
 
+        /// Depth of projects below the projects directory
+        #[arg(
+            long,
+            value_name = "N|auto",
+            value_parser = parse_projects_dir_depth,
+            conflicts_with_all = ["max_depth", "min_depth"],
+            help = "Scan only for projects N levels below the projects directory, or 'auto' to detect the level most git repositories and build manifests are at"
+        )]
+        projects_dir_depth: Option<ProjectsDirDepth>,
+
         /// Exclude specific directories (comma-separated)
         #[arg(
             short = 'e',
             long,
-            default_value = ".git,node_modules,__pycache__,target,.idea,.vscode",
-            help = "Directories to exclude (comma-separated)"
+            default_value = crate::indexer::project_indexer::DEFAULT_EXCLUDE_DIRS,
+            help = "Directories to exclude (comma-separated); start with '+' to add to the defaults instead of replacing them"
         )]
         exclude: String,
+
+        /// Stop measuring a project after this many files
+        #[arg(
+            long,
+            default_value_t = crate::indexer::project_indexer::DEFAULT_MAX_FILES_PER_PROJECT,
+            help = "Maximum number of files to walk per project before reporting a partial size"
+        )]
+        max_files_per_project: u64,
+
+        /// Save raw Ollama responses for debugging
+        #[arg(
+            long,
+            value_name = "DIR",
+            help = "Write each project's raw Ollama response before parsing to DIR/<path>.txt, where <path> is the project's path below the projects directory with / replaced by __"
+        )]
+        save_raw_responses: Option<PathBuf>,
+
+        /// How to compute each project's last modified time
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = MtimeSource::Git,
+            help = "Source of last_modified: latest git commit, directory mtime, or newest file in the tree"
+        )]
+        mtime_source: MtimeSource,
+
+        /// Time limit for git commands
+        #[arg(
+            long,
+            value_name = "SECS",
+            default_value_t = crate::indexer::project_indexer::DEFAULT_GIT_TIMEOUT_SECS,
+            help = "Give up on a project's git commands after SECS seconds"
+        )]
+        git_timeout_secs: u64,
+
+        /// Age after which git repositories count as archived
+        #[arg(
+            long,
+            value_name = "DAYS",
+            default_value_t = crate::indexer::project_indexer::DEFAULT_ARCHIVE_THRESHOLD_DAYS,
+            help = "Mark git repositories without a commit in the last DAYS days as archived"
+        )]
+        archive_threshold_days: u32,
+
+        /// Glob patterns of paths to exclude
+        #[arg(
+            long = "exclude-path",
+            value_name = "GLOB",
+            help = "Skip paths matching GLOB, e.g. '**/archive/**' (repeatable; --exclude matches directory names)"
+        )]
+        exclude_paths: Vec<String>,
+
+        /// Ancestor directory used as the category
+        #[arg(
+            long,
+            value_name = "N",
+            default_value_t = crate::indexer::project_indexer::DEFAULT_CATEGORY_DEPTH,
+            conflicts_with = "flatten_categories",
+            help = "Take each project's category from the directory N levels above it (1 = parent)"
+        )]
+        category_depth: usize,
+
+        /// Category for projects without a category directory
+        #[arg(
+            long,
+            value_name = "NAME",
+            default_value = "uncategorized",
+            help = "Category for projects directly under the projects directory, or nested too shallowly for --category-depth"
+        )]
+        default_category: String,
+
+        /// Categorize projects by language instead of directory
+        #[arg(
+            long,
+            conflicts_with = "flatten_categories",
+            help = "Use each project's language, from its build manifest (Cargo.toml, package.json, ...) or line counts, as its category"
+        )]
+        category_from_manifest: bool,
+
+        /// Fail on the first error instead of skipping
+        #[arg(
+            long,
+            help = "Stop at the first unreadable directory or failed project instead of skipping it"
+        )]
+        abort_on_error: bool,
+
+        /// Number of projects processed at the same time
+        #[arg(
+            long,
+            value_name = "N",
+            default_value_t = crate::indexer::project_indexer::DEFAULT_MAX_CONCURRENCY,
+            value_parser = parse_concurrency,
+            help = "Process up to N projects at the same time, overlapping their Ollama requests"
+        )]
+        max_concurrency: usize,
+
+        /// Walk top-level directories concurrently when discovering projects
+        #[arg(
+            long,
+            help = "Walk top-level directories concurrently when discovering projects"
+        )]
+        parallel_walk: bool,
+
+        /// Report progress as JSON lines on stderr
+        #[arg(
+            long,
+            help = "Also write progress events to stderr as JSON lines, for programs wrapping the CLI"
+        )]
+        progress_json: bool,
+
+        /// Only index git repositories
+        #[arg(long, help = "Skip directories that don't contain a .git folder")]
+        git_only: bool,
+
+        /// Detect each project's dominant language by line count
+        #[arg(
+            long,
+            help = "Count lines per file extension to record each project's primary language (slow)"
+        )]
+        detect_primary_language: bool,
+
+        /// Record a histogram of file extensions per project
+        #[arg(
+            long,
+            help = "Count each project's files by extension, skipping excluded directories"
+        )]
+        collect_file_types: bool,
+
+        /// Record the dependencies declared in each project's manifest
+        #[arg(
+            long,
+            help = "Record the dependencies listed in each project's Cargo.toml, package.json or requirements.txt"
+        )]
+        collect_dependencies: bool,
+
+        /// Clean up directory names into display names
+        #[arg(
+            long,
+            help = "Turn directory names into display names, e.g. my-project-v2_final into 'My Project'; the raw name is kept as dir_name"
+        )]
+        clean_names: bool,
+
+        /// Tag generation settings
+        #[command(flatten)]
+        tagging: TaggingArgs,
+
+        /// Continue each tagging request from a context primed with the system prompt
+        #[arg(
+            long,
+            requires = "max_concurrency",
+            help = "Encode the system prompt once and continue every tagging request from it, so the model doesn't re-process it (requires --max-concurrency 1)"
+        )]
+        reuse_context: bool,
+
+        /// Store an embedding of each project for semantic search
+        #[arg(
+            long,
+            help = "Store an embedding of each project's name, tags and type, computed with --model, for `search --semantic` (needs --ollama)"
+        )]
+        embed: bool,
+
+        /// File embeddings are cached in
+        #[arg(
+            long,
+            value_name = "PATH",
+            requires = "embed",
+            help = "Cache embeddings in PATH, reusing them for unchanged projects on the next run (default: <index>.embeddings.json)"
+        )]
+        embedding_cache: Option<PathBuf>,
+
+        /// Keep hand-added tags when re-indexing
+        #[arg(
+            long,
+            help = "Keep tags from the existing index that weren't generated by the model, and only refresh generated ones"
+        )]
+        preserve_manual_tags: bool,
+
+        /// Order of each project's tags in the index
+        #[arg(
+            long,
+            value_enum,
+            value_name = "ORDER",
+            help = "Sort each project's tags alphabetically (alpha) or most common across the index first (frequency) [default: generation order]"
+        )]
+        sort_tags: Option<TagOrder>,
+
+        /// Only re-index projects changed since a git commit
+        #[arg(
+            long,
+            value_name = "REF",
+            help = "Only re-index projects with files changed between REF and HEAD, keeping the other entries of the existing index (the projects directory must be inside a git repository)"
+        )]
+        since_commit: Option<String>,
+
+        /// Resume an interrupted run
+        #[arg(
+            long,
+            help = "Reuse the projects processed by an interrupted run instead of processing them again"
+        )]
+        resume: bool,
+
+        /// Reuse the existing index entries of unchanged projects
+        #[arg(
+            long,
+            help = "Keep the existing index entry, tags included, of projects whose last modification time hasn't changed, and only process new or changed projects"
+        )]
+        incremental: bool,
+
+        /// Write projects to the index as they are processed
+        #[arg(
+            long,
+            conflicts_with_all = ["since_commit", "resume", "incremental", "sort_tags"],
+            help = "Write each project to the output file as soon as it is processed instead of collecting them first, to bound memory on large collections (projects are kept in discovery order)"
+        )]
+        stream_write: bool,
+
+        /// Preview the projects a run would index
+        #[arg(
+            long,
+            conflicts_with = "stream_write",
+            help = "List the projects that would be indexed, with a summary, without generating tags or writing the index"
+        )]
+        dry_run: bool,
+
+        /// Overwrite an existing index without asking
+        #[arg(
+            short,
+            long,
+            help = "Overwrite the output file if it exists (required when not running in a terminal)"
+        )]
+        force: bool,
     },
 
     /// Search through indexed projects
@@ -105,6 +588,7 @@ pub enum Commands {
             short,
             long,
             default_value = "projects_index.json",
+            env = "INDEX_FILE",
             help = "JSON file containing the project index"
         )]
         index_file: PathBuf,
@@ -116,6 +600,52 @@ pub enum Commands {
         /// Search only in categories
         #[arg(short, long, help = "Only search in project categories")]
         category_only: bool,
+
+        /// Match the query exactly
+        #[arg(long, help = "Match case and accents exactly instead of ignoring them")]
+        exact: bool,
+
+        /// Rank projects by how closely they match
+        #[arg(
+            long,
+            group = "ranked",
+            help = "Rank projects by a fuzzy-match score, tolerating typos and left-out letters"
+        )]
+        fuzzy: bool,
+
+        /// Rank projects by meaning
+        #[arg(
+            long,
+            group = "ranked",
+            conflicts_with_all = ["tags_only", "category_only", "exact"],
+            help = "Rank projects by the similarity of their embedding with the query's, computed with --model (needs an index built with --embed)"
+        )]
+        semantic: bool,
+
+        /// List matches by most recent commit first
+        #[arg(
+            long,
+            conflicts_with = "ranked",
+            help = "List matching projects by most recent commit first instead of index order"
+        )]
+        recent: bool,
+
+        /// Maximum number of ranked matches
+        #[arg(
+            long,
+            value_name = "N",
+            default_value_t = 10,
+            requires = "ranked",
+            help = "Show at most N fuzzy or semantic matches"
+        )]
+        limit: usize,
+
+        /// Reject malformed index files
+        #[arg(
+            long,
+            help = "Fail with the position of the first malformed entry instead of tolerating blank lines"
+        )]
+        strict_json: bool,
     },
 
     /// Show project statistics
@@ -133,19 +663,69 @@ pub enum Commands {
             short,
             long,
             default_value = "projects_index.json",
+            env = "INDEX_FILE",
             help = "JSON file containing the project index"
         )]
         index_file: PathBuf,
 
         /// Show detailed statistics
-        #[arg(short, long, help = "Show detailed statistics for each category")]
+        #[arg(
+            short,
+            long,
+            help = "Also show the most common tags and the status of the projects in each category"
+        )]
         detailed: bool,
+
+        /// Number of tags listed by --detailed
+        #[arg(
+            long,
+            value_name = "N",
+            default_value_t = 10,
+            requires = "detailed",
+            help = "List the N most common tags with --detailed"
+        )]
+        top_tags: usize,
+
+        /// Report project activity by month
+        #[arg(
+            long,
+            help = "Count projects by the month they were last modified, oldest month first"
+        )]
+        timeline: bool,
+
+        /// Output format of the timeline
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = TimelineFormat::Text,
+            requires = "timeline",
+            help = "Timeline output: a text chart, or CSV rows of year-month,count"
+        )]
+        format: TimelineFormat,
+
+        /// List months without activity in the timeline
+        #[arg(
+            long,
+            requires = "timeline",
+            help = "Include months in which no project was modified"
+        )]
+        empty_months: bool,
+
+        /// Reject malformed index files
+        #[arg(
+            long,
+            help = "Fail with the position of the first malformed entry instead of tolerating blank lines"
+        )]
+        strict_json: bool,
     },
 
     /// Generate tags for a specific project
     #[command(
         about = "Generate tags for a specific project",
-        long_about = "Use Ollama to generate descriptive tags for a specific project directory."
+        long_about = "Use Ollama to generate descriptive tags for a specific project directory. \
+        Tags from the project's .tags file are included. The tags are printed, or written to \
+        --output, in the shape chosen with --tags-format. With --compare-models, tags are \
+        generated with each model and printed side by side, or written to --output as JSON."
     )]
     GenerateTags {
         /// Project directory
@@ -159,10 +739,516 @@ pub enum Commands {
         /// Output file for the tags
         #[arg(short, long, help = "Optional file to save the generated tags")]
         output: Option<PathBuf>,
+
+        /// Shape of the generated tags output
+        #[arg(
+            long = "tags-format",
+            alias = "tags-output-format",
+            value_enum,
+            default_value_t = TagsFormat::JsonArray,
+            help = "Output as a JSON array, a JSON object with project, path and tags, one tag per line, or comma-separated"
+        )]
+        tags_format: TagsFormat,
+
+        /// Tags used when Ollama can't be reached
+        #[arg(
+            long,
+            value_name = "TAGS",
+            value_delimiter = ',',
+            default_value = "rust,cli",
+            help = "Comma-separated tags to fall back to when Ollama is unreachable"
+        )]
+        default_tags: Vec<String>,
+
+        /// Models to compare
+        #[arg(
+            long,
+            value_name = "MODELS",
+            value_delimiter = ',',
+            conflicts_with = "tags_format",
+            help = "Generate tags with each of these comma-separated models and show them side by side"
+        )]
+        compare_models: Vec<String>,
+    },
+
+    /// Remove rarely used tags from the index
+    #[command(
+        about = "Remove tags used by fewer than N projects",
+        long_about = "Count how many projects carry each tag and remove the tags that occur \
+        fewer than N times from every project, then rewrite the index. Use --dry-run to \
+        preview which tags would be pruned."
+    )]
+    PruneTags {
+        /// Index file to prune
+        #[arg(
+            short,
+            long,
+            default_value = "projects_index.json",
+            env = "INDEX_FILE",
+            help = "JSON file containing the project index"
+        )]
+        index_file: PathBuf,
+
+        /// Minimum tag frequency to keep
+        #[arg(
+            short,
+            long = "below",
+            value_name = "N",
+            help = "Remove tags used by fewer than N projects"
+        )]
+        below: usize,
+
+        /// Preview without rewriting the index
+        #[arg(
+            long,
+            help = "Show which tags would be pruned without modifying the index"
+        )]
+        dry_run: bool,
+    },
+
+    /// Remove the projects under a directory from the index
+    #[command(
+        about = "Remove the projects under a directory from the index",
+        long_about = "Drop every index entry whose path lies within DIR, e.g. a projects root \
+        that is no longer tracked, and rewrite the index. The directory doesn't need to exist \
+        anymore. Use --dry-run to preview which projects would be removed."
+    )]
+    Prune {
+        /// Directory whose projects are removed
+        #[arg(
+            long,
+            value_name = "DIR",
+            help = "Remove the projects located within DIR"
+        )]
+        under: PathBuf,
+
+        /// Index file to prune
+        #[arg(
+            short,
+            long,
+            default_value = "projects_index.json",
+            env = "INDEX_FILE",
+            help = "JSON file containing the project index"
+        )]
+        index_file: PathBuf,
+
+        /// Preview without rewriting the index
+        #[arg(
+            long,
+            help = "Show which projects would be removed without modifying the index"
+        )]
+        dry_run: bool,
+    },
+
+    /// Regenerate tags for projects in an existing index
+    #[command(
+        about = "Regenerate tags for indexed projects",
+        long_about = "Load an existing index, generate tags again with Ollama and rewrite the \
+        index. With --missing-only, only projects without any tags are re-tagged, which is \
+        the cheapest way to recover from an interrupted or partly failed run. Projects whose \
+        tags can't be regenerated keep their previous tags."
+    )]
+    Retag {
+        /// Index file to re-tag
+        #[arg(
+            short,
+            long,
+            default_value = "projects_index.json",
+            env = "INDEX_FILE",
+            help = "JSON file containing the project index"
+        )]
+        index_file: PathBuf,
+
+        /// Only re-tag projects without tags
+        #[arg(long, help = "Only regenerate tags for projects whose tags are empty")]
+        missing_only: bool,
+
+        /// Report progress as JSON lines on stderr
+        #[arg(
+            long,
+            help = "Also write progress events to stderr as JSON lines, for programs wrapping the CLI"
+        )]
+        progress_json: bool,
+
+        /// Tag generation settings
+        #[command(flatten)]
+        tagging: TaggingArgs,
+    },
+
+    /// Check an index file for problems
+    #[command(
+        about = "Check an index file for problems",
+        long_about = "Load an index file and check that it is valid JSON, that every entry is a \
+        valid project, that paths are consistently absolute or relative, that no path appears \
+        twice and that no tag is empty. Each problem is reported with its line or entry, and \
+        the command exits with an error if any are found."
+    )]
+    Verify {
+        /// Index file to check
+        #[arg(
+            short,
+            long,
+            default_value = "projects_index.json",
+            env = "INDEX_FILE",
+            help = "JSON file containing the project index"
+        )]
+        index_file: PathBuf,
+    },
+
+    /// Compare two index files
+    #[command(
+        about = "Show what changed between two index files",
+        long_about = "Compare an old and a new index, matching projects by path, and list the \
+        projects that were added or removed and those whose name, category, status or tags \
+        changed. Useful to review a re-index before committing the new index."
+    )]
+    Diff {
+        /// Previous version of the index
+        #[arg(long, value_name = "FILE", help = "Old index file")]
+        old: PathBuf,
+
+        /// Current version of the index
+        #[arg(long, value_name = "FILE", help = "New index file")]
+        new: PathBuf,
+
+        /// Output format
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = DiffFormat::Text,
+            help = "Print the changes as text or as a JSON object"
+        )]
+        format: DiffFormat,
+    },
+
+    /// Write an existing index in another format
+    #[command(
+        about = "Export an existing index as JSON, YAML, TOML, Markdown or CSV",
+        long_about = "Load an index file and write its projects in another format, without \
+        re-scanning: e.g. a Markdown catalog for a wiki or a CSV table for a spreadsheet. \
+        Without --output the export is printed to stdout."
+    )]
+    Export {
+        /// Index file to export
+        #[arg(
+            short,
+            long,
+            default_value = "projects_index.json",
+            env = "INDEX_FILE",
+            help = "JSON file containing the project index"
+        )]
+        index_file: PathBuf,
+
+        /// Format of the export
+        #[arg(
+            long,
+            value_enum,
+            help = "Format of the export [default: from the output file extension, else json]"
+        )]
+        format: Option<OutputFormat>,
+
+        /// File to write the export to
+        #[arg(
+            short,
+            long,
+            value_name = "FILE",
+            help = "Write the export to FILE instead of stdout"
+        )]
+        output: Option<PathBuf>,
+    },
+
+    /// Give a project a display name
+    #[command(
+        alias = "rename-project",
+        about = "Set the display name of an indexed project",
+        long_about = "Change the name of the project at PATH in the index without re-scanning. \
+        The name is also recorded next to the index, in <index>.names.json, so it is kept \
+        when the projects are indexed again."
+    )]
+    SetName {
+        /// Directory of the project to rename
+        #[arg(help = "Path of the project, as indexed or relative to the current directory")]
+        path: PathBuf,
+
+        /// New display name
+        #[arg(help = "Name to display for the project")]
+        name: String,
+
+        /// Index file holding the project
+        #[arg(
+            short,
+            long,
+            default_value = "projects_index.json",
+            env = "INDEX_FILE",
+            help = "JSON file containing the project index"
+        )]
+        index_file: PathBuf,
+    },
+
+    /// Serve the index over HTTP
+    #[cfg(feature = "server")]
+    #[command(
+        about = "Serve the index as a read-only JSON API",
+        long_about = "Start an HTTP server exposing GET /projects (optionally filtered with \
+        ?tag=NAME) and GET /stats. The index file is reloaded when it changes on disk."
+    )]
+    Serve {
+        /// Index file to serve
+        #[arg(
+            short,
+            long,
+            default_value = "projects_index.json",
+            env = "INDEX_FILE",
+            help = "JSON file containing the project index"
+        )]
+        index_file: PathBuf,
+
+        /// Address to listen on
+        #[arg(
+            short,
+            long,
+            default_value = "127.0.0.1:8080",
+            help = "Address and port to listen on"
+        )]
+        addr: SocketAddr,
     },
 }
 
+impl Cli {
+    /// Resolve the color setting
+    ///
+    /// The deprecated `--no-color` flag turns colors off. So does a
+    /// non-empty `NO_COLOR` environment variable (see <https://no-color.org>),
+    /// unless `--color` is given a value other than `auto`.
+    pub fn color_choice(&self) -> ColorChoice {
+        let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        if self.no_color || (self.color == ColorChoice::Auto && no_color_env) {
+            ColorChoice::Never
+        } else {
+            self.color
+        }
+    }
+
+    /// Check constraints between arguments that depend on their values
+    ///
+    /// These can't be declared on the arguments, e.g. because the index
+    /// format may come from the output file's extension.
+    pub fn validate(&self) -> std::result::Result<(), clap::Error> {
+        let conflict =
+            |message: &str| Err(Cli::command().error(ErrorKind::ArgumentConflict, message));
+        if self.json
+            && !matches!(
+                self.command,
+                Commands::Index { .. } | Commands::Search { .. } | Commands::Stats { .. }
+            )
+        {
+            return conflict("--json is only supported by the index, search and stats commands");
+        }
+        // Indexes are read back in the format of their extension, so an
+        // explicit --format must agree with it
+        let mismatch = |format: Option<OutputFormat>, path: &Path| {
+            let format = format?;
+            if OutputFormat::from_path(path)? == format {
+                return None;
+            }
+            Some(format!(
+                "--format {} doesn't match the extension of {}",
+                format.to_possible_value()?.get_name(),
+                path.display()
+            ))
+        };
+        if let Commands::Index {
+            output,
+            format,
+            max_depth,
+            min_depth,
+            reuse_context,
+            max_concurrency,
+            stream_write,
+            incremental,
+            preserve_manual_tags,
+            since_commit,
+            ..
+        } = &self.command
+        {
+            if min_depth > max_depth {
+                return conflict(&format!(
+                    "--min-depth {} is greater than --max-depth {}",
+                    min_depth, max_depth
+                ));
+            }
+            if let Some(message) = mismatch(*format, output) {
+                return conflict(&message);
+            }
+            if *reuse_context && *max_concurrency != 1 {
+                return conflict("--reuse-context requires --max-concurrency 1");
+            }
+            let format = OutputFormat::resolve(*format, output);
+            if *stream_write && format != OutputFormat::Json {
+                return conflict("--stream-write only writes JSON indexes");
+            }
+            if *stream_write && self.json {
+                return conflict(
+                    "--json can't be used with --stream-write, which doesn't keep the projects",
+                );
+            }
+            // Markdown and CSV indexes can't be read back to be updated
+            let readable = !matches!(format, OutputFormat::Markdown | OutputFormat::Csv);
+            if !readable && (*incremental || *preserve_manual_tags || since_commit.is_some()) {
+                return conflict(
+                    "Markdown and CSV indexes can't be used with --incremental, --preserve-manual-tags or --since-commit",
+                );
+            }
+        }
+        if let Commands::Export {
+            format,
+            output: Some(output),
+            ..
+        } = &self.command
+        {
+            if let Some(message) = mismatch(*format, output) {
+                return conflict(&message);
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the command prints JSON, in which case errors are reported
+    /// as JSON as well
+    pub fn json_output(&self) -> bool {
+        // `validate` rejects `--json` on the other commands
+        self.json
+            || matches!(
+                self.command,
+                Commands::Diff {
+                    format: DiffFormat::Json,
+                    ..
+                }
+            )
+    }
+
+    /// Fill in settings not given on the command line from a config file
+    ///
+    /// Values passed explicitly on the command line always win; only
+    /// arguments left at their built-in default are replaced.
+    pub fn apply_config(&mut self, matches: &ArgMatches, config: &IndexerConfig) {
+        let is_default = |name: &str| {
+            matches
+                .subcommand()
+                .map(|(_, sub)| sub.value_source(name) == Some(ValueSource::DefaultValue))
+                .unwrap_or(false)
+        };
+
+        if config.enable_ollama {
+            self.ollama = true;
+        }
+
+        match &mut self.command {
+            Commands::Index {
+                projects_dir,
+                output,
+                tagging,
+                ..
+            } => {
+                if is_default("projects_dir") {
+                    *projects_dir = config.projects_dir.clone();
+                }
+                if is_default("output") {
+                    *output = config.index_file.clone();
+                }
+                tagging.category_aliases = config.category_aliases.clone();
+            }
+            Commands::Retag {
+                index_file,
+                tagging,
+                ..
+            } => {
+                if is_default("index_file") {
+                    *index_file = config.index_file.clone();
+                }
+                tagging.category_aliases = config.category_aliases.clone();
+            }
+            Commands::Search { index_file, .. }
+            | Commands::Stats { index_file, .. }
+            | Commands::PruneTags { index_file, .. }
+            | Commands::Prune { index_file, .. }
+            | Commands::Verify { index_file }
+            | Commands::Export { index_file, .. }
+            | Commands::SetName { index_file, .. } => {
+                if is_default("index_file") {
+                    *index_file = config.index_file.clone();
+                }
+            }
+            #[cfg(feature = "server")]
+            Commands::Serve { index_file, .. } => {
+                if is_default("index_file") {
+                    *index_file = config.index_file.clone();
+                }
+            }
+            Commands::GenerateTags { .. } | Commands::Diff { .. } => {}
+        }
+    }
+}
+
+/// Depth at which projects are scanned for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectsDirDepth {
+    /// A fixed number of levels below the projects directory
+    Fixed(u32),
+    /// The level most projects are found at, detected by probing the tree
+    Auto,
+}
+
+/// Parse a projects directory depth: a number or `auto`
+fn parse_projects_dir_depth(value: &str) -> std::result::Result<ProjectsDirDepth, String> {
+    if value.eq_ignore_ascii_case("auto") {
+        return Ok(ProjectsDirDepth::Auto);
+    }
+    value
+        .parse()
+        .map(ProjectsDirDepth::Fixed)
+        .map_err(|_| format!("'{}' is neither a depth nor 'auto'", value))
+}
+
+/// Parse a confidence threshold between 0 and 1
+fn parse_confidence(value: &str) -> std::result::Result<f32, String> {
+    let confidence: f32 = value
+        .parse()
+        .map_err(|_| format!("'{}' is not a number", value))?;
+    if (0.0..=1.0).contains(&confidence) {
+        Ok(confidence)
+    } else {
+        Err(format!("{} is not between 0 and 1", confidence))
+    }
+}
+
+/// Parse a number of projects to process at the same time, at least 1
+fn parse_concurrency(value: &str) -> std::result::Result<usize, String> {
+    match value.parse() {
+        Ok(0) => Err("at least one project must be processed at a time".to_string()),
+        Ok(concurrency) => Ok(concurrency),
+        Err(_) => Err(format!("'{}' is not a positive number", value)),
+    }
+}
+
 /// Parse command-line arguments
-pub fn parse_args() -> Cli {
-    Cli::parse()
+///
+/// Settings come from the command line first, then from the configuration
+/// file (`--config`, or `projets-indexer.toml` in the current directory when
+/// present), then from built-in defaults.
+pub fn parse_args() -> Result<Cli> {
+    let matches = Cli::command().get_matches();
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+
+    let config_path = cli.config.clone().or_else(|| {
+        let default = PathBuf::from(DEFAULT_CONFIG_FILE);
+        default.exists().then_some(default)
+    });
+    if let Some(path) = config_path {
+        let config = IndexerConfig::from_file(&path)?;
+        cli.apply_config(&matches, &config);
+    }
+    cli.validate().unwrap_or_else(|e| e.exit());
+
+    Ok(cli)
 }
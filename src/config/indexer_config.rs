@@ -3,8 +3,15 @@
 //! This module contains the configuration types and settings used by the project indexer.
 //! It defines how the indexer should behave, including directory paths and feature flags.
 
+use crate::error::{AppError, OllamaError, Result};
 use crate::ollama::OllamaClient;
-use std::path::PathBuf;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the configuration file looked up in the current directory
+pub const DEFAULT_CONFIG_FILE: &str = "projets-indexer.toml";
 
 /// Configuration for the project indexer
 ///
@@ -24,13 +31,14 @@ use std::path::PathBuf;
 ///     true,
 /// );
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct IndexerConfig {
     /// Directory containing projects to index
     ///
     /// This is the root directory that will be scanned for projects.
     /// The indexer will look for git repositories and project directories
     /// within this path.
+    #[serde(default = "default_projects_dir")]
     pub projects_dir: PathBuf,
 
     /// Output file for the index
@@ -38,6 +46,7 @@ pub struct IndexerConfig {
     /// The path where the generated project index will be saved.
     /// This should be a JSON file that can be used by other tools
     /// to access project metadata.
+    #[serde(default = "default_index_file")]
     pub index_file: PathBuf,
 
     /// Whether to enable Ollama for tag generation
@@ -47,16 +56,34 @@ pub struct IndexerConfig {
     /// - Ollama to be installed and running
     /// - Internet connectivity for API calls
     /// - Sufficient system resources for AI processing
+    #[serde(default)]
     pub enable_ollama: bool,
 
+    /// Display names for categories
+    ///
+    /// Maps a category as derived from the directory layout (e.g. `ml`) to
+    /// the name stored in the index (e.g. `Machine Learning`). Categories
+    /// without an entry are kept as they are.
+    #[serde(default)]
+    pub category_aliases: HashMap<String, String>,
+
     /// Optional Ollama client
     ///
     /// The client instance used to communicate with the Ollama API.
     /// This is initialized when `enable_ollama` is true and can be
     /// used to generate project tags.
+    #[serde(skip)]
     pub ollama_client: Option<OllamaClient>,
 }
 
+fn default_projects_dir() -> PathBuf {
+    PathBuf::from("~/projects")
+}
+
+fn default_index_file() -> PathBuf {
+    PathBuf::from("projects_index.json")
+}
+
 impl IndexerConfig {
     /// Create a new default configuration
     ///
@@ -87,7 +114,139 @@ impl IndexerConfig {
             projects_dir,
             index_file,
             enable_ollama,
+            category_aliases: HashMap::new(),
             ollama_client: None,
         }
     }
+
+    /// Load a configuration from a TOML file
+    ///
+    /// Path fields may reference environment variables as `${VAR}` and start
+    /// with `~` for the home directory; both are expanded when loading, so a
+    /// single committed config works across machines.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the configuration file
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be read or parsed, or if a path references an
+    /// environment variable that isn't set.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        let invalid = |message: String| AppError::InvalidConfig {
+            path: path.to_path_buf(),
+            message,
+        };
+        let mut config: Self = toml::from_str(&contents).map_err(|e| invalid(e.to_string()))?;
+        config.projects_dir = expand_path(&config.projects_dir)?;
+        config.index_file = expand_path(&config.index_file)?;
+        Ok(config)
+    }
+}
+
+/// Expand `~` and `${VAR}` references in a path using the environment
+///
+/// # Errors
+///
+/// Returns a validation error if a referenced variable is not set or a
+/// `${` is left unterminated.
+pub fn expand_path(path: &Path) -> Result<PathBuf> {
+    expand_path_with(path, |name| std::env::var(name).ok())
+}
+
+fn expand_path_with(path: &Path, lookup: impl Fn(&str) -> Option<String>) -> Result<PathBuf> {
+    let raw = path.to_string_lossy();
+    let mut expanded = String::with_capacity(raw.len());
+    let mut rest: &str = &raw;
+
+    if rest == "~" || rest.starts_with("~/") {
+        let home = lookup("HOME").ok_or_else(|| {
+            OllamaError::ValidationError(format!("Cannot expand '~' in {}: HOME is not set", raw))
+        })?;
+        expanded.push_str(&home);
+        rest = &rest[1..];
+    }
+
+    while let Some(start) = rest.find("${") {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after.find('}').ok_or_else(|| {
+            OllamaError::ValidationError(format!("Unterminated '${{' in path {}", raw))
+        })?;
+        let name = &after[..end];
+        let value = lookup(name).ok_or_else(|| {
+            OllamaError::ValidationError(format!(
+                "Environment variable {} used in path {} is not set",
+                name, raw
+            ))
+        })?;
+        expanded.push_str(&value);
+        rest = &after[end + 1..];
+    }
+    expanded.push_str(rest);
+
+    Ok(PathBuf::from(expanded))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "HOME" => Some("/home/me".to_string()),
+            "CODE" => Some("/srv/code".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_from_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join(DEFAULT_CONFIG_FILE);
+        fs::write(
+            &path,
+            "projects_dir = \"/srv/code\"\nenable_ollama = true\n\n[category_aliases]\nml = \"Machine Learning\"\n",
+        )
+        .unwrap();
+
+        let config = IndexerConfig::from_file(&path).unwrap();
+        assert_eq!(config.projects_dir, PathBuf::from("/srv/code"));
+        assert_eq!(config.index_file, PathBuf::from("projects_index.json"));
+        assert!(config.enable_ollama);
+        assert_eq!(config.category_aliases["ml"], "Machine Learning");
+
+        // Any valid TOML is accepted, e.g. literal strings and dotted keys
+        fs::write(
+            &path,
+            "projects_dir = '/srv/code' # shared checkout\ncategory_aliases.ml = \"\"\"Machine Learning\"\"\"\n",
+        )
+        .unwrap();
+        let config = IndexerConfig::from_file(&path).unwrap();
+        assert_eq!(config.projects_dir, PathBuf::from("/srv/code"));
+        assert_eq!(config.category_aliases["ml"], "Machine Learning");
+
+        fs::write(&path, "enable_ollama = \"yes\"\n").unwrap();
+        assert!(matches!(
+            IndexerConfig::from_file(&path),
+            Err(AppError::InvalidConfig { .. })
+        ));
+    }
+
+    #[test]
+    fn test_expand_path() {
+        let expand = |p: &str| expand_path_with(Path::new(p), lookup).unwrap();
+
+        assert_eq!(expand("~/projects"), PathBuf::from("/home/me/projects"));
+        assert_eq!(
+            expand("${CODE}/rust/${HOME}"),
+            PathBuf::from("/srv/code/rust//home/me")
+        );
+        assert_eq!(expand("plain/path"), PathBuf::from("plain/path"));
+        assert_eq!(expand("not~/expanded"), PathBuf::from("not~/expanded"));
+        assert!(expand_path_with(Path::new("${MISSING}/x"), lookup).is_err());
+        assert!(expand_path_with(Path::new("${CODE"), lookup).is_err());
+    }
 }
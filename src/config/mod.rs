@@ -1,3 +1,3 @@
 pub mod indexer_config;
 
-pub use indexer_config::IndexerConfig;
+pub use indexer_config::{IndexerConfig, DEFAULT_CONFIG_FILE};
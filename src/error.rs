@@ -17,6 +17,67 @@ pub enum AppError {
     /// JSON error
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
+    /// YAML serialization error
+    #[error("YAML error: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+    /// TOML serialization error
+    #[error("TOML error: {0}")]
+    Toml(#[from] toml::ser::Error),
+    /// Malformed index file, reported by strict loading
+    #[error("Invalid index {}: {message} at byte {offset} (line {line}, column {column})", path.display())]
+    InvalidIndex {
+        /// Path of the index file
+        path: std::path::PathBuf,
+        /// What is wrong with the index
+        message: String,
+        /// Byte offset of the error in the file
+        offset: usize,
+        /// Line of the error, starting at 1
+        line: usize,
+        /// Column of the error, starting at 1
+        column: usize,
+    },
+    /// Index in a format that is only written, never read
+    #[error("Cannot read index {}: {format} indexes are export-only", path.display())]
+    UnreadableIndex {
+        /// Path of the index file
+        path: std::path::PathBuf,
+        /// Name of the index format
+        format: &'static str,
+    },
+    /// Malformed configuration file
+    #[error("Invalid configuration {}: {message}", path.display())]
+    InvalidConfig {
+        /// Path of the configuration file
+        path: std::path::PathBuf,
+        /// What is wrong with the configuration, and where
+        message: String,
+    },
+    /// HTTP server error
+    #[cfg(feature = "server")]
+    #[error("Server error: {0}")]
+    Server(#[from] hyper::Error),
+}
+
+impl AppError {
+    /// Short machine-readable name of the error's kind
+    ///
+    /// Used in JSON error output; Ollama errors report the kind of the
+    /// underlying [`OllamaError`].
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AppError::Ollama(e) => e.kind(),
+            AppError::Io(_) => "io",
+            AppError::Json(_) => "json",
+            AppError::Yaml(_) => "yaml",
+            AppError::Toml(_) => "toml",
+            AppError::InvalidIndex { .. } => "invalid_index",
+            AppError::UnreadableIndex { .. } => "unreadable_index",
+            AppError::InvalidConfig { .. } => "invalid_config",
+            #[cfg(feature = "server")]
+            AppError::Server(_) => "server",
+        }
+    }
 }
 
 /// Custom error type for the project indexer
@@ -110,6 +171,29 @@ pub enum OllamaError {
     ParseError(String),
 }
 
+impl OllamaError {
+    /// Short machine-readable name of the error's kind
+    ///
+    /// Variants that describe the same failure share a kind, e.g. both
+    /// `Connection` and `ConnectionError` are `connection`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            OllamaError::RequestError(_) => "request",
+            OllamaError::UrlError(_) => "url",
+            OllamaError::JsonError(_) => "json",
+            OllamaError::ApiError { .. } => "api",
+            OllamaError::ValidationError(_) => "validation",
+            OllamaError::IoError(_) | OllamaError::WalkdirError(_) => "io",
+            OllamaError::TracingError(_) => "tracing",
+            OllamaError::JoinError(_) => "task",
+            OllamaError::Connection(_) | OllamaError::ConnectionError(_) => "connection",
+            OllamaError::Generation(_) | OllamaError::GenerationError(_) => "generation",
+            OllamaError::Parse(_) | OllamaError::ParseError(_) => "parse",
+            OllamaError::Setup(_) => "setup",
+        }
+    }
+}
+
 /// Type alias for Result using OllamaError
 ///
 /// This type alias simplifies error handling by using our custom error type
@@ -126,3 +210,20 @@ pub enum OllamaError {
 /// }
 /// ```
 pub type Result<T> = std::result::Result<T, AppError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_kind() {
+        let error: AppError = OllamaError::ConnectionError("refused".to_string()).into();
+        assert_eq!(error.kind(), "connection");
+        assert_eq!(
+            AppError::Ollama(OllamaError::Connection(String::new())).kind(),
+            "connection"
+        );
+        let error: AppError = std::io::Error::other("disk full").into();
+        assert_eq!(error.kind(), "io");
+    }
+}
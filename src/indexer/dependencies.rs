@@ -0,0 +1,181 @@
+//! Dependency collection
+//!
+//! This module reads the dependencies a project declares in the build
+//! manifest at its root: the `[dependencies]` of a `Cargo.toml`, the
+//! `dependencies` of a `package.json`, or the requirements listed in a
+//! `requirements.txt`. Only direct dependencies are recorded, by name.
+
+use std::{collections::BTreeSet, fs, path::Path};
+
+/// Parser extracting dependency names from a manifest's contents
+type ManifestParser = fn(&str) -> Option<Vec<String>>;
+
+/// Manifests dependencies are read from, in order of preference
+const DEPENDENCY_MANIFESTS: &[(&str, ManifestParser)] = &[
+    ("Cargo.toml", parse_cargo_dependencies),
+    ("package.json", parse_package_json_dependencies),
+    ("requirements.txt", parse_requirements),
+];
+
+/// Read the dependencies declared by the manifest at a project's root
+///
+/// The first manifest found is used. Dependencies are returned sorted and
+/// without duplicates. A project without a supported manifest, or whose
+/// manifest can't be read or parsed, has no dependencies.
+pub fn collect_dependencies(path: &Path) -> Vec<String> {
+    for (manifest, parse) in DEPENDENCY_MANIFESTS {
+        let manifest_path = path.join(manifest);
+        if !manifest_path.is_file() {
+            continue;
+        }
+        let parsed = fs::read_to_string(&manifest_path)
+            .ok()
+            .and_then(|contents| parse(&contents));
+        return match parsed {
+            Some(dependencies) => dependencies,
+            None => {
+                tracing::warn!("Failed to read dependencies from {:?}", manifest_path);
+                Vec::new()
+            }
+        };
+    }
+    Vec::new()
+}
+
+/// Names of the crates in the `[dependencies]` table of a `Cargo.toml`
+///
+/// Both `name = ...` entries and `[dependencies.name]` tables are read.
+/// Dev and build dependencies are left out.
+fn parse_cargo_dependencies(contents: &str) -> Option<Vec<String>> {
+    let mut dependencies = BTreeSet::new();
+    let mut in_dependencies = false;
+
+    for line in contents.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if let Some(header) = line.strip_prefix('[') {
+            let header = header.trim_end_matches(']').trim();
+            in_dependencies = header == "dependencies";
+            if let Some(name) = header.strip_prefix("dependencies.") {
+                dependencies.insert(name.trim_matches('"').to_string());
+            }
+            continue;
+        }
+        if !in_dependencies {
+            continue;
+        }
+        // `name = "1.0"`, `name = { ... }` or `name.workspace = true`
+        if let Some((key, _)) = line.split_once('=') {
+            let name = key.split('.').next().unwrap_or_default();
+            let name = name.trim().trim_matches('"');
+            if !name.is_empty() {
+                dependencies.insert(name.to_string());
+            }
+        }
+    }
+
+    Some(dependencies.into_iter().collect())
+}
+
+/// Names of the packages in the `dependencies` of a `package.json`
+///
+/// Returns `None` when the file isn't valid JSON.
+fn parse_package_json_dependencies(contents: &str) -> Option<Vec<String>> {
+    let manifest: serde_json::Value = serde_json::from_str(contents).ok()?;
+    let mut dependencies: Vec<String> = manifest
+        .get("dependencies")
+        .and_then(|dependencies| dependencies.as_object())
+        .map(|dependencies| dependencies.keys().cloned().collect())
+        .unwrap_or_default();
+    dependencies.sort();
+    Some(dependencies)
+}
+
+/// Names of the packages listed in a `requirements.txt`
+///
+/// Version specifiers, extras and environment markers are dropped, and pip
+/// options such as `-r other.txt` are skipped.
+fn parse_requirements(contents: &str) -> Option<Vec<String>> {
+    let dependencies: BTreeSet<String> = contents
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('-'))
+        .filter_map(|line| {
+            let name = line
+                .split(|c: char| !(c.is_alphanumeric() || matches!(c, '-' | '_' | '.')))
+                .next()?;
+            (!name.is_empty()).then(|| name.to_lowercase())
+        })
+        .collect();
+    Some(dependencies.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_parse_cargo_dependencies() {
+        let manifest = r#"
+[package]
+name = "tool"
+version = "0.1.0"
+
+[dependencies]
+serde = { version = "1.0", features = ["derive"] } # serialization
+tokio.workspace = true
+"reqwest" = "0.11"
+
+[dependencies.clap]
+version = "4"
+
+[dev-dependencies]
+tempfile = "3"
+"#;
+        assert_eq!(
+            parse_cargo_dependencies(manifest),
+            Some(vec![
+                "clap".to_string(),
+                "reqwest".to_string(),
+                "serde".to_string(),
+                "tokio".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_requirements() {
+        let requirements = "\
+# web
+Flask>=2.0
+requests[socks] ==2.31 ; python_version > '3.8'
+-r dev.txt
+numpy
+";
+        assert_eq!(
+            parse_requirements(requirements),
+            Some(vec![
+                "flask".to_string(),
+                "numpy".to_string(),
+                "requests".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_collect_dependencies() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path();
+        assert!(collect_dependencies(path).is_empty());
+
+        fs::write(
+            path.join("package.json"),
+            r#"{"dependencies": {"react": "^18", "axios": "1"}, "devDependencies": {"jest": "29"}}"#,
+        )
+        .unwrap();
+        assert_eq!(collect_dependencies(path), vec!["axios", "react"]);
+
+        fs::write(path.join("package.json"), "{ not json").unwrap();
+        assert!(collect_dependencies(path).is_empty());
+    }
+}
@@ -0,0 +1,103 @@
+//! Project depth detection
+//!
+//! Projects usually sit at the same depth below the projects directory, e.g.
+//! `~/projects/<category>/<project>`, but that depth varies between users.
+//! This module probes the directory tree for project markers (a `.git`
+//! directory or a known build manifest) to find the depth most projects are
+//! at, so it doesn't have to be configured by hand.
+
+use super::{languages::detect_manifest_language, project_indexer::is_excluded_dir};
+use std::{collections::BTreeMap, path::Path};
+use walkdir::WalkDir;
+
+/// Deepest level probed for projects
+pub const MAX_PROBE_DEPTH: usize = 6;
+
+/// Maximum number of directories visited while probing
+const MAX_PROBED_DIRS: usize = 10_000;
+
+/// Find the depth below `root` at which most projects are found
+///
+/// Directories named in `exclude_dirs` are skipped, and the probe doesn't
+/// descend into projects it has found. At most [`MAX_PROBED_DIRS`]
+/// directories are visited, so very large trees are only sampled. Ties go to
+/// the shallower depth. Returns `None` when no project is found within
+/// `max_depth` levels.
+pub fn detect_project_depth(
+    root: &Path,
+    exclude_dirs: &[String],
+    max_depth: usize,
+) -> Option<usize> {
+    let mut projects_by_depth: BTreeMap<usize, usize> = BTreeMap::new();
+    let mut visited = 0;
+
+    let mut walker = WalkDir::new(root)
+        .min_depth(1)
+        .max_depth(max_depth)
+        .into_iter();
+    while let Some(entry) = walker.next() {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        if !entry.file_type().is_dir() {
+            continue;
+        }
+        if is_excluded_dir(&entry, exclude_dirs) {
+            walker.skip_current_dir();
+            continue;
+        }
+
+        visited += 1;
+        if visited > MAX_PROBED_DIRS {
+            break;
+        }
+        if is_project_root(entry.path()) {
+            *projects_by_depth.entry(entry.depth()).or_insert(0) += 1;
+            walker.skip_current_dir();
+        }
+    }
+
+    projects_by_depth
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+        .map(|(depth, _)| depth)
+}
+
+/// Check whether a directory looks like the root of a project
+fn is_project_root(path: &Path) -> bool {
+    path.join(".git").exists() || detect_manifest_language(path).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_detect_project_depth() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        for project in ["work/rust/a", "work/rust/b", "home/go/c", "misc"] {
+            fs::create_dir_all(root.join(project).join(".git")).unwrap();
+        }
+        // Nested packages inside a project don't count
+        fs::create_dir_all(root.join("work/rust/a/crates/x")).unwrap();
+        fs::write(root.join("work/rust/a/crates/x/Cargo.toml"), "").unwrap();
+        fs::create_dir_all(root.join("work/node_modules/dep")).unwrap();
+        fs::write(root.join("work/node_modules/dep/package.json"), "").unwrap();
+        let exclude = vec![".git".to_string(), "node_modules".to_string()];
+
+        assert_eq!(
+            detect_project_depth(root, &exclude, MAX_PROBE_DEPTH),
+            Some(3)
+        );
+        assert_eq!(detect_project_depth(root, &exclude, 2), Some(1));
+
+        let empty = tempdir().unwrap();
+        assert_eq!(
+            detect_project_depth(empty.path(), &exclude, MAX_PROBE_DEPTH),
+            None
+        );
+    }
+}
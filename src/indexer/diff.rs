@@ -0,0 +1,160 @@
+//! Index comparison
+//!
+//! This module backs the `diff` command. It compares two versions of an
+//! index, matching projects by path, and reports the projects that were
+//! added or removed and those whose name, category, status or tags changed.
+
+use crate::models::{Project, ProjectStatus};
+use serde::Serialize;
+use std::{collections::HashMap, path::PathBuf};
+
+/// Differences between two indexes
+#[derive(Debug, Default, Serialize)]
+pub struct IndexDiff {
+    /// Projects only in the new index
+    pub added: Vec<Project>,
+    /// Projects only in the old index
+    pub removed: Vec<Project>,
+    /// Projects in both indexes whose metadata changed
+    pub changed: Vec<ProjectChange>,
+}
+
+impl IndexDiff {
+    /// Whether the two indexes hold the same projects with the same metadata
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// How a project present in both indexes changed
+///
+/// Fields that didn't change are `None` or empty.
+#[derive(Debug, Serialize)]
+pub struct ProjectChange {
+    /// Path identifying the project in both indexes
+    pub path: PathBuf,
+    /// Name of the project in the new index
+    pub name: String,
+    /// Old and new name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub renamed: Option<(String, String)>,
+    /// Old and new category
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<(String, String)>,
+    /// Old and new status
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<(ProjectStatus, ProjectStatus)>,
+    /// Tags only in the new index
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub added_tags: Vec<String>,
+    /// Tags only in the old index
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub removed_tags: Vec<String>,
+}
+
+impl ProjectChange {
+    /// Compare two versions of a project, returning `None` if nothing changed
+    ///
+    /// Tags are compared as sets, so reordering them is not a change.
+    pub fn between(old: &Project, new: &Project) -> Option<Self> {
+        let changed = |old: &String, new: &String| (old != new).then(|| (old.clone(), new.clone()));
+        let change = Self {
+            path: new.path.clone(),
+            name: new.name.clone(),
+            renamed: changed(&old.name, &new.name),
+            category: changed(&old.category, &new.category),
+            status: (old.status != new.status).then(|| (old.status.clone(), new.status.clone())),
+            added_tags: new
+                .tags
+                .iter()
+                .filter(|tag| !old.tags.contains(tag))
+                .cloned()
+                .collect(),
+            removed_tags: old
+                .tags
+                .iter()
+                .filter(|tag| !new.tags.contains(tag))
+                .cloned()
+                .collect(),
+        };
+
+        let unchanged = change.renamed.is_none()
+            && change.category.is_none()
+            && change.status.is_none()
+            && change.added_tags.is_empty()
+            && change.removed_tags.is_empty();
+        (!unchanged).then_some(change)
+    }
+}
+
+/// Compare two indexes, matching projects by path
+///
+/// Each list of the result follows the order of the index it comes from.
+pub fn diff_indexes(old: &[Project], new: &[Project]) -> IndexDiff {
+    let old_by_path: HashMap<&PathBuf, &Project> =
+        old.iter().map(|project| (&project.path, project)).collect();
+    let new_by_path: HashMap<&PathBuf, &Project> =
+        new.iter().map(|project| (&project.path, project)).collect();
+
+    let mut diff = IndexDiff::default();
+    for project in new {
+        match old_by_path.get(&project.path) {
+            Some(old_project) => diff
+                .changed
+                .extend(ProjectChange::between(old_project, project)),
+            None => diff.added.push(project.clone()),
+        }
+    }
+    diff.removed = old
+        .iter()
+        .filter(|project| !new_by_path.contains_key(&project.path))
+        .cloned()
+        .collect();
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(name: &str, tags: &[&str]) -> Project {
+        let mut project = Project::new(name.to_string(), PathBuf::from(format!("/p/{}", name)));
+        project.tags = tags.iter().map(|t| t.to_string()).collect();
+        project
+    }
+
+    #[test]
+    fn test_diff_indexes() {
+        let old = vec![
+            project("kept", &["rust", "cli"]),
+            project("retagged", &["rust", "web"]),
+            project("gone", &[]),
+        ];
+        let mut archived = project("retagged", &["web", "async"]);
+        archived.status = ProjectStatus::Archived;
+        let new = vec![
+            project("kept", &["cli", "rust"]),
+            archived,
+            project("fresh", &["go"]),
+        ];
+
+        let diff = diff_indexes(&old, &new);
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].name, "fresh");
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].name, "gone");
+        assert_eq!(diff.changed.len(), 1);
+
+        let change = &diff.changed[0];
+        assert_eq!(change.name, "retagged");
+        assert_eq!(
+            change.status,
+            Some((ProjectStatus::Unknown, ProjectStatus::Archived))
+        );
+        assert_eq!(change.added_tags, vec!["async"]);
+        assert_eq!(change.removed_tags, vec!["rust"]);
+        assert!(change.renamed.is_none());
+
+        assert!(diff_indexes(&old, &old).is_empty());
+    }
+}
@@ -0,0 +1,257 @@
+//! Embedding cache
+//!
+//! Computing embeddings is expensive, and a project whose content hasn't
+//! changed doesn't need a new one. This module provides [`EmbeddingCache`], a
+//! bounded least-recently-used cache of embedding vectors keyed by the model
+//! and a content id, which can be saved next to the index and reloaded on the
+//! next run.
+
+use crate::{error::Result, ollama::OllamaClient};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Mutex, MutexGuard},
+};
+
+/// Number of embeddings kept by default
+pub const DEFAULT_EMBEDDING_CACHE_CAPACITY: usize = 4096;
+
+/// Path of the embedding cache belonging to an index file
+pub fn embedding_cache_path(index_file: &Path) -> PathBuf {
+    let mut path = index_file.as_os_str().to_owned();
+    path.push(".embeddings.json");
+    PathBuf::from(path)
+}
+
+/// Compute a stable content id for a piece of text
+///
+/// This is a 64-bit FNV-1a hash, which stays the same across runs and Rust
+/// versions, so it can be used as a persistent cache key.
+pub fn content_id(text: &str) -> String {
+    let hash = text.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
+/// Cache key of the embedding of `text` computed by `model`
+///
+/// Embeddings of different models can't be compared, so switching models
+/// misses the entries of the previous one.
+pub fn cache_key(model: &str, text: &str) -> String {
+    format!("{}:{}", model, content_id(text))
+}
+
+/// A cached embedding, as stored on disk
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    content_id: String,
+    embedding: Vec<f32>,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    /// Embeddings along with the tick they were last used at
+    entries: HashMap<String, (Vec<f32>, u64)>,
+    tick: u64,
+}
+
+/// A bounded cache of embeddings, evicting the least recently used entry
+///
+/// The cache can be shared between tasks: all methods take `&self`.
+#[derive(Debug)]
+pub struct EmbeddingCache {
+    capacity: usize,
+    state: Mutex<CacheState>,
+}
+
+impl EmbeddingCache {
+    /// Create an empty cache holding at most `capacity` embeddings
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    /// Load a cache saved with [`save`](Self::save)
+    ///
+    /// A missing file yields an empty cache. When the file holds more than
+    /// `capacity` embeddings, only the most recently used ones are kept.
+    pub fn load(path: &Path, capacity: usize) -> Result<Self> {
+        let cache = Self::new(capacity);
+        if path.exists() {
+            let entries: Vec<CacheEntry> = serde_json::from_str(&fs::read_to_string(path)?)?;
+            for entry in entries {
+                cache.insert(entry.content_id, entry.embedding);
+            }
+        }
+        Ok(cache)
+    }
+
+    /// Write the cache to disk, least recently used entries first
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let state = self.lock();
+        let mut entries: Vec<(&String, &(Vec<f32>, u64))> = state.entries.iter().collect();
+        entries.sort_by_key(|(_, (_, used))| *used);
+        let entries: Vec<CacheEntry> = entries
+            .into_iter()
+            .map(|(content_id, (embedding, _))| CacheEntry {
+                content_id: content_id.clone(),
+                embedding: embedding.clone(),
+            })
+            .collect();
+        fs::write(path, serde_json::to_string(&entries)?)?;
+        Ok(())
+    }
+
+    /// Look up the embedding for a content id, marking it as recently used
+    pub fn get(&self, content_id: &str) -> Option<Vec<f32>> {
+        let mut state = self.lock();
+        state.tick += 1;
+        let tick = state.tick;
+        state.entries.get_mut(content_id).map(|(embedding, used)| {
+            *used = tick;
+            embedding.clone()
+        })
+    }
+
+    /// Store the embedding for a content id, evicting the least recently used
+    /// entry if the cache is full
+    pub fn insert(&self, content_id: String, embedding: Vec<f32>) {
+        let mut state = self.lock();
+        state.tick += 1;
+        let tick = state.tick;
+        if !state.entries.contains_key(&content_id) && state.entries.len() >= self.capacity {
+            let oldest = state
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, used))| *used)
+                .map(|(content_id, _)| content_id.clone());
+            if let Some(oldest) = oldest {
+                state.entries.remove(&oldest);
+            }
+        }
+        state.entries.insert(content_id, (embedding, tick));
+    }
+
+    /// Return the cached embedding for `text`, computing it with `client` on
+    /// a miss
+    pub async fn get_or_embed(&self, client: &OllamaClient, text: &str) -> Result<Vec<f32>> {
+        let id = cache_key(client.model(), text);
+        if let Some(embedding) = self.get(&id) {
+            return Ok(embedding);
+        }
+        let embedding = client.create_embedding(text).await?;
+        self.insert(id, embedding.clone());
+        Ok(embedding)
+    }
+
+    /// Return the cached embeddings for `texts`, computing the missing ones
+    /// with `client` in a single batch
+    ///
+    /// Embeddings are returned in the order of `texts`.
+    pub async fn get_or_embed_batch(
+        &self,
+        client: &OllamaClient,
+        texts: &[String],
+    ) -> Result<Vec<Vec<f32>>> {
+        let ids: Vec<String> = texts
+            .iter()
+            .map(|text| cache_key(client.model(), text))
+            .collect();
+        let mut embeddings: Vec<Option<Vec<f32>>> = ids.iter().map(|id| self.get(id)).collect();
+
+        let missing: Vec<usize> = (0..texts.len())
+            .filter(|&i| embeddings[i].is_none())
+            .collect();
+        if !missing.is_empty() {
+            let inputs: Vec<String> = missing.iter().map(|&i| texts[i].clone()).collect();
+            let computed = client.embed_batch(client.model(), &inputs).await?;
+            for (i, embedding) in missing.into_iter().zip(computed) {
+                self.insert(ids[i].clone(), embedding.clone());
+                embeddings[i] = Some(embedding);
+            }
+        }
+        Ok(embeddings.into_iter().flatten().collect())
+    }
+
+    /// Number of cached embeddings
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    /// Whether the cache holds no embeddings
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> MutexGuard<'_, CacheState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_embedding_cache_evicts_least_recently_used() {
+        let cache = EmbeddingCache::new(2);
+        cache.insert("a".to_string(), vec![1.0]);
+        cache.insert("b".to_string(), vec![2.0]);
+        assert_eq!(cache.get("a"), Some(vec![1.0]));
+
+        cache.insert("c".to_string(), vec![3.0]);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.get("a"), Some(vec![1.0]));
+        assert_eq!(cache.get("c"), Some(vec![3.0]));
+    }
+
+    #[test]
+    fn test_embedding_cache_roundtrip() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("embeddings.json");
+        assert!(EmbeddingCache::load(&path, 2).unwrap().is_empty());
+
+        let cache = EmbeddingCache::new(3);
+        cache.insert("a".to_string(), vec![1.0]);
+        cache.insert("b".to_string(), vec![2.0]);
+        cache.insert("c".to_string(), vec![3.0]);
+        cache.get("a");
+        cache.save(&path).unwrap();
+
+        // Reloading into a smaller cache keeps the most recently used entries
+        let cache = EmbeddingCache::load(&path, 2).unwrap();
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.get("c"), Some(vec![3.0]));
+        assert_eq!(cache.get("a"), Some(vec![1.0]));
+    }
+
+    #[test]
+    fn test_content_id_is_stable() {
+        assert_eq!(content_id(""), "cbf29ce484222325");
+        assert_ne!(content_id("a"), content_id("b"));
+    }
+
+    #[test]
+    fn test_cache_key_includes_model() {
+        assert_eq!(
+            cache_key("nomic-embed-text", ""),
+            "nomic-embed-text:cbf29ce484222325"
+        );
+        assert_ne!(cache_key("llama3.2", "a"), cache_key("mistral", "a"));
+    }
+
+    #[test]
+    fn test_embedding_cache_path() {
+        assert_eq!(
+            embedding_cache_path(Path::new("/data/index.json")),
+            PathBuf::from("/data/index.json.embeddings.json")
+        );
+    }
+}
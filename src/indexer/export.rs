@@ -0,0 +1,251 @@
+//! Index export formats
+//!
+//! Besides JSON, an index can be written as YAML or TOML for tools that
+//! prefer those formats. Both are serialized from the same [`Project`]
+//! fields as JSON, and can be read back by the
+//! [`index_file`](super::index_file) module:
+//! - YAML: a sequence of project mappings
+//! - TOML: a `[[projects]]` table per project; fields without a value are
+//!   left out, since TOML has no null
+//!
+//! An index can also be rendered for people rather than tools: as a
+//! Markdown catalog, e.g. to be committed to a wiki (see [`to_markdown`]),
+//! or as CSV to be opened in a spreadsheet (see [`to_csv`]). Markdown and
+//! CSV leave out most fields, so they are export formats only.
+
+use crate::models::{Project, ProjectStatus};
+use std::{collections::BTreeMap, path::Path};
+
+/// Format an index is written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// JSON array, or one project per line for `.jsonl` paths
+    #[default]
+    Json,
+    /// YAML sequence of projects
+    Yaml,
+    /// TOML array of `projects` tables
+    Toml,
+    /// Markdown catalog grouped by category
+    Markdown,
+    /// CSV table with one row per project
+    Csv,
+}
+
+impl OutputFormat {
+    /// Format matching the extension of an index path
+    ///
+    /// Recognizes `.json`, `.jsonl`, `.yaml`, `.yml`, `.toml`, `.md`,
+    /// `.markdown` and `.csv`, ignoring case. Returns `None` for any other extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "json" | "jsonl" => Some(Self::Json),
+            "yaml" | "yml" => Some(Self::Yaml),
+            "toml" => Some(Self::Toml),
+            "md" | "markdown" => Some(Self::Markdown),
+            "csv" => Some(Self::Csv),
+            _ => None,
+        }
+    }
+
+    /// Format of an index, from `--format` or else the path's extension
+    ///
+    /// Falls back to JSON when neither names a format.
+    pub fn resolve(format: Option<Self>, path: &Path) -> Self {
+        format.or_else(|| Self::from_path(path)).unwrap_or_default()
+    }
+}
+
+/// Render projects as a Markdown catalog
+///
+/// Projects are grouped under a `##` heading per category, in alphabetical
+/// order. Each project gets a `###` heading and a table of its status, tags
+/// and path. Within a category, active projects come first, then stale,
+/// unknown and archived ones, each sorted by name.
+pub fn to_markdown(projects: &[Project]) -> String {
+    let mut categories: BTreeMap<&str, Vec<&Project>> = BTreeMap::new();
+    for project in projects {
+        categories
+            .entry(project.category.as_str())
+            .or_default()
+            .push(project);
+    }
+
+    let mut out = String::from("# Projects\n");
+    for (category, mut projects) in categories {
+        projects.sort_by(|a, b| {
+            status_rank(&a.status)
+                .cmp(&status_rank(&b.status))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        out.push_str(&format!("\n## {}\n", category));
+        for project in projects {
+            let tags = if project.tags.is_empty() {
+                "-".to_string()
+            } else {
+                project.tags.join(", ")
+            };
+            out.push_str(&format!(
+                "\n### {}\n\n| Status | Tags | Path |\n| --- | --- | --- |\n| {} | {} | `{}` |\n",
+                project.name,
+                format!("{:?}", project.status).to_lowercase(),
+                markdown_cell(&tags),
+                markdown_cell(&project.path.display().to_string().replace('`', "'")),
+            ));
+        }
+    }
+    out
+}
+
+/// Position of a status in the Markdown catalog, active projects first
+fn status_rank(status: &ProjectStatus) -> u8 {
+    match status {
+        ProjectStatus::Active => 0,
+        ProjectStatus::Stale => 1,
+        ProjectStatus::Unknown => 2,
+        ProjectStatus::Archived => 3,
+    }
+}
+
+/// Escape the characters that would break a Markdown table cell
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Columns of a CSV export, in order
+const CSV_COLUMNS: &[&str] = &[
+    "name",
+    "category",
+    "status",
+    "tags",
+    "path",
+    "last_modified",
+];
+
+/// Render projects as CSV, one row per project after a header row
+///
+/// Tags are joined with `;` so they stay in a single column. Fields are
+/// quoted as described in RFC 4180 when they hold a comma, a quote or a
+/// line break, and rows end with CRLF.
+pub fn to_csv(projects: &[Project]) -> String {
+    let mut out = format!("{}\r\n", CSV_COLUMNS.join(","));
+    for project in projects {
+        let fields = [
+            project.name.clone(),
+            project.category.clone(),
+            format!("{:?}", project.status).to_lowercase(),
+            project.tags.join(";"),
+            project.path.display().to_string(),
+            project.last_modified.to_rfc3339(),
+        ];
+        let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        out.push_str(&row.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// Quote a CSV field if needed, doubling the quotes it contains
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_output_format_resolve() {
+        let path = |name: &str| PathBuf::from(name);
+        assert_eq!(
+            OutputFormat::from_path(&path("i.YML")),
+            Some(OutputFormat::Yaml)
+        );
+        assert_eq!(
+            OutputFormat::from_path(&path("i.toml")),
+            Some(OutputFormat::Toml)
+        );
+        assert_eq!(
+            OutputFormat::from_path(&path("i.jsonl")),
+            Some(OutputFormat::Json)
+        );
+        assert_eq!(OutputFormat::from_path(&path("index")), None);
+        assert_eq!(
+            OutputFormat::resolve(None, &path("index.txt")),
+            OutputFormat::Json
+        );
+        assert_eq!(
+            OutputFormat::resolve(Some(OutputFormat::Toml), &path("index.yaml")),
+            OutputFormat::Toml
+        );
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        let project = |name: &str, category: &str, status: ProjectStatus, tags: &[&str]| {
+            let mut project = Project::new(name.to_string(), PathBuf::from(format!("/p/{}", name)));
+            project.category = category.to_string();
+            project.status = status;
+            project.tags = tags.iter().map(|tag| tag.to_string()).collect();
+            project
+        };
+        let projects = [
+            project("old|v1", "work", ProjectStatus::Archived, &[]),
+            project("web", "work", ProjectStatus::Active, &["ts", "a|b"]),
+            project("tool", "tools", ProjectStatus::Stale, &["rust"]),
+        ];
+        assert_eq!(
+            to_markdown(&projects),
+            "\
+# Projects
+
+## tools
+
+### tool
+
+| Status | Tags | Path |
+| --- | --- | --- |
+| stale | rust | `/p/tool` |
+
+## work
+
+### web
+
+| Status | Tags | Path |
+| --- | --- | --- |
+| active | ts, a\\|b | `/p/web` |
+
+### old|v1
+
+| Status | Tags | Path |
+| --- | --- | --- |
+| archived | - | `/p/old\\|v1` |
+"
+        );
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let mut project =
+            Project::new("Tools, misc".to_string(), PathBuf::from("/p/the \"tools\""));
+        project.category = "work".to_string();
+        project.status = ProjectStatus::Active;
+        project.tags = vec!["rust".to_string(), "cli".to_string()];
+        project.last_modified = chrono::DateTime::UNIX_EPOCH;
+        assert_eq!(
+            to_csv(&[project]),
+            "name,category,status,tags,path,last_modified\r\n\
+             \"Tools, misc\",work,active,rust;cli,\"/p/the \"\"tools\"\"\",1970-01-01T00:00:00+00:00\r\n"
+        );
+        assert_eq!(
+            to_csv(&[]),
+            "name,category,status,tags,path,last_modified\r\n"
+        );
+    }
+}
@@ -0,0 +1,135 @@
+//! Path glob patterns
+//!
+//! This module compiles shell-style glob patterns, such as `**/archive/**`,
+//! into matchers for whole paths. Supported syntax:
+//! - `*` matches any run of characters within a path component
+//! - `?` matches a single character within a path component
+//! - `**` matches any number of path components
+//! - `[abc]`, `[a-z]` and `[!abc]` match a character class
+//!
+//! Patterns are translated to regular expressions here rather than compiled
+//! with `globset`, which isn't a dependency. Matching follows globset with
+//! `literal_separator` enabled, except that `{a,b}` alternatives and
+//! backslash escapes are not supported.
+
+use crate::error::{OllamaError, Result};
+use regex::Regex;
+use std::path::Path;
+
+/// A compiled glob pattern matched against full paths
+#[derive(Debug, Clone)]
+pub struct PathGlob {
+    regex: Regex,
+}
+
+impl PathGlob {
+    /// Compile a glob pattern
+    ///
+    /// # Errors
+    ///
+    /// Returns a validation error if the pattern has an unterminated `[`.
+    pub fn new(pattern: &str) -> Result<Self> {
+        let regex = Regex::new(&glob_to_regex(pattern)?).map_err(|e| {
+            OllamaError::ValidationError(format!("Invalid glob '{}': {}", pattern, e))
+        })?;
+        Ok(Self { regex })
+    }
+
+    /// Check whether a path matches the pattern
+    ///
+    /// Path separators are normalized to `/` before matching.
+    pub fn is_match(&self, path: &Path) -> bool {
+        let path = path.to_string_lossy().replace('\\', "/");
+        self.regex.is_match(&path)
+    }
+}
+
+/// Translate a glob pattern into an anchored regular expression
+fn glob_to_regex(pattern: &str) -> Result<String> {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut regex = String::from("^");
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                let at_start = i == 0 || chars[i - 1] == '/';
+                if at_start && chars.get(i + 2) == Some(&'/') {
+                    // `**/` matches zero or more leading components
+                    regex.push_str("(?:.*/)?");
+                    i += 3;
+                } else if i > 0 && chars[i - 1] == '/' && i + 2 == chars.len() {
+                    // `/**` at the end also matches the directory itself
+                    regex.pop();
+                    regex.push_str("(?:/.*)?");
+                    i += 2;
+                } else {
+                    regex.push_str(".*");
+                    i += 2;
+                }
+            }
+            '*' => {
+                regex.push_str("[^/]*");
+                i += 1;
+            }
+            '?' => {
+                regex.push_str("[^/]");
+                i += 1;
+            }
+            '[' => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|&c| c == ']')
+                    .map(|offset| i + 1 + offset)
+                    .ok_or_else(|| {
+                        OllamaError::ValidationError(format!(
+                            "Unterminated '[' in glob '{}'",
+                            pattern
+                        ))
+                    })?;
+                let class: String = chars[i + 1..end].iter().collect();
+                let class = match class.strip_prefix('!') {
+                    Some(negated) => format!("^{}", negated),
+                    None => class,
+                };
+                regex.push('[');
+                regex.push_str(&class.replace('\\', "\\\\").replace('[', "\\["));
+                regex.push(']');
+                i = end + 1;
+            }
+            c => {
+                regex.push_str(&regex::escape(&c.to_string()));
+                i += 1;
+            }
+        }
+    }
+
+    regex.push('$');
+    Ok(regex)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_glob() {
+        let matches =
+            |pattern: &str, path: &str| PathGlob::new(pattern).unwrap().is_match(Path::new(path));
+
+        assert!(matches("**/archive/**", "/home/me/projects/archive"));
+        assert!(matches(
+            "**/archive/**",
+            "/home/me/projects/archive/old/app"
+        ));
+        assert!(!matches("**/archive/**", "/home/me/projects/archived"));
+        assert!(matches("work/*/tmp-*", "work/rust/tmp-1"));
+        assert!(!matches("work/*/tmp-*", "work/rust/nested/tmp-1"));
+        assert!(matches("**/v?", "/p/v2"));
+        assert!(matches("**/[!.]*", "/p/visible"));
+        assert!(!matches("**/[!.]*", "/p/.hidden"));
+        assert!(matches("/p/a.b", "/p/a.b"));
+        assert!(!matches("/p/a.b", "/p/axb"));
+        assert!(PathGlob::new("[abc").is_err());
+    }
+}
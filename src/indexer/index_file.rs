@@ -0,0 +1,555 @@
+//! Index file persistence
+//!
+//! This module contains helpers for reading and writing the JSON index
+//! produced by the project indexer, so that commands operating on an
+//! existing index share a single load/save path.
+//!
+//! Two layouts are supported, selected by the file extension:
+//! - `.jsonl`: one `Project` per line, which can be read incrementally
+//! - anything else: a pretty-printed JSON array
+//!
+//! Indexes exported as YAML or TOML (`.yaml`, `.yml`, `.toml`) can be read
+//! back as well, and are saved again in the same format. Markdown and CSV
+//! exports leave out most fields, so they can't be read.
+//!
+//! Strict loading reads the whole file up front and reports malformed
+//! content as [`AppError::InvalidIndex`], with the byte offset of the error.
+
+use super::export::{self, OutputFormat};
+use crate::{
+    error::{AppError, Result},
+    models::Project,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File},
+    io::{self, BufRead, BufReader, BufWriter, Lines, Write},
+    path::{Path, PathBuf},
+    vec,
+};
+
+/// Layout of a TOML index, which needs a table at the top level
+#[derive(Serialize, Deserialize)]
+struct TomlIndex<P> {
+    #[serde(default)]
+    projects: P,
+}
+
+/// Check whether an index path uses the line-delimited JSON layout
+pub fn is_jsonl(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.eq_ignore_ascii_case("jsonl"))
+        .unwrap_or(false)
+}
+
+/// Iterator over the projects stored in an index file
+///
+/// For `.jsonl` indexes projects are parsed one line at a time through a
+/// buffered reader, so only a single project is held in memory. JSON array
+/// indexes are loaded in full and then yielded one by one.
+pub struct ProjectStream {
+    inner: StreamInner,
+}
+
+enum StreamInner {
+    Lines(Lines<BufReader<File>>),
+    Loaded(vec::IntoIter<Project>),
+}
+
+impl Iterator for ProjectStream {
+    type Item = Result<Project>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.inner {
+            StreamInner::Lines(lines) => loop {
+                let line = match lines.next()? {
+                    Ok(line) => line,
+                    Err(e) => return Some(Err(e.into())),
+                };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                return Some(serde_json::from_str(&line).map_err(Into::into));
+            },
+            StreamInner::Loaded(projects) => projects.next().map(Ok),
+        }
+    }
+}
+
+/// Check that an index file exists before reading it
+///
+/// A missing index is reported as a not-found [`AppError::Io`] pointing to
+/// the `index` command, rather than the bare error from opening the file.
+pub fn require_index(path: &Path) -> Result<()> {
+    if path.exists() {
+        return Ok(());
+    }
+    Err(AppError::Io(io::Error::new(
+        io::ErrorKind::NotFound,
+        format!(
+            "index file {} not found; run `projets-indexer index` to create it",
+            path.display()
+        ),
+    )))
+}
+
+/// Open an index file for reading projects one at a time
+///
+/// # Arguments
+///
+/// * `path` - Path to the index file
+pub fn stream_index(path: &Path) -> Result<ProjectStream> {
+    if !matches!(
+        OutputFormat::from_path(path),
+        None | Some(OutputFormat::Json)
+    ) {
+        return stream_index_with(path, true);
+    }
+    let file = File::open(path)?;
+    let inner = if is_jsonl(path) {
+        StreamInner::Lines(BufReader::new(file).lines())
+    } else {
+        let projects: Vec<Project> = serde_json::from_reader(BufReader::new(file))?;
+        StreamInner::Loaded(projects.into_iter())
+    };
+    Ok(ProjectStream { inner })
+}
+
+/// Open an index file for reading projects, strictly or not
+///
+/// A strict stream is loaded with [`load_index_strict`] before yielding
+/// any project.
+pub fn stream_index_with(path: &Path, strict: bool) -> Result<ProjectStream> {
+    if !strict {
+        return stream_index(path);
+    }
+    Ok(ProjectStream {
+        inner: StreamInner::Loaded(load_index_strict(path)?.into_iter()),
+    })
+}
+
+/// Load a project index from disk, rejecting any malformed content
+///
+/// Unlike [`load_index`], blank lines in `.jsonl` indexes are an error, and
+/// parse errors become [`AppError::InvalidIndex`] with the byte offset,
+/// line and column at which they occurred in the file.
+///
+/// # Arguments
+///
+/// * `path` - Path to the index file
+pub fn load_index_strict(path: &Path) -> Result<Vec<Project>> {
+    let format = OutputFormat::from_path(path).unwrap_or_default();
+    let unreadable = |format| AppError::UnreadableIndex {
+        path: path.to_path_buf(),
+        format,
+    };
+    match format {
+        OutputFormat::Markdown => return Err(unreadable("Markdown")),
+        OutputFormat::Csv => return Err(unreadable("CSV")),
+        _ => {}
+    }
+
+    let contents = fs::read_to_string(path)?;
+    let invalid = |message: String, offset: usize| {
+        let before = &contents[..offset];
+        AppError::InvalidIndex {
+            path: path.to_path_buf(),
+            message,
+            offset,
+            line: before.matches('\n').count() + 1,
+            column: before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1,
+        }
+    };
+
+    match format {
+        OutputFormat::Yaml => {
+            return serde_yaml::from_str(&contents).map_err(|e| {
+                let offset = e.location().map_or(0, |location| location.index());
+                invalid(yaml_error_message(&e), offset)
+            });
+        }
+        OutputFormat::Toml => {
+            return toml::from_str(&contents)
+                .map(|index: TomlIndex<Vec<Project>>| index.projects)
+                .map_err(|e| {
+                    let offset = e.span().map_or(0, |span| span.start);
+                    invalid(e.message().to_string(), offset)
+                });
+        }
+        _ => {}
+    }
+
+    if !is_jsonl(path) {
+        return serde_json::from_str(&contents).map_err(|e| {
+            let offset = byte_offset(&contents, e.line(), e.column());
+            invalid(error_message(&e), offset)
+        });
+    }
+
+    let mut projects = Vec::new();
+    let mut line_start = 0;
+    for line in contents.split_inclusive('\n') {
+        let entry = line.trim_end_matches(['\n', '\r']);
+        if entry.trim().is_empty() {
+            return Err(invalid("blank line".to_string(), line_start));
+        }
+        let project = serde_json::from_str(entry).map_err(|e| {
+            let offset = line_start + byte_offset(entry, e.line(), e.column());
+            invalid(error_message(&e), offset)
+        })?;
+        projects.push(project);
+        line_start += line.len();
+    }
+    Ok(projects)
+}
+
+/// Byte offset of a 1-based line and column reported by serde_json
+///
+/// serde_json counts columns in bytes, and reports column 0 for errors at
+/// the start of a line.
+fn byte_offset(text: &str, line: usize, column: usize) -> usize {
+    let line_start: usize = text
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum();
+    (line_start + column.saturating_sub(1)).min(text.len())
+}
+
+/// Error message from serde_json without its position suffix
+fn error_message(error: &serde_json::Error) -> String {
+    let message = error.to_string();
+    let suffix = format!(" at line {} column {}", error.line(), error.column());
+    message
+        .strip_suffix(&suffix)
+        .map(str::to_string)
+        .unwrap_or(message)
+}
+
+/// Error message from serde_yaml without its position suffix
+fn yaml_error_message(error: &serde_yaml::Error) -> String {
+    let message = error.to_string();
+    let Some(location) = error.location() else {
+        return message;
+    };
+    let suffix = format!(" at line {} column {}", location.line(), location.column());
+    message
+        .strip_suffix(&suffix)
+        .map(str::to_string)
+        .unwrap_or(message)
+}
+
+/// Load a project index from disk
+///
+/// # Arguments
+///
+/// * `path` - Path to the index file
+pub fn load_index(path: &Path) -> Result<Vec<Project>> {
+    stream_index(path)?.collect()
+}
+
+/// Write a project index to disk
+///
+/// `.jsonl` paths get one compact project per line, and YAML and TOML paths
+/// are written in their format; any other path gets a pretty-printed JSON
+/// array.
+///
+/// # Arguments
+///
+/// * `path` - Path to the index file
+/// * `projects` - Projects to serialize
+pub fn save_index(path: &Path, projects: &[Project]) -> Result<()> {
+    match OutputFormat::from_path(path) {
+        Some(format @ (OutputFormat::Yaml | OutputFormat::Toml)) => {
+            save_index_as(path, projects, format)
+        }
+        _ => save_json(path, projects),
+    }
+}
+
+/// Write a project index to disk as JSON
+fn save_json(path: &Path, projects: &[Project]) -> Result<()> {
+    if is_jsonl(path) {
+        let mut writer = BufWriter::new(File::create(path)?);
+        for project in projects {
+            serde_json::to_writer(&mut writer, project)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()?;
+    } else {
+        let json = serde_json::to_string_pretty(projects)?;
+        fs::write(path, json)?;
+    }
+    Ok(())
+}
+
+/// Write a project index to disk in the given format
+///
+/// JSON indexes are written as described in [`save_index`], one project
+/// per line for `.jsonl` paths; the other formats are rendered by the
+/// [`export`](super::export) module.
+pub fn save_index_as(path: &Path, projects: &[Project], format: OutputFormat) -> Result<()> {
+    if format == OutputFormat::Json {
+        return save_json(path, projects);
+    }
+    fs::write(path, render_index(projects, format)?)?;
+    Ok(())
+}
+
+/// Render projects in the given format, JSON being a pretty-printed array
+pub fn render_index(projects: &[Project], format: OutputFormat) -> Result<String> {
+    Ok(match format {
+        OutputFormat::Json => serde_json::to_string_pretty(projects)? + "\n",
+        OutputFormat::Yaml => serde_yaml::to_string(projects)?,
+        OutputFormat::Toml => toml::to_string(&TomlIndex { projects })?,
+        OutputFormat::Markdown => export::to_markdown(projects),
+        OutputFormat::Csv => export::to_csv(projects),
+    })
+}
+
+/// Writer adding projects to an index file one at a time
+///
+/// Each project is serialized as soon as it is written, so an index can be
+/// written without holding all of its projects in memory. The file uses the
+/// same layout as [`save_index`]: one line per project for `.jsonl` paths,
+/// a pretty-printed JSON array otherwise. Call [`finish`](Self::finish) to
+/// complete the file.
+///
+/// Projects are written to a `{path}.partial` file next to the index, which
+/// replaces it on [`finish`](Self::finish). Until then the existing index
+/// stays readable, e.g. for the tags of the projects being re-indexed.
+pub struct IndexWriter {
+    writer: BufWriter<File>,
+    path: PathBuf,
+    partial_path: PathBuf,
+    jsonl: bool,
+    count: usize,
+}
+
+impl IndexWriter {
+    /// Start writing the index file at `path`
+    pub fn create(path: &Path) -> Result<Self> {
+        let jsonl = is_jsonl(path);
+        let mut partial_path = path.as_os_str().to_owned();
+        partial_path.push(".partial");
+        let partial_path = PathBuf::from(partial_path);
+        let mut writer = BufWriter::new(File::create(&partial_path)?);
+        if !jsonl {
+            writer.write_all(b"[")?;
+        }
+        Ok(Self {
+            writer,
+            path: path.to_path_buf(),
+            partial_path,
+            jsonl,
+            count: 0,
+        })
+    }
+
+    /// Append a project to the index
+    pub fn write(&mut self, project: &Project) -> Result<()> {
+        if self.jsonl {
+            serde_json::to_writer(&mut self.writer, project)?;
+            self.writer.write_all(b"\n")?;
+        } else {
+            // Indent the project as an element of a pretty-printed array
+            let json = serde_json::to_string_pretty(project)?;
+            let separator: &[u8] = if self.count == 0 { b"\n" } else { b",\n" };
+            self.writer.write_all(separator)?;
+            self.writer
+                .write_all(format!("  {}", json.replace('\n', "\n  ")).as_bytes())?;
+        }
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Number of projects written so far
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Complete the index file, replacing the previous one
+    pub fn finish(mut self) -> Result<()> {
+        if !self.jsonl {
+            let end: &[u8] = if self.count == 0 { b"]" } else { b"\n]" };
+            self.writer.write_all(end)?;
+        }
+        self.writer.flush()?;
+        fs::rename(&self.partial_path, &self.path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_jsonl_roundtrip() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("index.jsonl");
+        let projects = vec![
+            Project::new("a".to_string(), PathBuf::from("/p/a")),
+            Project::new("b".to_string(), PathBuf::from("/p/b")),
+        ];
+
+        save_index(&path, &projects).unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 2);
+
+        let names: Vec<String> = stream_index(&path)
+            .unwrap()
+            .map(|p| p.unwrap().name)
+            .collect();
+        assert_eq!(names, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_json_array_roundtrip() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("index.json");
+        let projects = vec![Project::new("a".to_string(), PathBuf::from("/p/a"))];
+
+        save_index(&path, &projects).unwrap();
+        let loaded = load_index(&path).unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].name, "a");
+    }
+
+    #[test]
+    fn test_yaml_and_toml_roundtrip() {
+        let temp_dir = tempdir().unwrap();
+        let mut tool = Project::new("tool \"x\"".to_string(), PathBuf::from("/p/tool"));
+        tool.tags = vec!["rust".to_string(), "cli".to_string()];
+        tool.file_types.insert("rs".to_string(), 3);
+        tool.last_commit_date = Some(chrono::Utc::now());
+        let projects = vec![
+            tool,
+            Project::new("web".to_string(), PathBuf::from("/p/web")),
+        ];
+
+        for name in ["index.yaml", "index.toml"] {
+            let path = temp_dir.path().join(name);
+            save_index(&path, &projects).unwrap();
+            let loaded = load_index(&path).unwrap();
+            assert_eq!(
+                serde_json::to_value(&loaded).unwrap(),
+                serde_json::to_value(&projects).unwrap(),
+                "{}",
+                name
+            );
+
+            save_index(&path, &[]).unwrap();
+            assert!(load_index(&path).unwrap().is_empty());
+        }
+
+        // Hand-edited indexes may use plain scalars and flow sequences
+        let path = temp_dir.path().join("index.yaml");
+        fs::write(
+            &path,
+            "- name: tool\n  path: /p/tool\n  tags: [rust, cli]\n",
+        )
+        .unwrap();
+        let loaded = load_index_strict(&path).unwrap();
+        assert_eq!((loaded[0].name.as_str(), loaded[0].tags.len()), ("tool", 2));
+
+        let path = temp_dir.path().join("index.toml");
+        fs::write(
+            &path,
+            "[[projects]]\nname = 'tool' # edited\npath = \"/p/tool\"\n",
+        )
+        .unwrap();
+        assert_eq!(load_index_strict(&path).unwrap()[0].name, "tool");
+
+        let path = temp_dir.path().join("index.yaml");
+        fs::write(&path, "- name: \"a\"\n  tags: rust\n").unwrap();
+        assert!(matches!(
+            load_index_strict(&path),
+            Err(AppError::InvalidIndex { line: 2, .. })
+        ));
+
+        let path = temp_dir.path().join("index.md");
+        save_index_as(&path, &projects, OutputFormat::Markdown).unwrap();
+        assert!(matches!(
+            load_index(&path),
+            Err(AppError::UnreadableIndex {
+                format: "Markdown",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_index_writer() {
+        let temp_dir = tempdir().unwrap();
+        let projects = vec![
+            Project::new("a".to_string(), PathBuf::from("/p/a")),
+            Project::new("b".to_string(), PathBuf::from("/p/b")),
+        ];
+
+        for name in ["index.json", "index.jsonl"] {
+            let path = temp_dir.path().join(name);
+            let mut writer = IndexWriter::create(&path).unwrap();
+            for project in &projects {
+                writer.write(project).unwrap();
+            }
+            assert_eq!(writer.count(), 2);
+            writer.finish().unwrap();
+
+            let streamed = fs::read_to_string(&path).unwrap();
+            save_index(&path, &projects).unwrap();
+            assert_eq!(streamed, fs::read_to_string(&path).unwrap());
+        }
+
+        let path = temp_dir.path().join("empty.json");
+        IndexWriter::create(&path).unwrap().finish().unwrap();
+        assert!(load_index(&path).unwrap().is_empty());
+
+        // The previous index stays in place until the new one is complete
+        let mut writer = IndexWriter::create(&path).unwrap();
+        for project in &projects {
+            writer.write(project).unwrap();
+        }
+        assert!(load_index(&path).unwrap().is_empty());
+        writer.finish().unwrap();
+        assert_eq!(load_index(&path).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_load_index_strict() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("index.jsonl");
+        let project = r#"{"name":"a","path":"/p/a"}"#;
+
+        fs::write(&path, format!("{project}\n{project}\n")).unwrap();
+        assert_eq!(load_index_strict(&path).unwrap().len(), 2);
+
+        fs::write(&path, format!("{project}\n\n{project}\n")).unwrap();
+        assert!(load_index(&path).is_ok());
+        match load_index_strict(&path).unwrap_err() {
+            AppError::InvalidIndex { offset, line, .. } => {
+                assert_eq!((offset, line), (project.len() + 1, 2))
+            }
+            e => panic!("unexpected error: {e}"),
+        }
+
+        fs::write(&path, format!("{project}\n{{\"name\":1}}\n")).unwrap();
+        match load_index_strict(&path).unwrap_err() {
+            AppError::InvalidIndex {
+                offset,
+                line,
+                column,
+                ..
+            } => assert_eq!((offset, line, column), (project.len() + 9, 2, 9)),
+            e => panic!("unexpected error: {e}"),
+        }
+
+        let path = temp_dir.path().join("index.json");
+        fs::write(&path, "[\n  {\"name\": \"a\", \"path\": }\n]").unwrap();
+        let error = load_index_strict(&path).unwrap_err();
+        assert!(matches!(error, AppError::InvalidIndex { line: 2, .. }));
+        assert!(error.to_string().contains("index.json"));
+    }
+}
@@ -0,0 +1,135 @@
+//! Resumable indexing runs
+//!
+//! Tagging thousands of projects can take hours. While indexing, the indexer
+//! keeps a ledger next to the index, `{index}.job.jsonl`, and appends each
+//! project to it as soon as it has been processed. If the run dies, the next
+//! run started with `resume` reuses the projects in the ledger instead of
+//! processing them again. The ledger is deleted once the index is written.
+//!
+//! Unlike the embedding cache, the ledger only lives for the length of a run
+//! and doesn't depend on the contents of the projects.
+
+use crate::{error::Result, models::Project};
+use std::{
+    collections::HashMap,
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+/// Path of the job ledger belonging to an index file
+pub fn job_ledger_path(index_file: &Path) -> PathBuf {
+    let mut path = index_file.as_os_str().to_owned();
+    path.push(".job.jsonl");
+    PathBuf::from(path)
+}
+
+/// Ledger of the projects processed during an indexing run
+#[derive(Debug)]
+pub struct JobLedger {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl JobLedger {
+    /// Open the ledger of an index file for a new run
+    ///
+    /// With `resume`, the projects recorded by an interrupted run are
+    /// returned by path and kept in the ledger; a line cut short when that
+    /// run died is ignored. Otherwise any previous ledger is discarded.
+    pub fn open(index_file: &Path, resume: bool) -> Result<(Self, HashMap<PathBuf, Project>)> {
+        let path = job_ledger_path(index_file);
+        let mut done = HashMap::new();
+        let mut partial_line = false;
+        if resume && path.exists() {
+            let contents = fs::read_to_string(&path)?;
+            for line in contents.lines() {
+                match serde_json::from_str::<Project>(line) {
+                    Ok(project) => {
+                        done.insert(project.path.clone(), project);
+                    }
+                    Err(e) => tracing::warn!("Ignoring unreadable job ledger entry: {}", e),
+                }
+            }
+            partial_line = !contents.is_empty() && !contents.ends_with('\n');
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(resume)
+            .truncate(!resume)
+            .open(&path)?;
+        if partial_line {
+            // Keep new entries off the line left unfinished
+            file.write_all(b"\n")?;
+        }
+        let ledger = Self {
+            path,
+            file: Mutex::new(file),
+        };
+        Ok((ledger, done))
+    }
+
+    /// Record a processed project
+    ///
+    /// The entry is flushed right away, so it survives the process dying.
+    pub fn record(&self, project: &Project) -> Result<()> {
+        let mut line = serde_json::to_string(project)?;
+        line.push('\n');
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        file.write_all(line.as_bytes())?;
+        file.flush()?;
+        Ok(())
+    }
+
+    /// Delete the ledger once the run has completed
+    pub fn finish(self) -> Result<()> {
+        drop(self.file);
+        fs::remove_file(&self.path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_job_ledger_resume() {
+        let temp_dir = tempdir().unwrap();
+        let index_file = temp_dir.path().join("index.json");
+        let path = job_ledger_path(&index_file);
+        assert_eq!(path, temp_dir.path().join("index.json.job.jsonl"));
+
+        let (ledger, done) = JobLedger::open(&index_file, true).unwrap();
+        assert!(done.is_empty());
+        ledger
+            .record(&Project::new("a".to_string(), PathBuf::from("/p/a")))
+            .unwrap();
+        drop(ledger);
+        // A run dying mid-write leaves a partial line behind
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(br#"{"name": "b", "pa"#).unwrap();
+
+        let (ledger, done) = JobLedger::open(&index_file, true).unwrap();
+        assert_eq!(done.len(), 1);
+        assert_eq!(done[Path::new("/p/a")].name, "a");
+        ledger
+            .record(&Project::new("c".to_string(), PathBuf::from("/p/c")))
+            .unwrap();
+        drop(ledger);
+
+        let (ledger, done) = JobLedger::open(&index_file, true).unwrap();
+        assert_eq!(done.len(), 2);
+        ledger.finish().unwrap();
+        assert!(!path.exists());
+
+        fs::write(&path, "{}\n").unwrap();
+        let (_, done) = JobLedger::open(&index_file, false).unwrap();
+        assert!(done.is_empty());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "");
+    }
+}
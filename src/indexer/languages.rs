@@ -0,0 +1,249 @@
+//! Language detection
+//!
+//! This module determines which programming language dominates a project by
+//! counting the lines of source files, grouped by file extension, or more
+//! cheaply from the build manifest at the project root.
+
+use super::project_indexer::is_excluded_dir;
+use std::{collections::HashMap, fs, path::Path};
+use walkdir::WalkDir;
+
+/// Source file extensions and the language they belong to
+const LANGUAGE_EXTENSIONS: &[(&str, &str)] = &[
+    ("rs", "rust"),
+    ("py", "python"),
+    ("js", "javascript"),
+    ("jsx", "javascript"),
+    ("mjs", "javascript"),
+    ("ts", "typescript"),
+    ("tsx", "typescript"),
+    ("go", "go"),
+    ("java", "java"),
+    ("kt", "kotlin"),
+    ("scala", "scala"),
+    ("c", "c"),
+    ("h", "c"),
+    ("cc", "c++"),
+    ("cpp", "c++"),
+    ("cxx", "c++"),
+    ("hpp", "c++"),
+    ("cs", "c#"),
+    ("rb", "ruby"),
+    ("php", "php"),
+    ("swift", "swift"),
+    ("dart", "dart"),
+    ("ex", "elixir"),
+    ("exs", "elixir"),
+    ("hs", "haskell"),
+    ("ml", "ocaml"),
+    ("lua", "lua"),
+    ("r", "r"),
+    ("jl", "julia"),
+    ("sh", "shell"),
+    ("bash", "shell"),
+    ("zig", "zig"),
+    ("vue", "vue"),
+    ("svelte", "svelte"),
+];
+
+/// Build manifests and the language they indicate, most specific first
+const MANIFEST_LANGUAGES: &[(&str, &str)] = &[
+    ("Cargo.toml", "rust"),
+    ("go.mod", "go"),
+    ("tsconfig.json", "typescript"),
+    ("package.json", "javascript"),
+    ("pyproject.toml", "python"),
+    ("setup.py", "python"),
+    ("requirements.txt", "python"),
+    ("build.gradle.kts", "kotlin"),
+    ("pom.xml", "java"),
+    ("build.gradle", "java"),
+    ("build.sbt", "scala"),
+    ("Gemfile", "ruby"),
+    ("composer.json", "php"),
+    ("Package.swift", "swift"),
+    ("pubspec.yaml", "dart"),
+    ("mix.exs", "elixir"),
+    ("stack.yaml", "haskell"),
+    ("dune-project", "ocaml"),
+    ("build.zig", "zig"),
+    ("CMakeLists.txt", "c++"),
+];
+
+/// Map a file extension to the language it is written in
+pub fn language_for_extension(extension: &str) -> Option<&'static str> {
+    let extension = extension.to_ascii_lowercase();
+    LANGUAGE_EXTENSIONS
+        .iter()
+        .find(|(ext, _)| *ext == extension)
+        .map(|(_, language)| *language)
+}
+
+/// Count lines of source code per language in a project
+///
+/// Directories whose name appears in `exclude_dirs` are skipped, so build
+/// output and vendored dependencies don't skew the result.
+pub fn count_lines_by_language(path: &Path, exclude_dirs: &[String]) -> HashMap<String, usize> {
+    let mut lines = HashMap::new();
+
+    for entry in WalkDir::new(path)
+        .into_iter()
+        .filter_entry(|e| !is_excluded_dir(e, exclude_dirs))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+    {
+        let Some(language) = entry
+            .path()
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(language_for_extension)
+        else {
+            continue;
+        };
+        let Ok(contents) = fs::read(entry.path()) else {
+            continue;
+        };
+
+        let count = count_lines(&contents);
+        *lines.entry(language.to_string()).or_insert(0) += count;
+    }
+
+    lines
+}
+
+/// Count the files of a project by extension
+///
+/// Extensions are lowercased; files without an extension are not counted.
+/// Directories whose name appears in `exclude_dirs` are skipped.
+pub fn count_file_types(path: &Path, exclude_dirs: &[String]) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+
+    for entry in WalkDir::new(path)
+        .into_iter()
+        .filter_entry(|e| !is_excluded_dir(e, exclude_dirs))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+    {
+        if let Some(extension) = entry.path().extension().and_then(|ext| ext.to_str()) {
+            *counts.entry(extension.to_ascii_lowercase()).or_insert(0) += 1;
+        }
+    }
+
+    counts
+}
+
+/// Determine the language with the most lines of code in a project
+pub fn detect_primary_language(path: &Path, exclude_dirs: &[String]) -> Option<String> {
+    count_lines_by_language(path, exclude_dirs)
+        .into_iter()
+        .filter(|(_, count)| *count > 0)
+        // Break ties by name so the result is deterministic
+        .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+        .map(|(language, _)| language)
+}
+
+/// Determine a project's language from the build manifest at its root
+///
+/// Only the project root is checked, so this is much cheaper than
+/// [`detect_primary_language`].
+pub fn detect_manifest_language(path: &Path) -> Option<&'static str> {
+    MANIFEST_LANGUAGES
+        .iter()
+        .find(|(manifest, _)| path.join(manifest).is_file())
+        .map(|(_, language)| *language)
+}
+
+/// Determine a project's type from the build manifest at its root
+///
+/// The type names the ecosystem the project belongs to: the manifest's
+/// language, except that JavaScript and TypeScript projects are both `node`.
+pub fn detect_project_type(path: &Path) -> Option<String> {
+    let project_type = match detect_manifest_language(path)? {
+        "javascript" | "typescript" => "node",
+        language => language,
+    };
+    Some(project_type.to_string())
+}
+
+/// Count the lines in a file's contents, including a final unterminated line
+fn count_lines(contents: &[u8]) -> usize {
+    let newlines = contents.iter().filter(|&&b| b == b'\n').count();
+    match contents.last() {
+        Some(b'\n') | None => newlines,
+        Some(_) => newlines + 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_detect_project_type() {
+        let temp_dir = tempdir().unwrap();
+        assert_eq!(detect_project_type(temp_dir.path()), None);
+        fs::write(temp_dir.path().join("package.json"), "{}").unwrap();
+        assert_eq!(
+            detect_project_type(temp_dir.path()).as_deref(),
+            Some("node")
+        );
+        fs::write(temp_dir.path().join("tsconfig.json"), "{}").unwrap();
+        assert_eq!(
+            detect_project_type(temp_dir.path()).as_deref(),
+            Some("node")
+        );
+        fs::write(temp_dir.path().join("Cargo.toml"), "[package]").unwrap();
+        assert_eq!(
+            detect_project_type(temp_dir.path()).as_deref(),
+            Some("rust")
+        );
+    }
+
+    #[test]
+    fn test_detect_primary_language() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(temp_dir.path().join("main.rs"), "fn main() {}\n").unwrap();
+        fs::write(temp_dir.path().join("build.py"), "a = 1\nb = 2\nc = 3").unwrap();
+        fs::create_dir(temp_dir.path().join("node_modules")).unwrap();
+        fs::write(
+            temp_dir.path().join("node_modules").join("dep.js"),
+            "1\n2\n3\n4\n5\n",
+        )
+        .unwrap();
+
+        let excludes = vec!["node_modules".to_string()];
+        let lines = count_lines_by_language(temp_dir.path(), &excludes);
+        assert_eq!(lines.get("rust"), Some(&1));
+        assert_eq!(lines.get("python"), Some(&3));
+        assert_eq!(lines.get("javascript"), None);
+
+        assert_eq!(
+            detect_primary_language(temp_dir.path(), &excludes),
+            Some("python".to_string())
+        );
+
+        let file_types = count_file_types(temp_dir.path(), &excludes);
+        assert_eq!(file_types.get("rs"), Some(&1));
+        assert_eq!(file_types.get("py"), Some(&1));
+        assert_eq!(file_types.get("js"), None);
+    }
+
+    #[test]
+    fn test_detect_manifest_language() {
+        let temp_dir = tempdir().unwrap();
+        assert_eq!(detect_manifest_language(temp_dir.path()), None);
+
+        fs::write(temp_dir.path().join("package.json"), "{}").unwrap();
+        assert_eq!(
+            detect_manifest_language(temp_dir.path()),
+            Some("javascript")
+        );
+
+        fs::write(temp_dir.path().join("tsconfig.json"), "{}").unwrap();
+        assert_eq!(
+            detect_manifest_language(temp_dir.path()),
+            Some("typescript")
+        );
+    }
+}
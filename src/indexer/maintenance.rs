@@ -0,0 +1,159 @@
+//! Index maintenance
+//!
+//! This module contains operations that clean up an existing index in place,
+//! such as removing tags that are too rare to be useful or the projects of a
+//! root that is no longer tracked.
+
+use crate::models::Project;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::Path,
+};
+
+/// Order in which each project's tags are listed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum TagOrder {
+    /// Alphabetical, which keeps diffs between index versions small
+    Alpha,
+    /// Tags used by the most projects first, alphabetical among equals
+    Frequency,
+}
+
+/// Count how many projects carry each tag
+///
+/// A tag listed twice on the same project is only counted once.
+pub fn tag_frequencies(projects: &[Project]) -> HashMap<String, usize> {
+    let mut frequencies = HashMap::new();
+    for project in projects {
+        let unique: HashSet<&String> = project.tags.iter().collect();
+        for tag in unique {
+            *frequencies.entry(tag.clone()).or_insert(0) += 1;
+        }
+    }
+    frequencies
+}
+
+/// Remove tags used by fewer than `min_count` projects
+///
+/// Returns the pruned tags along with the number of projects that carried
+/// them, sorted by tag name.
+///
+/// # Arguments
+///
+/// * `projects` - Projects to prune tags from
+/// * `min_count` - Minimum number of projects a tag must appear in to be kept
+pub fn prune_rare_tags(projects: &mut [Project], min_count: usize) -> BTreeMap<String, usize> {
+    let pruned: BTreeMap<String, usize> = tag_frequencies(projects)
+        .into_iter()
+        .filter(|(_, count)| *count < min_count)
+        .collect();
+
+    for project in projects.iter_mut() {
+        project.tags.retain(|tag| !pruned.contains_key(tag));
+    }
+
+    pruned
+}
+
+/// Sort the tags of every project
+///
+/// For [`TagOrder::Frequency`], frequencies are counted across all
+/// `projects`, so the tags most common in the portfolio come first.
+pub fn sort_tags(projects: &mut [Project], order: TagOrder) {
+    let frequencies = match order {
+        TagOrder::Alpha => HashMap::new(),
+        TagOrder::Frequency => tag_frequencies(projects),
+    };
+    for project in projects.iter_mut() {
+        project.tags.sort_by(|a, b| {
+            let frequency = |tag: &String| frequencies.get(tag).copied().unwrap_or(0);
+            frequency(b).cmp(&frequency(a)).then_with(|| a.cmp(b))
+        });
+    }
+}
+
+/// Remove the projects located within `prefix`
+///
+/// Paths are compared component by component, so `/p/work` matches
+/// `/p/work/app` but not `/p/workshop`. The prefix is compared as given and,
+/// when it still exists, in its canonical form too, so a relative prefix can
+/// drop projects recorded with absolute paths. Returns the removed projects.
+pub fn remove_projects_under(projects: &mut Vec<Project>, prefix: &Path) -> Vec<Project> {
+    let canonical = prefix.canonicalize().ok();
+    let (removed, kept) = projects.drain(..).partition(|project: &Project| {
+        project.path.starts_with(prefix)
+            || canonical
+                .as_ref()
+                .is_some_and(|canonical| project.path.starts_with(canonical))
+    });
+    *projects = kept;
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn project_with_tags(name: &str, tags: &[&str]) -> Project {
+        let mut project = Project::new(name.to_string(), PathBuf::from(name));
+        project.tags = tags.iter().map(|t| t.to_string()).collect();
+        project
+    }
+
+    #[test]
+    fn test_prune_rare_tags() {
+        let mut projects = vec![
+            project_with_tags("a", &["rust", "cli", "one-off"]),
+            project_with_tags("b", &["rust", "cli"]),
+            project_with_tags("c", &["rust", "web"]),
+        ];
+
+        let pruned = prune_rare_tags(&mut projects, 2);
+
+        assert_eq!(pruned.len(), 2);
+        assert_eq!(pruned.get("one-off"), Some(&1));
+        assert_eq!(pruned.get("web"), Some(&1));
+        assert_eq!(projects[0].tags, vec!["rust", "cli"]);
+        assert_eq!(projects[2].tags, vec!["rust"]);
+    }
+
+    #[test]
+    fn test_sort_tags() {
+        let mut projects = vec![
+            project_with_tags("a", &["web", "cli", "rust"]),
+            project_with_tags("b", &["rust", "cli"]),
+            project_with_tags("c", &["rust", "async"]),
+        ];
+
+        sort_tags(&mut projects, TagOrder::Frequency);
+        assert_eq!(projects[0].tags, vec!["rust", "cli", "web"]);
+        assert_eq!(projects[2].tags, vec!["rust", "async"]);
+
+        sort_tags(&mut projects, TagOrder::Alpha);
+        assert_eq!(projects[0].tags, vec!["cli", "rust", "web"]);
+        assert_eq!(projects[2].tags, vec!["async", "rust"]);
+    }
+
+    #[test]
+    fn test_remove_projects_under() {
+        let mut projects = vec![
+            Project::new("app".to_string(), PathBuf::from("/p/work/app")),
+            Project::new("lib".to_string(), PathBuf::from("/p/work/nested/lib")),
+            Project::new("shop".to_string(), PathBuf::from("/p/workshop")),
+            Project::new("home".to_string(), PathBuf::from("/p/home")),
+        ];
+
+        let removed = remove_projects_under(&mut projects, Path::new("/p/work/"));
+        let removed: Vec<&str> = removed.iter().map(|p| p.name.as_str()).collect();
+        let kept: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(removed, vec!["app", "lib"]);
+        assert_eq!(kept, vec!["shop", "home"]);
+    }
+
+    #[test]
+    fn test_duplicate_tags_counted_once() {
+        let projects = vec![project_with_tags("a", &["rust", "rust"])];
+        assert_eq!(tag_frequencies(&projects).get("rust"), Some(&1));
+    }
+}
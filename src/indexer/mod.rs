@@ -1,3 +1,26 @@
+pub mod dependencies;
+pub mod depth;
+pub mod diff;
+pub mod embedding_cache;
+pub mod export;
+pub mod glob;
+pub mod index_file;
+pub mod job;
+pub mod languages;
+pub mod maintenance;
+pub mod names;
+pub mod progress;
 pub mod project_indexer;
+pub mod readme;
+pub mod search;
+pub mod stats;
+pub mod verify;
 
+pub use export::OutputFormat;
+pub use index_file::{
+    load_index, render_index, require_index, save_index, save_index_as, stream_index_with,
+    IndexWriter,
+};
+pub use progress::{JsonReporter, NoopReporter, ProgressReporter};
 pub use project_indexer::ProjectIndexer;
+pub use stats::ProjectStats;
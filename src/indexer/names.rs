@@ -0,0 +1,147 @@
+//! Project display names
+//!
+//! A project's name defaults to its directory name. The `set-name` command
+//! records a different display name in a sidecar file next to the index,
+//! `{index}.names.json`, mapping project paths to names. The indexer reads it
+//! on every run, so renamed projects keep their names across re-indexing.
+//! Directory names can also be cleaned up into display names automatically
+//! with [`clean_name`].
+
+use crate::{error::Result, models::Project};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Display names by project path
+pub type NameOverrides = BTreeMap<PathBuf, String>;
+
+/// Path of the name overrides file belonging to an index file
+pub fn name_overrides_path(index_file: &Path) -> PathBuf {
+    let mut path = index_file.as_os_str().to_owned();
+    path.push(".names.json");
+    PathBuf::from(path)
+}
+
+/// Load the name overrides stored next to an index file
+///
+/// A missing file yields no overrides.
+pub fn load_name_overrides(index_file: &Path) -> Result<NameOverrides> {
+    let path = name_overrides_path(index_file);
+    if !path.exists() {
+        return Ok(NameOverrides::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+/// Write the name overrides stored next to an index file
+pub fn save_name_overrides(index_file: &Path, overrides: &NameOverrides) -> Result<()> {
+    let json = serde_json::to_string_pretty(overrides)?;
+    fs::write(name_overrides_path(index_file), json)?;
+    Ok(())
+}
+
+/// Words dropped from the end of a directory name when cleaning it
+const NAME_SUFFIXES: &[&str] = &["final", "copy", "old", "new", "backup", "bak", "latest"];
+
+/// Turn a directory name into a readable display name
+///
+/// Dashes and underscores become spaces, trailing version numbers (`v2`,
+/// `1.0.3`) and leftover words such as `final` or `copy` are dropped, and
+/// each word is capitalized: `my-project-v2_final` becomes `My Project`.
+/// The first word is always kept, and a name with no words is returned as is.
+pub fn clean_name(dir_name: &str) -> String {
+    let mut words: Vec<&str> = dir_name
+        .split(|c: char| c == '-' || c == '_' || c.is_whitespace())
+        .filter(|word| !word.is_empty())
+        .collect();
+    while words.len() > 1 && words.last().is_some_and(|word| is_name_suffix(word)) {
+        words.pop();
+    }
+    if words.is_empty() {
+        return dir_name.to_string();
+    }
+
+    words
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Check whether a word is a version number or a leftover suffix word
+fn is_name_suffix(word: &str) -> bool {
+    let version = word.strip_prefix(['v', 'V']).unwrap_or(word);
+    let is_version = !version.is_empty()
+        && version
+            .split('.')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()));
+    is_version || NAME_SUFFIXES.contains(&word.to_lowercase().as_str())
+}
+
+/// Find the project at `path` in an index
+///
+/// `path` matches a project when it is the path recorded in the index or
+/// resolves to the same directory, so both relative and absolute forms work.
+pub fn find_project_mut<'a>(projects: &'a mut [Project], path: &Path) -> Option<&'a mut Project> {
+    let canonical = path.canonicalize().ok();
+    projects.iter_mut().find(|project| {
+        project.path == path
+            || (canonical.is_some() && project.path.canonicalize().ok() == canonical)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_name_overrides_roundtrip() {
+        let temp_dir = tempdir().unwrap();
+        let index_file = temp_dir.path().join("index.json");
+        assert_eq!(
+            name_overrides_path(&index_file),
+            temp_dir.path().join("index.json.names.json")
+        );
+        assert!(load_name_overrides(&index_file).unwrap().is_empty());
+
+        let overrides = NameOverrides::from([(PathBuf::from("/p/tool"), "My Tool".to_string())]);
+        save_name_overrides(&index_file, &overrides).unwrap();
+        assert_eq!(load_name_overrides(&index_file).unwrap(), overrides);
+    }
+
+    #[test]
+    fn test_clean_name() {
+        assert_eq!(clean_name("my-project-v2_final"), "My Project");
+        assert_eq!(clean_name("data_pipeline-1.0.3"), "Data Pipeline");
+        assert_eq!(clean_name("webApp"), "WebApp");
+        assert_eq!(clean_name("2048"), "2048");
+        assert_eq!(clean_name("python3-tools"), "Python3 Tools");
+        assert_eq!(clean_name("___"), "___");
+    }
+
+    #[test]
+    fn test_find_project_mut() {
+        let temp_dir = tempdir().unwrap();
+        let project_dir = temp_dir.path().join("tool");
+        fs::create_dir(&project_dir).unwrap();
+
+        let mut projects = vec![
+            Project::new("other".to_string(), PathBuf::from("/p/other")),
+            Project::new("tool".to_string(), project_dir.clone()),
+        ];
+        let found = find_project_mut(&mut projects, &project_dir.join("..").join("tool"));
+        assert_eq!(found.map(|p| p.name.as_str()), Some("tool"));
+        let found = find_project_mut(&mut projects, Path::new("/p/other"));
+        assert_eq!(found.map(|p| p.name.as_str()), Some("other"));
+        assert!(find_project_mut(&mut projects, Path::new("/p/missing")).is_none());
+    }
+}
@@ -0,0 +1,168 @@
+//! Indexing progress reporting
+//!
+//! This module defines the hooks the indexer calls while it scans and
+//! processes projects. Keeping them behind a trait lets the CLI draw progress
+//! bars while library consumers and tests plug in their own reporting.
+//!
+//! [`JsonReporter`] writes the events as JSON lines instead, for programs
+//! wrapping the CLI. Two reporters can be combined by passing them as a
+//! tuple, which forwards every event to both.
+
+use serde_json::json;
+use std::{
+    io::{self, Write},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+
+/// Receives progress events from [`ProjectIndexer::index_projects`]
+///
+/// [`ProjectIndexer::index_projects`]: crate::indexer::ProjectIndexer::index_projects
+pub trait ProgressReporter: Send + Sync {
+    /// Called once before the directory scan starts
+    fn on_scan_start(&self);
+
+    /// Called once the scan is done with the number of projects to process
+    fn on_scan_complete(&self, _total: usize) {}
+
+    /// Called when a project directory is about to be processed
+    fn on_project(&self, name: &str);
+
+    /// Called once indexing is complete with the number of indexed projects
+    fn on_finish(&self, count: usize);
+}
+
+/// A reporter that ignores every event
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopReporter;
+
+impl ProgressReporter for NoopReporter {
+    fn on_scan_start(&self) {}
+
+    fn on_project(&self, _name: &str) {}
+
+    fn on_finish(&self, _count: usize) {}
+}
+
+impl<A: ProgressReporter, B: ProgressReporter> ProgressReporter for (A, B) {
+    fn on_scan_start(&self) {
+        self.0.on_scan_start();
+        self.1.on_scan_start();
+    }
+
+    fn on_scan_complete(&self, total: usize) {
+        self.0.on_scan_complete(total);
+        self.1.on_scan_complete(total);
+    }
+
+    fn on_project(&self, name: &str) {
+        self.0.on_project(name);
+        self.1.on_project(name);
+    }
+
+    fn on_finish(&self, count: usize) {
+        self.0.on_finish(count);
+        self.1.on_finish(count);
+    }
+}
+
+/// A reporter writing one JSON object per event, each on its own line
+///
+/// Every event has a `phase`:
+/// - `discover` when the scan starts
+/// - `scan` for each project, with its position `current` among the `total`
+///   projects to process and its `name`
+/// - `done` when indexing is complete, with the number of indexed projects
+///   as `current` and `total`
+///
+/// Write errors are ignored, so a closed pipe doesn't stop indexing.
+#[derive(Debug)]
+pub struct JsonReporter<W: Write + Send = io::Stderr> {
+    out: Mutex<W>,
+    current: AtomicUsize,
+    total: AtomicUsize,
+}
+
+impl JsonReporter {
+    /// Create a reporter writing to standard error
+    pub fn stderr() -> Self {
+        Self::new(io::stderr())
+    }
+}
+
+impl<W: Write + Send> JsonReporter<W> {
+    /// Create a reporter writing to `out`
+    pub fn new(out: W) -> Self {
+        Self {
+            out: Mutex::new(out),
+            current: AtomicUsize::new(0),
+            total: AtomicUsize::new(0),
+        }
+    }
+
+    /// Stop reporting and return the writer
+    pub fn into_inner(self) -> W {
+        self.out.into_inner().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn emit(&self, event: serde_json::Value) {
+        let mut out = self.out.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = writeln!(out, "{}", event).and_then(|_| out.flush());
+    }
+}
+
+impl<W: Write + Send> ProgressReporter for JsonReporter<W> {
+    fn on_scan_start(&self) {
+        self.emit(json!({ "phase": "discover" }));
+    }
+
+    fn on_scan_complete(&self, total: usize) {
+        self.total.store(total, Ordering::Relaxed);
+    }
+
+    fn on_project(&self, name: &str) {
+        let current = self.current.fetch_add(1, Ordering::Relaxed) + 1;
+        self.emit(json!({
+            "phase": "scan",
+            "current": current,
+            "total": self.total.load(Ordering::Relaxed),
+            "name": name,
+        }));
+    }
+
+    fn on_finish(&self, count: usize) {
+        self.emit(json!({ "phase": "done", "current": count, "total": count }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_reporter() {
+        let reporter = JsonReporter::new(Vec::new());
+        reporter.on_scan_start();
+        reporter.on_scan_complete(2);
+        reporter.on_project("foo");
+        reporter.on_project("bar");
+        reporter.on_finish(2);
+
+        let output = String::from_utf8(reporter.into_inner()).unwrap();
+        let events: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                json!({ "phase": "discover" }),
+                json!({ "phase": "scan", "current": 1, "total": 2, "name": "foo" }),
+                json!({ "phase": "scan", "current": 2, "total": 2, "name": "bar" }),
+                json!({ "phase": "done", "current": 2, "total": 2 }),
+            ]
+        );
+    }
+}
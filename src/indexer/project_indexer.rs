@@ -7,17 +7,82 @@
 use crate::{
     error::{OllamaError, Result},
     models::{Project, ProjectStatus},
-    ollama::{ClientConfig, OllamaClient},
+    ollama::{
+        parse_scored_tags, parse_tags_with, ClientStats, GenerationUsage, OllamaClient, TagContext,
+        TagDelimiter, TagExample, MAX_COMMIT_MESSAGES,
+    },
 };
-use indicatif::{ProgressBar, ProgressStyle};
-use serde::{Deserialize, Serialize};
+
+use super::{
+    dependencies::collect_dependencies,
+    embedding_cache::{embedding_cache_path, EmbeddingCache, DEFAULT_EMBEDDING_CACHE_CAPACITY},
+    export::OutputFormat,
+    glob::PathGlob,
+    job::JobLedger,
+    languages::{
+        count_file_types, detect_manifest_language, detect_primary_language, detect_project_type,
+    },
+    maintenance::{sort_tags, TagOrder},
+    names::{clean_name, NameOverrides},
+    readme::inspect_readme,
+    search::{embedding_text, search_index, SearchFields},
+    stats::ProjectStats,
+    ProgressReporter,
+};
+use chrono::{DateTime, TimeZone, Utc};
+use futures_util::{future, stream, Stream, StreamExt};
 use std::{
     collections::HashMap,
-    fs,
-    path::{Path, PathBuf},
-    time::SystemTime,
+    fs, io,
+    path::{Component, Path, PathBuf},
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
 };
-use walkdir::WalkDir;
+use tokio::{process::Command, sync::OnceCell};
+use walkdir::{DirEntry, WalkDir};
+
+/// Default limit on the number of files walked per project
+pub const DEFAULT_MAX_FILES_PER_PROJECT: u64 = 100_000;
+
+/// Default number of projects processed at the same time
+pub const DEFAULT_MAX_CONCURRENCY: usize = 4;
+
+/// Directory names excluded from scanning unless `exclude` replaces them
+pub const DEFAULT_EXCLUDE_DIRS: &str = ".git,node_modules,__pycache__,target,.idea,.vscode";
+
+/// Default number of directories above a project its category is taken from
+pub const DEFAULT_CATEGORY_DEPTH: usize = 1;
+
+/// Category given to every project when categories are flattened
+pub const FLAT_CATEGORY: &str = "all";
+
+/// Default time limit for a single git command, in seconds
+pub const DEFAULT_GIT_TIMEOUT_SECS: u64 = 10;
+
+/// Projects with a commit within this many days are active
+pub const ACTIVE_WINDOW_DAYS: i64 = 30;
+
+/// Default number of days without a commit after which a project is archived
+pub const DEFAULT_ARCHIVE_THRESHOLD_DAYS: u32 = 365;
+
+/// Longest commit subject shown to the model, in characters
+const MAX_COMMIT_MESSAGE_CHARS: usize = 120;
+
+/// Most projects embedded in a single request by `index_projects`
+const EMBED_BATCH_SIZE: usize = 64;
+
+/// How a project's `last_modified` timestamp is determined
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum MtimeSource {
+    /// Time of the latest git commit, falling back to `dir` outside git repos
+    #[default]
+    Git,
+    /// Filesystem modification time of the project directory itself
+    Dir,
+    /// Newest modification time of any file in the project, honoring excludes
+    Tree,
+}
 
 /// Configuration for the project indexer
 #[derive(Debug, Clone)]
@@ -28,15 +93,227 @@ pub struct IndexerConfig {
     /// JSON file to store the index
     pub index_file: PathBuf,
 
+    /// Format the index is written in
+    ///
+    /// Markdown and CSV indexes leave out most fields, so they can't be read
+    /// back, e.g. by `incremental` runs.
+    pub output_format: OutputFormat,
+
     /// Maximum directory depth to traverse
     pub max_depth: u32,
 
     /// Minimum directory depth to traverse
     pub min_depth: u32,
 
-    /// Directories to exclude
+    /// Directories to exclude, comma-separated
+    ///
+    /// The list replaces [`DEFAULT_EXCLUDE_DIRS`], unless it starts with `+`
+    /// to add to them instead. See [`parse_exclude_dirs`].
     pub exclude: String,
 
+    /// Glob patterns of paths to exclude, matched against the full path
+    ///
+    /// Unlike `exclude`, which compares directory names, these select whole
+    /// subtrees by location, e.g. `**/archive/**`.
+    pub exclude_paths: Vec<PathGlob>,
+
+    /// Use tags from a project's `.tags` file instead of generating them
+    ///
+    /// When disabled, tags read from `.tags` are merged with the generated ones.
+    pub prefer_file_tags: bool,
+
+    /// Maximum number of files to walk when measuring a project
+    ///
+    /// Projects holding more files are reported with a partial size instead
+    /// of being walked in full.
+    pub max_files_per_project: u64,
+
+    /// Directory where raw Ollama responses are saved for debugging
+    ///
+    /// Each project's unparsed response is written to `{dir}/{path}.txt`,
+    /// where `path` is the project's path relative to `projects_dir` with
+    /// its components joined by `__`, e.g. `work__tool.txt`. Projects with
+    /// the same name in different categories thus get different files.
+    pub save_raw_responses: Option<PathBuf>,
+
+    /// Source used to compute each project's `last_modified` timestamp
+    pub mtime_source: MtimeSource,
+
+    /// Time limit for each git command run against a project
+    ///
+    /// Projects whose git commands time out are treated as if git
+    /// information were unavailable.
+    pub git_timeout: Duration,
+
+    /// Days without a commit after which a git repository is archived
+    ///
+    /// Repositories with a commit within [`ACTIVE_WINDOW_DAYS`] are active,
+    /// and those in between stale.
+    pub archive_threshold_days: u32,
+
+    /// How many directories above a project its category name is taken from
+    ///
+    /// `1` uses the immediate parent. Ancestors at or above `projects_dir`
+    /// are never used; such projects are uncategorized.
+    pub category_depth: usize,
+
+    /// Name categories after each project's language instead of its directory
+    ///
+    /// The language comes from the build manifest at the project root, or
+    /// from line counts when there is none.
+    pub category_from_manifest: bool,
+
+    /// Put every project in the single category [`FLAT_CATEGORY`]
+    ///
+    /// For organizing projects by tags alone. Takes precedence over the other
+    /// category settings.
+    pub flatten_categories: bool,
+
+    /// Category for projects without a category directory
+    ///
+    /// Applies to direct children of `projects_dir` and to projects nested
+    /// less deeply than `category_depth`.
+    pub default_category: String,
+
+    /// Display names for categories, keyed by the category they replace
+    ///
+    /// Applied after the category is determined, including to
+    /// `default_category`; categories without an alias are kept unchanged.
+    pub category_aliases: HashMap<String, String>,
+
+    /// Display names replacing the directory name of some projects
+    ///
+    /// Keyed by project path, as recorded by the `set-name` command.
+    pub name_overrides: NameOverrides,
+
+    /// Only index directories that are git repositories
+    pub git_only: bool,
+
+    /// Walk the top-level directories of `projects_dir` on separate threads
+    /// when discovering projects
+    ///
+    /// Speeds up discovery on slow or network filesystems.
+    pub parallel_walk: bool,
+
+    /// Number of projects processed at the same time
+    ///
+    /// Mostly useful to overlap the Ollama requests of several projects.
+    /// Values below 1 are treated as 1.
+    pub max_concurrency: usize,
+
+    /// Stop indexing at the first unreadable directory or failed project
+    ///
+    /// By default such entries are logged and skipped.
+    pub abort_on_error: bool,
+
+    /// Whether to detect each project's dominant language by counting lines
+    ///
+    /// This reads every source file of every project, so it is off by default.
+    pub detect_primary_language: bool,
+
+    /// Whether to record how many files of each extension a project holds
+    pub collect_file_types: bool,
+
+    /// Whether to record the dependencies declared in each project's manifest
+    pub collect_dependencies: bool,
+
+    /// Whether to turn directory names into readable display names
+    ///
+    /// See [`clean_name`]. Names from `name_overrides` are kept as they are.
+    pub clean_names: bool,
+
+    /// Example projects and tags included in prompts as few-shot examples
+    pub tag_examples: Vec<TagExample>,
+
+    /// System prompt overriding the built-in tagging instruction
+    pub system_prompt: Option<String>,
+
+    /// How tags are separated in model responses
+    pub tag_delimiter: TagDelimiter,
+
+    /// Drop generated tags the model is less confident about than this
+    ///
+    /// When set, the model is asked for a confidence between 0 and 1 with each
+    /// tag. Responses without confidences are parsed as usual and all their
+    /// tags kept.
+    pub tag_min_confidence: Option<f32>,
+
+    /// Number of recent commit subjects to show the model when tagging
+    ///
+    /// At most [`MAX_COMMIT_MESSAGES`] are used. Projects that aren't git
+    /// repositories or have no commits are tagged without them.
+    pub tag_from_commits: Option<usize>,
+
+    /// Continue each tagging request from a context primed with the system
+    /// prompt
+    ///
+    /// The context is requested once, before the first project is tagged,
+    /// and every request continues from that same context, so Ollama skips
+    /// re-processing the system prompt. Category hints are then only part of
+    /// each project's prompt.
+    pub reuse_generation_context: bool,
+
+    /// Store an embedding of each project, for semantic search
+    ///
+    /// Embeddings are computed by the Ollama client's model from each
+    /// project's [`embedding_text`], once tagged. They are cached in
+    /// `embedding_cache`, so unchanged projects aren't embedded again on the
+    /// next run. Without an Ollama client, projects get no embedding.
+    pub embed_projects: bool,
+
+    /// File embeddings are cached in between runs
+    ///
+    /// Defaults to `{index}.embeddings.json`, next to the index.
+    pub embedding_cache: Option<PathBuf>,
+
+    /// Discover and inspect projects without tagging them or writing the
+    /// index
+    ///
+    /// Projects are returned with no tags, and neither the index file nor
+    /// the job ledger is touched.
+    pub dry_run: bool,
+
+    /// Tags given to projects when tag generation fails or yields nothing
+    ///
+    /// Empty by default, so such projects stay untagged rather than carrying
+    /// tags that look real but weren't derived from the project.
+    pub default_tags: Vec<String>,
+
+    /// Keep tags that weren't generated by the model when re-tagging
+    ///
+    /// On re-index, each project's manual tags are read from the existing
+    /// index and kept alongside freshly generated ones.
+    pub preserve_manual_tags: bool,
+
+    /// Order each project's tags are saved in
+    ///
+    /// `None` keeps the order they were generated or read in.
+    pub sort_tags: Option<TagOrder>,
+
+    /// Only re-index projects with files changed since this git commit
+    ///
+    /// The changes are listed with `git diff` in the repository holding
+    /// `projects_dir`; other projects keep their entry from the existing
+    /// index, and projects missing from it are indexed. When `projects_dir`
+    /// isn't inside a git repository or the diff fails, every project is
+    /// indexed. Only honored by [`ProjectIndexer::index_projects`].
+    pub since_commit: Option<String>,
+
+    /// Reuse the projects processed by an interrupted run
+    ///
+    /// Every run of [`ProjectIndexer::index_projects`] records the projects
+    /// it has processed in a ledger next to the index file, deleted once the
+    /// index is written. With `resume`, the projects found in the ledger are
+    /// taken from it instead of being processed again.
+    pub resume: bool,
+
+    /// Reuse the entries of the existing index for unchanged projects
+    ///
+    /// A project is unchanged when its last modification time, computed as
+    /// configured by `mtime_source`, matches the one in the index. Its entry
+    /// is kept, with its tags, and only its status is refreshed.
+    pub incremental: bool,
+
     /// Ollama client for tag generation
     pub ollama_client: Option<OllamaClient>,
 }
@@ -53,17 +330,66 @@ impl IndexerConfig {
         Self {
             projects_dir,
             index_file,
+            output_format: OutputFormat::Json,
             max_depth,
             min_depth,
             exclude,
+            exclude_paths: Vec::new(),
+            prefer_file_tags: false,
+            max_files_per_project: DEFAULT_MAX_FILES_PER_PROJECT,
+            save_raw_responses: None,
+            mtime_source: MtimeSource::default(),
+            git_timeout: Duration::from_secs(DEFAULT_GIT_TIMEOUT_SECS),
+            archive_threshold_days: DEFAULT_ARCHIVE_THRESHOLD_DAYS,
+            git_only: false,
+            parallel_walk: false,
+            max_concurrency: DEFAULT_MAX_CONCURRENCY,
+            abort_on_error: false,
+            category_depth: DEFAULT_CATEGORY_DEPTH,
+            category_from_manifest: false,
+            flatten_categories: false,
+            default_category: "uncategorized".to_string(),
+            category_aliases: HashMap::new(),
+            name_overrides: NameOverrides::new(),
+            detect_primary_language: false,
+            collect_file_types: false,
+            collect_dependencies: false,
+            clean_names: false,
+            tag_examples: Vec::new(),
+            system_prompt: None,
+            tag_delimiter: TagDelimiter::Auto,
+            tag_min_confidence: None,
+            tag_from_commits: None,
+            reuse_generation_context: false,
+            embed_projects: false,
+            embedding_cache: None,
+            dry_run: false,
+            default_tags: Vec::new(),
+            preserve_manual_tags: false,
+            sort_tags: None,
+            since_commit: None,
+            resume: false,
+            incremental: false,
             ollama_client: None,
         }
     }
+
+    /// File embeddings are cached in, see `embedding_cache`
+    pub fn embedding_cache_file(&self) -> PathBuf {
+        self.embedding_cache
+            .clone()
+            .unwrap_or_else(|| embedding_cache_path(&self.index_file))
+    }
 }
 
 /// Main project indexer implementation
 pub struct ProjectIndexer {
     config: IndexerConfig,
+    usage: Mutex<GenerationUsage>,
+    /// Context primed with the system prompt, see `reuse_generation_context`
+    generation_context: OnceCell<Option<Vec<u32>>>,
+    /// Embeddings of project texts, see `embed_projects`
+    embedding_cache: EmbeddingCache,
 }
 
 impl ProjectIndexer {
@@ -71,116 +397,822 @@ impl ProjectIndexer {
     pub fn new(config: IndexerConfig, ollama_client: Option<OllamaClient>) -> Self {
         let mut config = config;
         config.ollama_client = ollama_client;
-        Self { config }
+        let embedding_cache = if config.embed_projects && !config.dry_run {
+            let path = config.embedding_cache_file();
+            let cache = EmbeddingCache::load(&path, DEFAULT_EMBEDDING_CACHE_CAPACITY)
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Ignoring unreadable embedding cache {:?}: {}", path, e);
+                    EmbeddingCache::new(DEFAULT_EMBEDDING_CACHE_CAPACITY)
+                });
+            if !cache.is_empty() {
+                tracing::debug!("Loaded {} cached embeddings from {:?}", cache.len(), path);
+            }
+            cache
+        } else {
+            EmbeddingCache::new(DEFAULT_EMBEDDING_CACHE_CAPACITY)
+        };
+        Self {
+            config,
+            usage: Mutex::new(GenerationUsage::default()),
+            generation_context: OnceCell::new(),
+            embedding_cache,
+        }
+    }
+
+    /// Tokens and model time spent on tag generation so far
+    pub fn usage(&self) -> GenerationUsage {
+        *self.usage.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Requests made by the Ollama client so far, if one is configured
+    pub fn client_stats(&self) -> Option<ClientStats> {
+        self.config.ollama_client.as_ref().map(OllamaClient::stats)
     }
 
     /// Index projects in the configured directory
-    pub async fn index_projects<F>(&self, mut progress_callback: F) -> Result<Vec<Project>>
-    where
-        F: FnMut(&str),
-    {
+    ///
+    /// Progress is reported through `reporter`; pass [`NoopReporter`] to
+    /// index silently.
+    ///
+    /// [`NoopReporter`]: super::NoopReporter
+    pub async fn index_projects(&self, reporter: &dyn ProgressReporter) -> Result<Vec<Project>> {
+        reporter.on_scan_start();
+
         let mut projects = Vec::new();
-        let exclude_dirs: Vec<&str> = self.config.exclude.split(',').collect();
+        let previous = self.previous_projects();
+        let changed_files = match &self.config.since_commit {
+            Some(since) => self.changed_files(since).await,
+            None => None,
+        };
+        let existing = match changed_files {
+            Some(_) => self.existing_projects(),
+            None => HashMap::new(),
+        };
+
+        if let Some(dir) = &self.config.save_raw_responses {
+            if !self.config.dry_run {
+                fs::create_dir_all(dir)?;
+            }
+        }
+
+        let mut candidates = self.discover_projects()?;
+        if let Some(changed_files) = &changed_files {
+            // Projects missing from the existing index are indexed anyway
+            candidates.retain(|path| {
+                if has_changed_files(path, changed_files) {
+                    return true;
+                }
+                match existing.get(path) {
+                    Some(project) => {
+                        projects.push(project.clone());
+                        false
+                    }
+                    None => true,
+                }
+            });
+        }
+        if self.config.incremental {
+            let existing = self.existing_projects();
+            let mut changed = Vec::with_capacity(candidates.len());
+            for path in candidates {
+                match existing.get(&path) {
+                    Some(previous) => match self.reuse_unchanged(&path, previous).await {
+                        Some(project) => projects.push(project),
+                        None => changed.push(path),
+                    },
+                    None => changed.push(path),
+                }
+            }
+            candidates = changed;
+        }
+        let (ledger, mut done) = if self.config.dry_run {
+            (None, HashMap::new())
+        } else {
+            let (ledger, done) = JobLedger::open(&self.config.index_file, self.config.resume)?;
+            (Some(ledger), done)
+        };
+        candidates.retain(|path| match done.remove(path) {
+            Some(project) => {
+                projects.push(project);
+                false
+            }
+            None => true,
+        });
+        reporter.on_scan_complete(candidates.len());
+
+        // Projects are sorted below, so they may complete in any order
+        let mut results = stream::iter(candidates)
+            .map(|path| async {
+                reporter.on_project(dir_name(&path));
+                let result = self.process_project(&path, previous.get(&path)).await;
+                (path, result)
+            })
+            .buffer_unordered(self.config.max_concurrency.max(1));
+        while let Some((path, result)) = results.next().await {
+            match result {
+                Ok(project) => {
+                    if let Some(Err(e)) = ledger.as_ref().map(|ledger| ledger.record(&project)) {
+                        tracing::warn!("Failed to record {:?} in the job ledger: {}", path, e);
+                    }
+                    projects.push(project);
+                }
+                Err(e) if self.config.abort_on_error => return Err(e),
+                Err(e) => tracing::warn!("Skipping project {:?}: {}", path, e),
+            }
+        }
+        if self.config.embed_projects && !self.config.dry_run {
+            self.embed_missing(&mut projects).await;
+        }
+
+        // Sort projects by category and name, then path for projects that
+        // share both, so the order doesn't depend on completion order
+        projects.sort_by(|a, b| {
+            a.category
+                .cmp(&b.category)
+                .then(a.name.cmp(&b.name))
+                .then(a.path.cmp(&b.path))
+        });
+        if let Some(order) = self.config.sort_tags {
+            sort_tags(&mut projects, order);
+        }
+
+        // Save index to file
+        if let Some(ledger) = ledger {
+            self.save_index(&projects)?;
+            self.save_embedding_cache()?;
+            ledger.finish()?;
+        }
+
+        reporter.on_finish(projects.len());
+
+        Ok(projects)
+    }
+
+    /// Index projects, yielding each one as soon as it has been processed
+    ///
+    /// Unlike [`index_projects`](Self::index_projects), projects come in
+    /// discovery order and nothing is written to the index file: sorting and
+    /// saving are left to the caller. Projects that fail to process are
+    /// skipped, unless `abort_on_error` is set, in which case the error is
+    /// yielded and the stream ends. The indexer remains usable afterwards,
+    /// e.g. to read its [`usage`](Self::usage).
+    ///
+    /// The scan and each project are reported through `reporter`; calling
+    /// its `on_finish` is left to the caller, once done with the stream.
+    pub fn stream<'a>(
+        &'a self,
+        reporter: &'a dyn ProgressReporter,
+    ) -> impl Stream<Item = Result<Project>> + 'a {
+        reporter.on_scan_start();
+        let (candidates, error) = match self.discover_projects() {
+            Ok(candidates) => (candidates, None),
+            Err(e) => (Vec::new(), Some(e)),
+        };
+        reporter.on_scan_complete(candidates.len());
+        let previous = Arc::new(self.previous_projects());
+
+        let projects = stream::iter(candidates)
+            .map(move |path| {
+                let previous = Arc::clone(&previous);
+                async move {
+                    reporter.on_project(dir_name(&path));
+                    match self.process_project(&path, previous.get(&path)).await {
+                        Ok(mut project) => {
+                            if self.config.embed_projects && !self.config.dry_run {
+                                self.embed_project(&mut project).await;
+                            }
+                            Some(Ok(project))
+                        }
+                        Err(e) if self.config.abort_on_error => Some(Err(e)),
+                        Err(e) => {
+                            tracing::warn!("Skipping project {:?}: {}", path, e);
+                            None
+                        }
+                    }
+                }
+            })
+            // Unlike index_projects, keep the discovery order
+            .buffered(self.config.max_concurrency.max(1))
+            .filter_map(future::ready)
+            // Stop after the first error
+            .scan(false, |failed, result| {
+                let item = (!*failed).then(|| {
+                    *failed = result.is_err();
+                    result
+                });
+                future::ready(item)
+            });
+        stream::iter(error.map(Err)).chain(projects)
+    }
+
+    /// Walk `projects_dir` and collect the directories to index as projects
+    ///
+    /// With `parallel_walk`, the subtrees below `projects_dir` are walked on
+    /// separate threads; candidates still come in the same order. A
+    /// `min_depth` above `max_depth` is a validation error.
+    fn discover_projects(&self) -> Result<Vec<PathBuf>> {
+        let (min_depth, max_depth) = (
+            self.config.min_depth as usize,
+            self.config.max_depth as usize,
+        );
+        if min_depth > max_depth {
+            return Err(OllamaError::ValidationError(format!(
+                "Minimum depth {} is greater than maximum depth {}",
+                min_depth, max_depth
+            ))
+            .into());
+        }
+        let root = &self.config.projects_dir;
+        if !self.config.parallel_walk || max_depth < 2 {
+            return self.walk_candidates(root, min_depth, max_depth);
+        }
 
-        for entry in WalkDir::new(&self.config.projects_dir)
-            .max_depth(self.config.max_depth as usize)
-            .min_depth(self.config.min_depth as usize)
+        // Each top-level directory is walked on its own, starting with the
+        // directory itself, so merging the walks keeps the sequential order
+        // walkdir clamps `min_depth` to `max_depth`, so only walk the root
+        // itself when it may be a candidate
+        let mut candidates = match min_depth {
+            0 => self.walk_candidates(root, 0, 0)?,
+            _ => Vec::new(),
+        };
+        let exclude_dirs = self.exclude_dirs();
+        let subtrees: Vec<PathBuf> = WalkDir::new(root)
+            .min_depth(1)
+            .max_depth(1)
             .into_iter()
+            .filter_entry(|e| self.should_walk(e, &exclude_dirs))
             .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_dir())
+            .map(|e| e.into_path())
+            .collect();
+
+        let threads = thread::available_parallelism()
+            .map_or(1, |n| n.get())
+            .min(subtrees.len())
+            .max(1);
+        let subtree_min_depth = min_depth.saturating_sub(1);
+        let mut results: Vec<(usize, Result<Vec<PathBuf>>)> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads)
+                .map(|worker| {
+                    let subtrees = &subtrees;
+                    scope.spawn(move || {
+                        subtrees
+                            .iter()
+                            .enumerate()
+                            .skip(worker)
+                            .step_by(threads)
+                            .map(|(i, subtree)| {
+                                let found =
+                                    self.walk_candidates(subtree, subtree_min_depth, max_depth - 1);
+                                (i, found)
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().expect("directory walk panicked"))
+                .collect()
+        });
+
+        results.sort_by_key(|(i, _)| *i);
+        for (_, found) in results {
+            candidates.extend(found?);
+        }
+        Ok(candidates)
+    }
+
+    /// Walk `root` and collect the directories to index as projects, between
+    /// `min_depth` and `max_depth` levels below it
+    fn walk_candidates(
+        &self,
+        root: &Path,
+        min_depth: usize,
+        max_depth: usize,
+    ) -> Result<Vec<PathBuf>> {
+        let mut candidates = Vec::new();
+        let exclude_dirs = self.exclude_dirs();
+        let index_dir = self.index_dir();
+
+        // Entries above `min_depth` are walked rather than skipped with
+        // `WalkDir::min_depth`, which would keep them from `filter_entry` and
+        // so let excluded directories through
+        for entry in WalkDir::new(root)
+            .max_depth(max_depth)
+            .into_iter()
+            .filter_entry(|e| e.depth() == 0 || self.should_walk(e, &exclude_dirs))
         {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) if self.config.abort_on_error => return Err(io::Error::from(e).into()),
+                Err(e) => {
+                    tracing::warn!("Skipping unreadable entry: {}", e);
+                    continue;
+                }
+            };
+            if entry.depth() < min_depth {
+                continue;
+            }
             let path = entry.path();
-            if path.is_dir()
-                && !exclude_dirs
-                    .iter()
-                    .any(|&dir| path.to_string_lossy().contains(dir))
-            {
-                progress_callback(
-                    path.file_name()
-                        .unwrap_or_default()
-                        .to_str()
-                        .unwrap_or_default(),
-                );
-                if let Ok(project) = self.process_project(path).await {
-                    projects.push(project);
+            if path.is_dir() {
+                if self.config.git_only && !path.join(".git").exists() {
+                    continue;
+                }
+                // The directory receiving the index is an output location, not a project
+                if index_dir.is_some() && path.canonicalize().ok() == index_dir {
+                    tracing::warn!("Skipping {:?}: it contains the index file", path);
+                    continue;
                 }
+                candidates.push(path.to_path_buf());
             }
         }
 
-        // Sort projects by category and name
-        projects.sort_by(|a, b| a.category.cmp(&b.category).then(a.name.cmp(&b.name)));
+        Ok(candidates)
+    }
 
-        // Save index to file
-        self.save_index(&projects)?;
+    /// Decide whether the walk should visit an entry and its children
+    ///
+    /// Directories named in `exclude_dirs` and paths matching `exclude_paths`
+    /// are skipped along with everything below them. Directories whose path
+    /// isn't valid UTF-8 are skipped with a warning: the JSON index can only
+    /// store UTF-8 paths, and a lossily converted path would point at a
+    /// directory that doesn't exist.
+    fn should_walk(&self, entry: &DirEntry, exclude_dirs: &[String]) -> bool {
+        if is_excluded_dir(entry, exclude_dirs) {
+            return false;
+        }
+        if entry.file_type().is_dir() && entry.path().to_str().is_none() {
+            tracing::warn!(
+                "Skipping directory with a non-UTF-8 path: {}",
+                entry.path().display()
+            );
+            return false;
+        }
+        !self.is_excluded_path(entry.path())
+    }
 
-        Ok(projects)
+    /// Check a path against the `exclude_paths` globs
+    ///
+    /// Globs are tried against both the full path and the path relative to
+    /// `projects_dir`.
+    fn is_excluded_path(&self, path: &Path) -> bool {
+        let relative = path.strip_prefix(&self.config.projects_dir).ok();
+        self.config.exclude_paths.iter().any(|glob| {
+            glob.is_match(path) || relative.is_some_and(|relative| glob.is_match(relative))
+        })
+    }
+
+    /// Replace a category with its configured alias, if any
+    fn category_alias(&self, category: String) -> String {
+        match self.config.category_aliases.get(&category) {
+            Some(alias) => alias.clone(),
+            None => category,
+        }
+    }
+
+    /// Projects of the existing index, by path, when manual tags are preserved
+    fn previous_projects(&self) -> HashMap<PathBuf, Project> {
+        if !self.config.preserve_manual_tags {
+            return HashMap::new();
+        }
+        self.existing_projects()
+    }
+
+    /// Projects of the existing index, by path
+    ///
+    /// A missing or unreadable index yields no projects.
+    fn existing_projects(&self) -> HashMap<PathBuf, Project> {
+        if !self.config.index_file.exists() {
+            return HashMap::new();
+        }
+        match super::load_index(&self.config.index_file) {
+            Ok(projects) => projects
+                .into_iter()
+                .map(|project| (project.path.clone(), project))
+                .collect(),
+            Err(e) => {
+                tracing::warn!("Failed to load the existing index: {}", e);
+                HashMap::new()
+            }
+        }
+    }
+
+    /// The index entry of a project, if the project hasn't changed since
+    ///
+    /// Its status is refreshed, as it also depends on the current date.
+    async fn reuse_unchanged(&self, path: &Path, previous: &Project) -> Option<Project> {
+        // Entries from indexes that predate modification times can't be compared
+        if previous.last_modified.timestamp() == 0 {
+            return None;
+        }
+        let last_commit = self.last_commit(path).await;
+        let last_modified = self.last_modified(path, &self.exclude_dirs(), last_commit)?;
+        if last_modified != previous.last_modified {
+            return None;
+        }
+
+        let mut project = previous.clone();
+        project.status = detect_status(path, last_commit, self.config.archive_threshold_days);
+        project.last_commit_date = last_commit.or_else(|| dir_mtime(path));
+        Some(project)
+    }
+
+    /// Absolute paths of the files changed between `since` and `HEAD`
+    ///
+    /// Returns `None`, meaning every project should be indexed, when
+    /// `projects_dir` isn't inside a git repository or the diff fails.
+    async fn changed_files(&self, since: &str) -> Option<Vec<PathBuf>> {
+        let root = &self.config.projects_dir;
+        let timeout = self.config.git_timeout;
+        let Some(toplevel) = run_git(root, &["rev-parse", "--show-toplevel"], timeout).await else {
+            tracing::warn!(
+                "{:?} is not inside a git repository, indexing all projects",
+                root
+            );
+            return None;
+        };
+        let Some(diff) =
+            run_git(root, &["diff", "--name-only", since, "HEAD", "--"], timeout).await
+        else {
+            tracing::warn!(
+                "Failed to list files changed since {}, indexing all projects",
+                since
+            );
+            return None;
+        };
+
+        let toplevel = PathBuf::from(toplevel.trim());
+        Some(
+            diff.lines()
+                .filter(|line| !line.is_empty())
+                .map(|line| toplevel.join(line))
+                .collect(),
+        )
+    }
+
+    /// Canonical path of the directory the index file is written to
+    fn index_dir(&self) -> Option<PathBuf> {
+        let parent = self.config.index_file.parent()?;
+        let parent = if parent.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            parent
+        };
+        parent.canonicalize().ok()
+    }
+
+    /// Directory names excluded from scanning
+    fn exclude_dirs(&self) -> Vec<String> {
+        parse_exclude_dirs(&self.config.exclude)
     }
 
     /// Process a single project directory
-    async fn process_project(&self, path: &Path) -> Result<Project> {
-        let name = path
+    ///
+    /// `previous` is the project's entry in the existing index, whose manual
+    /// tags are carried over when `preserve_manual_tags` is set.
+    async fn process_project(&self, path: &Path, previous: Option<&Project>) -> Result<Project> {
+        let dir_name = path
             .file_name()
             .unwrap_or_default()
             .to_str()
             .unwrap_or_default()
             .to_string();
-
-        let category = path
-            .parent()
-            .and_then(|p| p.file_name())
-            .and_then(|n| n.to_str())
-            .unwrap_or("uncategorized")
-            .to_string();
+        let name = match self.config.name_overrides.get(path) {
+            Some(name) => name.clone(),
+            None if self.config.clean_names => clean_name(&dir_name),
+            None => dir_name.clone(),
+        };
 
         let mut project = Project::new(name, path.to_path_buf());
-        project.category = category;
+        if self.config.clean_names {
+            project.dir_name = Some(dir_name);
+        }
+
+        let last_commit = self.last_commit(path).await;
+        project.status = detect_status(path, last_commit, self.config.archive_threshold_days);
+        project.last_commit_date = last_commit.or_else(|| dir_mtime(path));
+
+        let exclude_dirs = self.exclude_dirs();
+        let (size_bytes, truncated) =
+            measure_project_size(path, &exclude_dirs, self.config.max_files_per_project);
+        project.size_bytes = size_bytes;
+        project.size_truncated = truncated;
+
+        project.project_type = detect_project_type(path);
+        let (has_dockerfile, has_compose) = detect_container_files(path);
+        project.has_dockerfile = has_dockerfile;
+        project.has_compose = has_compose;
+        project.has_tests = detect_tests(path, &exclude_dirs, self.config.max_files_per_project);
+        let readme = inspect_readme(path);
+        project.has_readme_badges = readme.has_badges;
+        project.primary_link = readme.primary_link;
 
-        // Detect project status
-        if path.join(".git").exists() {
-            project.status = self.detect_git_status(path).await;
+        if let Some(last_modified) = self.last_modified(path, &exclude_dirs, last_commit) {
+            project.last_modified = last_modified;
         }
 
-        // Generate tags if Ollama is enabled
-        if let Some(client) = &self.config.ollama_client {
-            if let Ok(tags) = client
-                .generate_tags(path.to_str().unwrap_or_default())
+        if self.config.detect_primary_language {
+            project.primary_language = detect_primary_language(path, &exclude_dirs);
+        }
+        if self.config.collect_file_types {
+            project.file_types = count_file_types(path, &exclude_dirs);
+        }
+        if self.config.collect_dependencies {
+            project.dependencies = collect_dependencies(path);
+        }
+
+        let category = if self.config.flatten_categories {
+            FLAT_CATEGORY.to_string()
+        } else if self.config.category_from_manifest {
+            // Fall back to counting lines for projects without a known manifest
+            let language = detect_manifest_language(path)
+                .map(str::to_string)
+                .or_else(|| {
+                    if self.config.detect_primary_language {
+                        project.primary_language.clone()
+                    } else {
+                        detect_primary_language(path, &exclude_dirs)
+                    }
+                });
+            language.unwrap_or_else(|| self.config.default_category.clone())
+        } else {
+            project_category(
+                path,
+                &self.config.projects_dir,
+                self.config.category_depth,
+                &self.config.default_category,
+            )
+        };
+        project.category = self.category_alias(category);
+
+        if self.config.dry_run {
+            return Ok(project);
+        }
+        if let Some(previous) = previous {
+            project.tags = previous.tags.clone();
+            project.generated_tags = previous.generated_tags.clone();
+        }
+        self.tag_project(&mut project).await;
+
+        Ok(project)
+    }
+
+    /// Compute a project's embedding from its [`embedding_text`]
+    ///
+    /// A failure is logged and leaves the project without an embedding.
+    async fn embed_project(&self, project: &mut Project) {
+        let Some(client) = &self.config.ollama_client else {
+            return;
+        };
+        match self
+            .embedding_cache
+            .get_or_embed(client, &embedding_text(project))
+            .await
+        {
+            Ok(embedding) => project.embedding = Some(embedding),
+            Err(e) => tracing::warn!("Failed to embed {}: {}", project.name, e),
+        }
+    }
+
+    /// Compute the embeddings of projects that don't have one yet, sending
+    /// them to the server [`EMBED_BATCH_SIZE`] at a time
+    ///
+    /// A failed batch is logged and leaves its projects without an
+    /// embedding.
+    async fn embed_missing(&self, projects: &mut [Project]) {
+        let Some(client) = &self.config.ollama_client else {
+            return;
+        };
+        let mut missing: Vec<&mut Project> = projects
+            .iter_mut()
+            .filter(|project| project.embedding.is_none())
+            .collect();
+        for batch in missing.chunks_mut(EMBED_BATCH_SIZE) {
+            let texts: Vec<String> = batch
+                .iter()
+                .map(|project| embedding_text(project))
+                .collect();
+            match self
+                .embedding_cache
+                .get_or_embed_batch(client, &texts)
                 .await
             {
-                project.tags = tags;
+                Ok(embeddings) => {
+                    for (project, embedding) in batch.iter_mut().zip(embeddings) {
+                        project.embedding = Some(embedding);
+                    }
+                }
+                Err(e) => tracing::warn!("Failed to embed {} projects: {}", batch.len(), e),
             }
         }
+    }
 
-        Ok(project)
+    /// Write the embeddings computed so far next to the index file
+    ///
+    /// Does nothing unless `embed_projects` is set. Called by
+    /// [`index_projects`](Self::index_projects); callers of
+    /// [`stream`](Self::stream) save the cache once done.
+    pub fn save_embedding_cache(&self) -> Result<()> {
+        if !self.config.embed_projects || self.config.dry_run {
+            return Ok(());
+        }
+        self.embedding_cache
+            .save(&self.config.embedding_cache_file())
+    }
+
+    /// Replace a project's tags with freshly generated ones
+    ///
+    /// Tags from the project's `.tags` file are merged with tags generated by
+    /// Ollama, if enabled. With `preserve_manual_tags`, the project's current
+    /// manual tags are kept as well. Returns `false` if tag generation failed,
+    /// in which case the project only carries its file and manual tags.
+    pub async fn tag_project(&self, project: &mut Project) -> bool {
+        let manual_tags: Vec<String> = if self.config.preserve_manual_tags {
+            project.manual_tags().cloned().collect()
+        } else {
+            Vec::new()
+        };
+
+        // Tags committed alongside the project take part in tagging
+        let file_tags = read_tags_file(&project.path);
+        let prefer_file_tags = self.config.prefer_file_tags && !file_tags.is_empty();
+        project.tags = file_tags;
+        project.generated_tags.clear();
+        for tag in manual_tags {
+            if !project.tags.contains(&tag) {
+                project.tags.push(tag);
+            }
+        }
+        if prefer_file_tags {
+            return true;
+        }
+
+        // Generate tags if Ollama is enabled
+        let Some(client) = &self.config.ollama_client else {
+            return true;
+        };
+        let context = TagContext {
+            project: project.path.to_str().unwrap_or_default().to_string(),
+            // A flat or default category says nothing about the project
+            category: (!self.config.flatten_categories
+                && project.category != self.category_alias(self.config.default_category.clone()))
+            .then(|| project.category.clone()),
+            examples: self.config.tag_examples.clone(),
+            system_prompt: self.config.system_prompt.clone(),
+            scored: self.config.tag_min_confidence.is_some(),
+            commit_messages: match self.config.tag_from_commits {
+                Some(count) => {
+                    recent_commit_messages(&project.path, count, self.config.git_timeout).await
+                }
+                None => Vec::new(),
+            },
+            generation_context: None,
+        };
+        let context = TagContext {
+            generation_context: if self.config.reuse_generation_context {
+                self.primed_context(client, &context).await
+            } else {
+                None
+            },
+            ..context
+        };
+        let response = match client.generate_tags_response(&context).await {
+            Ok(response) => {
+                self.usage
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .record(&response);
+                response
+            }
+            Err(e) => {
+                tracing::warn!("Failed to generate tags for {}: {}", project.name, e);
+                self.apply_default_tags(project);
+                return false;
+            }
+        };
+
+        let truncated = response.is_truncated();
+        let response = response.response;
+
+        if let Some(dir) = &self.config.save_raw_responses {
+            let raw_path = dir.join(format!(
+                "{}.txt",
+                raw_response_name(&self.config.projects_dir, &project.path)
+            ));
+            if let Err(e) = fs::write(&raw_path, &response) {
+                tracing::warn!("Failed to save raw response to {:?}: {}", raw_path, e);
+            }
+        }
+        let mut tags = self
+            .config
+            .tag_min_confidence
+            .and_then(|min_confidence| parse_scored_tags(&response, min_confidence))
+            .unwrap_or_else(|| parse_tags_with(&response, self.config.tag_delimiter));
+        if truncated {
+            // The last tag may have been cut off mid-word
+            tracing::warn!(
+                "Tag generation for {} was cut short, dropping its last tag",
+                project.name
+            );
+            tags.pop();
+        }
+        for tag in tags {
+            if !project.tags.contains(&tag) {
+                project.tags.push(tag.clone());
+                project.generated_tags.push(tag);
+            }
+        }
+        self.apply_default_tags(project);
+        true
+    }
+
+    /// Context primed with the system prompt, requested on first use
+    ///
+    /// The category is left out so the context suits every project. If
+    /// priming fails, projects are tagged without a context.
+    async fn primed_context(
+        &self,
+        client: &OllamaClient,
+        context: &TagContext,
+    ) -> Option<Vec<u32>> {
+        self.generation_context
+            .get_or_init(|| async {
+                let base = TagContext {
+                    category: None,
+                    ..context.clone()
+                };
+                client.prime_context(&base).await.unwrap_or_else(|e| {
+                    tracing::warn!("Failed to prime the generation context: {}", e);
+                    None
+                })
+            })
+            .await
+            .clone()
     }
 
-    /// Detect project status based on git repository
-    async fn detect_git_status(&self, path: &Path) -> ProjectStatus {
-        // TODO: Implement git status detection
-        ProjectStatus::Unknown
+    /// Give an untagged project the configured default tags
+    ///
+    /// They count as generated, so they are replaced by the next successful
+    /// tagging run.
+    fn apply_default_tags(&self, project: &mut Project) {
+        if project.tags.is_empty() {
+            project.tags = self.config.default_tags.clone();
+            project.generated_tags = self.config.default_tags.clone();
+        }
+    }
+
+    /// Time of a project's latest git commit
+    ///
+    /// Returns `None` for projects that aren't git repositories, have no
+    /// commits, or whose `git log` exceeds `git_timeout`.
+    async fn last_commit(&self, path: &Path) -> Option<DateTime<Utc>> {
+        if !path.join(".git").exists() {
+            return None;
+        }
+        last_commit_time(path, self.config.git_timeout).await
+    }
+
+    /// Compute when a project was last modified, according to `mtime_source`
+    ///
+    /// `last_commit` is the project's [`last_commit`](Self::last_commit).
+    fn last_modified(
+        &self,
+        path: &Path,
+        exclude_dirs: &[String],
+        last_commit: Option<DateTime<Utc>>,
+    ) -> Option<DateTime<Utc>> {
+        match self.config.mtime_source {
+            MtimeSource::Git => last_commit.or_else(|| dir_mtime(path)),
+            MtimeSource::Dir => dir_mtime(path),
+            MtimeSource::Tree => tree_mtime(path, exclude_dirs),
+        }
     }
 
     /// Save project index to file
     fn save_index(&self, projects: &[Project]) -> Result<()> {
-        let json = serde_json::to_string_pretty(projects)
-            .map_err(|e| OllamaError::JsonError(e.to_string()))?;
-        fs::write(&self.config.index_file, json).map_err(|e| OllamaError::IoError(e))?;
-        Ok(())
+        super::save_index_as(&self.config.index_file, projects, self.config.output_format)
     }
 
     /// Search through indexed projects
+    ///
+    /// Matches the query against the names, tags and categories of the
+    /// projects in the index file, ignoring case and accents.
     pub async fn search_projects(&self, query: &str) -> Result<Vec<Project>> {
-        // TODO: Implement project search
-        Ok(Vec::new())
+        search_index(
+            &self.config.index_file,
+            query,
+            SearchFields::ALL,
+            false,
+            false,
+        )
     }
 
-    /// Get statistics about indexed projects
-    pub async fn get_statistics(&self) -> Result<ProjectStatistics> {
-        // TODO: Implement statistics calculation
-        Ok(ProjectStatistics {
-            total_projects: 0,
-            active_projects: 0,
-            archived_projects: 0,
-            projects_by_category: HashMap::new(),
-        })
+    /// Get statistics about the projects in the index file
+    pub async fn get_statistics(&self) -> Result<ProjectStats> {
+        Ok(ProjectStats::from_projects(&super::load_index(
+            &self.config.index_file,
+        )?))
     }
 
     /// Generate tags for a specific project
@@ -190,33 +1222,1353 @@ impl ProjectIndexer {
     }
 }
 
-/// Statistics about indexed projects
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ProjectStatistics {
-    pub total_projects: usize,
-    pub active_projects: usize,
-    pub archived_projects: usize,
-    pub projects_by_category: HashMap<String, usize>,
+/// File name, without extension, of a project's saved raw response
+///
+/// The project's path relative to the projects directory, with components
+/// joined by `__` and characters other than ASCII letters, digits, `-`,
+/// `_` and `.` replaced by `_`.
+fn raw_response_name(projects_dir: &Path, project: &Path) -> String {
+    let relative = project.strip_prefix(projects_dir).unwrap_or(project);
+    let name = relative
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(
+                part.to_string_lossy()
+                    .chars()
+                    .map(|c| {
+                        if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                            c
+                        } else {
+                            '_'
+                        }
+                    })
+                    .collect::<String>(),
+            ),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("__");
+    if name.is_empty() {
+        "project".to_string()
+    } else {
+        name
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use tempfile::tempdir;
+/// Name of a project directory, as reported to a [`ProgressReporter`]
+fn dir_name(path: &Path) -> &str {
+    path.file_name()
+        .unwrap_or_default()
+        .to_str()
+        .unwrap_or_default()
+}
 
-    #[tokio::test]
-    async fn test_index_projects() {
-        let temp_dir = tempdir().unwrap();
-        let config = IndexerConfig::new(
-            temp_dir.path().to_path_buf(),
-            temp_dir.path().join("index.json"),
-            3,
-            3,
-            ".git,node_modules".to_string(),
-        );
+/// Parse a comma-separated list of directory names to exclude
+///
+/// A list starting with `+`, e.g. `+dist,vendor`, adds to
+/// [`DEFAULT_EXCLUDE_DIRS`]; any other list replaces them. Names are
+/// trimmed, and empty entries, such as those left by a trailing comma, are
+/// dropped so they can't match every directory.
+pub fn parse_exclude_dirs(exclude: &str) -> Vec<String> {
+    let names = match exclude.trim_start().strip_prefix('+') {
+        Some(extra) => format!("{},{}", DEFAULT_EXCLUDE_DIRS, extra),
+        None => exclude.to_string(),
+    };
+    let mut dirs: Vec<String> = Vec::new();
+    for name in names
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    {
+        if !dirs.iter().any(|dir| dir == name) {
+            dirs.push(name.to_string());
+        }
+    }
+    dirs
+}
 
-        let indexer = ProjectIndexer::new(config, None);
-        let result = indexer.index_projects(|_| {}).await;
-        assert!(result.is_ok());
+/// Check whether a walked entry is a directory excluded from scanning
+pub(crate) fn is_excluded_dir(entry: &DirEntry, exclude_dirs: &[String]) -> bool {
+    entry.depth() > 0
+        && entry.file_type().is_dir()
+        && exclude_dirs
+            .iter()
+            .any(|dir| entry.file_name().to_string_lossy() == dir.as_str())
+}
+
+/// Classify a project by the time since its latest commit
+///
+/// Projects are `Active` up to [`ACTIVE_WINDOW_DAYS`], `Archived` after
+/// `archive_threshold_days` and `Stale` in between. A threshold within the
+/// active window leaves no room for stale projects.
+fn status_for_age(age: chrono::Duration, archive_threshold_days: u32) -> ProjectStatus {
+    let archive_threshold = chrono::Duration::days(i64::from(archive_threshold_days));
+    if age > archive_threshold {
+        ProjectStatus::Archived
+    } else if age <= chrono::Duration::days(ACTIVE_WINDOW_DAYS) {
+        ProjectStatus::Active
+    } else {
+        ProjectStatus::Stale
+    }
+}
+
+/// Time of the latest commit in a git repository
+async fn last_commit_time(path: &Path, timeout: Duration) -> Option<DateTime<Utc>> {
+    let output = run_git(path, &["log", "-1", "--format=%ct"], timeout).await?;
+    let timestamp = output.trim().parse::<i64>().ok()?;
+    Utc.timestamp_opt(timestamp, 0).single()
+}
+
+/// Subjects of the latest `count` commits in a git repository
+///
+/// At most [`MAX_COMMIT_MESSAGES`] are returned, each stripped of control
+/// characters and cut to [`MAX_COMMIT_MESSAGE_CHARS`]. Returns nothing for
+/// directories that aren't git repositories or have no commits.
+async fn recent_commit_messages(path: &Path, count: usize, timeout: Duration) -> Vec<String> {
+    let count = count.min(MAX_COMMIT_MESSAGES).to_string();
+    let Some(output) = run_git(path, &["log", "--format=%s", "-n", &count], timeout).await else {
+        return Vec::new();
+    };
+    output.lines().filter_map(sanitize_commit_message).collect()
+}
+
+/// Clean up a commit subject for inclusion in a prompt
+///
+/// Returns `None` for subjects that are empty once cleaned.
+fn sanitize_commit_message(subject: &str) -> Option<String> {
+    let cleaned = subject
+        .chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let cleaned: String = cleaned.chars().take(MAX_COMMIT_MESSAGE_CHARS).collect();
+    (!cleaned.is_empty()).then_some(cleaned)
+}
+
+/// Run a git command in a repository and return its standard output
+///
+/// Returns `None` if git fails or doesn't finish within `timeout`, in which
+/// case the child process is killed so it can't stall the indexing run.
+async fn run_git(path: &Path, args: &[&str], timeout: Duration) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(args)
+        .kill_on_drop(true)
+        .output();
+
+    let output = match tokio::time::timeout(timeout, output).await {
+        Ok(output) => output.ok()?,
+        Err(_) => {
+            tracing::warn!(
+                "git {} timed out after {:?} in {:?}",
+                args.join(" "),
+                timeout,
+                path
+            );
+            return None;
+        }
+    };
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Check whether any of `changed_files` lies inside a project directory
+fn has_changed_files(project: &Path, changed_files: &[PathBuf]) -> bool {
+    let Ok(project) = project.canonicalize() else {
+        return true;
+    };
+    changed_files.iter().any(|file| file.starts_with(&project))
+}
+
+/// Detect a project's status
+///
+/// An `ARCHIVED.md` marker archives a project by hand. Otherwise git
+/// repositories get their status from the age of their latest commit, see
+/// [`status_for_age`]. Repositories without commits and other directories
+/// are `Unknown`.
+fn detect_status(
+    path: &Path,
+    last_commit: Option<DateTime<Utc>>,
+    archive_threshold_days: u32,
+) -> ProjectStatus {
+    if has_archived_marker(path) {
+        return ProjectStatus::Archived;
+    }
+    match last_commit {
+        Some(time) => status_for_age(Utc::now() - time, archive_threshold_days),
+        None => ProjectStatus::Unknown,
+    }
+}
+
+/// Filesystem modification time of a directory
+fn dir_mtime(path: &Path) -> Option<DateTime<Utc>> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    Some(modified.into())
+}
+
+/// Newest modification time among the files of a project
+fn tree_mtime(path: &Path, exclude_dirs: &[String]) -> Option<DateTime<Utc>> {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_entry(|e| !is_excluded_dir(e, exclude_dirs))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok()?.modified().ok())
+        .max()
+        .map(Into::into)
+}
+
+/// Sum the size of the files in a project directory
+///
+/// Directories whose name appears in `exclude_dirs` are skipped. The walk
+/// stops after `max_files` files, in which case the returned size covers only
+/// the files seen so far and the second element is `true`.
+fn measure_project_size(path: &Path, exclude_dirs: &[String], max_files: u64) -> (u64, bool) {
+    let mut size_bytes = 0;
+
+    for (file_count, entry) in WalkDir::new(path)
+        .into_iter()
+        .filter_entry(|e| !is_excluded_dir(e, exclude_dirs))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .enumerate()
+    {
+        if file_count as u64 >= max_files {
+            return (size_bytes, true);
+        }
+        size_bytes += entry.metadata().map(|m| m.len()).unwrap_or(0);
+    }
+
+    (size_bytes, false)
+}
+
+/// Read the tags listed in a project's `.tags` file
+///
+/// The file may separate tags with newlines, commas, or both. Tags are
+/// trimmed, lowercased and deduplicated. A missing or unreadable file yields
+/// no tags.
+fn read_tags_file(path: &Path) -> Vec<String> {
+    let Ok(contents) = fs::read_to_string(path.join(".tags")) else {
+        return Vec::new();
+    };
+
+    let mut tags: Vec<String> = Vec::new();
+    for tag in contents
+        .lines()
+        .flat_map(|line| line.split(','))
+        .map(|tag| tag.trim().to_lowercase())
+        .filter(|tag| !tag.is_empty())
+    {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// Name of the directory `depth` levels above a project, below `projects_dir`
+///
+/// Projects with no such directory, such as direct children of
+/// `projects_dir`, get `default_category`.
+fn project_category(
+    path: &Path,
+    projects_dir: &Path,
+    depth: usize,
+    default_category: &str,
+) -> String {
+    let relative = path.strip_prefix(projects_dir).unwrap_or(path);
+    let components: Vec<&str> = relative
+        .components()
+        .filter_map(|c| c.as_os_str().to_str())
+        .collect();
+
+    if depth == 0 || components.len() <= depth {
+        return default_category.to_string();
+    }
+    components[components.len() - 1 - depth].to_string()
+}
+
+/// Compose file names recognized by Docker Compose
+const COMPOSE_FILES: &[&str] = &[
+    "compose.yaml",
+    "compose.yml",
+    "docker-compose.yaml",
+    "docker-compose.yml",
+];
+
+/// Check whether a project's root holds an `ARCHIVED.md` file, in any case
+fn has_archived_marker(path: &Path) -> bool {
+    fs::read_dir(path).is_ok_and(|entries| {
+        entries
+            .filter_map(|e| e.ok())
+            .any(|entry| entry.file_name().eq_ignore_ascii_case("ARCHIVED.md"))
+    })
+}
+
+/// Check a project's root for a Dockerfile and a Docker Compose file
+///
+/// Variants such as `Dockerfile.dev` and `app.Dockerfile` count as Dockerfiles.
+fn detect_container_files(path: &Path) -> (bool, bool) {
+    let Ok(entries) = fs::read_dir(path) else {
+        return (false, false);
+    };
+
+    let (mut has_dockerfile, mut has_compose) = (false, false);
+    for entry in entries.filter_map(|e| e.ok()) {
+        let name = entry.file_name();
+        let Some(name) = name.to_str() else {
+            continue;
+        };
+        has_dockerfile |= name == "Dockerfile"
+            || name.starts_with("Dockerfile.")
+            || name.ends_with(".Dockerfile");
+        has_compose |= COMPOSE_FILES.contains(&name);
+    }
+    (has_dockerfile, has_compose)
+}
+
+/// Directory names that hold tests by convention
+const TEST_DIRS: &[&str] = &["tests", "test", "spec", "specs", "__tests__"];
+
+/// Check whether a project appears to contain tests
+///
+/// Looks for a test directory (`tests/`, `spec/`, `__tests__/`, ...) or a
+/// file named like a test: `test_*.py`, `*_test.go`, `*.test.ts`,
+/// `*.spec.js`, `*_spec.rb`. At most `max_files` files are inspected.
+fn detect_tests(path: &Path, exclude_dirs: &[String], max_files: u64) -> bool {
+    let mut file_count = 0;
+    for entry in WalkDir::new(path)
+        .into_iter()
+        .filter_entry(|e| !is_excluded_dir(e, exclude_dirs))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.depth() > 0)
+    {
+        let name = entry.file_name().to_string_lossy();
+        if entry.file_type().is_dir() {
+            if TEST_DIRS.contains(&name.as_ref()) {
+                return true;
+            }
+            continue;
+        }
+
+        file_count += 1;
+        if file_count > max_files {
+            return false;
+        }
+        if is_test_file_name(&name) {
+            return true;
+        }
+    }
+    false
+}
+
+/// Check whether a file name follows a common test naming convention
+fn is_test_file_name(name: &str) -> bool {
+    let Some((stem, _extension)) = name.rsplit_once('.') else {
+        return false;
+    };
+    stem.starts_with("test_")
+        || stem.ends_with("_test")
+        || stem.ends_with("_spec")
+        || stem.ends_with(".test")
+        || stem.ends_with(".spec")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexer::{load_index, save_index, NoopReporter};
+    use tempfile::tempdir;
+
+    /// Config indexing `dir` at `depth`, with the index file inside it
+    fn test_config(dir: &Path, depth: u32) -> IndexerConfig {
+        IndexerConfig::new(
+            dir.to_path_buf(),
+            dir.join("index.json"),
+            depth,
+            depth,
+            "node_modules".to_string(),
+        )
+    }
+
+    /// Run git in `repo` as a test user, failing the test if it fails
+    fn git(repo: &Path, args: &[&str]) {
+        git_with_env(repo, args, &[]);
+    }
+
+    fn git_with_env(repo: &Path, args: &[&str], envs: &[(&str, &str)]) {
+        let output = std::process::Command::new("git")
+            .arg("-C")
+            .arg(repo)
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .envs(envs.iter().copied())
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_index_projects() {
+        let temp_dir = tempdir().unwrap();
+        let config = IndexerConfig::new(
+            temp_dir.path().to_path_buf(),
+            temp_dir.path().join("index.json"),
+            3,
+            3,
+            ".git,node_modules".to_string(),
+        );
+
+        let indexer = ProjectIndexer::new(config, None);
+        let result = indexer.index_projects(&NoopReporter).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_get_statistics() {
+        let temp_dir = tempdir().unwrap();
+        fs::create_dir_all(temp_dir.path().join("work").join("tool")).unwrap();
+        fs::create_dir_all(temp_dir.path().join("work").join("web")).unwrap();
+        let mut config = test_config(temp_dir.path(), 2);
+        config.exclude = String::new();
+
+        let indexer = ProjectIndexer::new(config, None);
+        let projects = indexer.index_projects(&NoopReporter).await.unwrap();
+        let stats = indexer.get_statistics().await.unwrap();
+        assert_eq!(stats, ProjectStats::from_projects(&projects));
+        assert_eq!(stats.total_projects, 2);
+        assert_eq!(stats.projects_by_category["work"], 2);
+    }
+
+    #[derive(Default)]
+    struct RecordingReporter {
+        events: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl ProgressReporter for RecordingReporter {
+        fn on_scan_start(&self) {
+            self.events.lock().unwrap().push("start".to_string());
+        }
+
+        fn on_scan_complete(&self, total: usize) {
+            self.events.lock().unwrap().push(format!("total:{}", total));
+        }
+
+        fn on_project(&self, name: &str) {
+            self.events.lock().unwrap().push(name.to_string());
+        }
+
+        fn on_finish(&self, count: usize) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("finish:{}", count));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_index_projects_reports_progress() {
+        let temp_dir = tempdir().unwrap();
+        fs::create_dir_all(temp_dir.path().join("tools").join("cli").join("my-project")).unwrap();
+        let mut config = test_config(temp_dir.path(), 3);
+        config.exclude = ".git,node_modules".to_string();
+
+        let reporter = RecordingReporter::default();
+        let indexer = ProjectIndexer::new(config, None);
+        indexer.index_projects(&reporter).await.unwrap();
+
+        assert_eq!(
+            *reporter.events.lock().unwrap(),
+            vec!["start", "total:1", "my-project", "finish:1"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_index_projects_concurrently() {
+        let temp_dir = tempdir().unwrap();
+        for project in ["b/tool", "a/tool", "a/app", "c/web", "b/api", "a/db"] {
+            fs::create_dir_all(temp_dir.path().join(project)).unwrap();
+        }
+        let mut config = test_config(temp_dir.path(), 2);
+
+        config.max_concurrency = 1;
+        let sequential = ProjectIndexer::new(config.clone(), None)
+            .index_projects(&NoopReporter)
+            .await
+            .unwrap();
+        config.max_concurrency = 8;
+        let reporter = RecordingReporter::default();
+        let concurrent = ProjectIndexer::new(config, None)
+            .index_projects(&reporter)
+            .await
+            .unwrap();
+
+        let paths = |projects: &[Project]| -> Vec<PathBuf> {
+            projects.iter().map(|p| p.path.clone()).collect()
+        };
+        assert_eq!(paths(&concurrent), paths(&sequential));
+        assert_eq!(concurrent[0].path, temp_dir.path().join("a/app"));
+        // One progress event per project, between the totals
+        assert_eq!(reporter.events.lock().unwrap().len(), 6 + 3);
+    }
+
+    #[tokio::test]
+    async fn test_index_projects_incremental() {
+        let temp_dir = tempdir().unwrap();
+        let (tool, web) = (temp_dir.path().join("tool"), temp_dir.path().join("web"));
+        fs::create_dir_all(&tool).unwrap();
+        fs::create_dir_all(&web).unwrap();
+        let index_file = temp_dir.path().join("index.json");
+
+        let mut unchanged = Project::new("tool".to_string(), tool.clone());
+        unchanged.tags = vec!["cli".to_string()];
+        unchanged.last_modified = dir_mtime(&tool).unwrap();
+        let mut changed = Project::new("web".to_string(), web.clone());
+        changed.tags = vec!["stale-tag".to_string()];
+        changed.last_modified = Utc.timestamp_opt(1, 0).unwrap();
+        save_index(&index_file, &[unchanged, changed]).unwrap();
+
+        let mut config = test_config(temp_dir.path(), 1);
+        config.mtime_source = MtimeSource::Dir;
+        config.incremental = true;
+        let reporter = RecordingReporter::default();
+        let projects = ProjectIndexer::new(config, None)
+            .index_projects(&reporter)
+            .await
+            .unwrap();
+
+        let tags: Vec<(&str, Vec<String>)> = projects
+            .iter()
+            .map(|p| (p.name.as_str(), p.tags.clone()))
+            .collect();
+        assert_eq!(
+            tags,
+            vec![("tool", vec!["cli".to_string()]), ("web", Vec::new())]
+        );
+        assert!(reporter
+            .events
+            .lock()
+            .unwrap()
+            .contains(&"total:1".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_index_projects_exclude_paths() {
+        let temp_dir = tempdir().unwrap();
+        fs::create_dir_all(temp_dir.path().join("work").join("rust").join("tool")).unwrap();
+        fs::create_dir_all(temp_dir.path().join("archive").join("rust").join("old")).unwrap();
+        let mut config = test_config(temp_dir.path(), 3);
+        config.exclude_paths = vec![PathGlob::new("**/archive/**").unwrap()];
+
+        let indexer = ProjectIndexer::new(config, None);
+        let projects = indexer.index_projects(&NoopReporter).await.unwrap();
+        let names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["tool"]);
+    }
+
+    #[tokio::test]
+    async fn test_index_projects_exclude_dir_names() {
+        let temp_dir = tempdir().unwrap();
+        // Excluded names only apply below the projects directory
+        let root = temp_dir.path().join("target");
+        for project in ["work/distributed", "work/dist", "dist/tool"] {
+            fs::create_dir_all(root.join(project)).unwrap();
+        }
+        let mut config = test_config(&root, 2);
+        config.index_file = temp_dir.path().join("index.json");
+        config.exclude = "+dist".to_string();
+
+        let indexer = ProjectIndexer::new(config, None);
+        let projects = indexer.index_projects(&NoopReporter).await.unwrap();
+        let names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["distributed"]);
+    }
+
+    #[tokio::test]
+    async fn test_index_projects_resume() {
+        let temp_dir = tempdir().unwrap();
+        for project in ["work/done", "work/todo"] {
+            fs::create_dir_all(temp_dir.path().join(project)).unwrap();
+        }
+        let index_file = temp_dir.path().join("index.json");
+
+        // An interrupted run had already tagged `done`
+        let mut done = Project::new("done".to_string(), temp_dir.path().join("work/done"));
+        done.category = "work".to_string();
+        done.tags = vec!["from-ledger".to_string()];
+        let (ledger, _) = JobLedger::open(&index_file, false).unwrap();
+        ledger.record(&done).unwrap();
+        drop(ledger);
+
+        let mut config = test_config(temp_dir.path(), 2);
+        config.resume = true;
+        let reporter = RecordingReporter::default();
+        let projects = ProjectIndexer::new(config, None)
+            .index_projects(&reporter)
+            .await
+            .unwrap();
+
+        assert_eq!(projects.len(), 2);
+        assert_eq!(projects[0].tags, vec!["from-ledger"]);
+        assert!(projects[1].tags.is_empty());
+        assert_eq!(
+            *reporter.events.lock().unwrap(),
+            vec!["start", "total:1", "todo", "finish:2"]
+        );
+        assert!(!crate::indexer::job::job_ledger_path(&index_file).exists());
+    }
+
+    #[tokio::test]
+    async fn test_index_projects_parallel_walk() {
+        let temp_dir = tempdir().unwrap();
+        for project in [
+            "a/one",
+            "a/two",
+            "b/three",
+            "c/nested/four",
+            "node_modules/dep",
+        ] {
+            fs::create_dir_all(temp_dir.path().join(project)).unwrap();
+        }
+
+        let mut config = test_config(temp_dir.path(), 3);
+        config.min_depth = 1;
+        let sequential = ProjectIndexer::new(config.clone(), None)
+            .discover_projects()
+            .unwrap();
+        config.parallel_walk = true;
+        let parallel = ProjectIndexer::new(config.clone(), None)
+            .discover_projects()
+            .unwrap();
+
+        assert_eq!(sequential.len(), 8);
+        assert_eq!(parallel, sequential);
+
+        config.min_depth = 2;
+        let parallel = ProjectIndexer::new(config.clone(), None)
+            .discover_projects()
+            .unwrap();
+        config.parallel_walk = false;
+        let sequential = ProjectIndexer::new(config, None)
+            .discover_projects()
+            .unwrap();
+        assert_eq!(sequential.len(), 5);
+        assert_eq!(parallel, sequential);
+    }
+
+    #[tokio::test]
+    async fn test_index_projects_dry_run() {
+        let temp_dir = tempdir().unwrap();
+        let projects_dir = temp_dir.path().join("projects");
+        fs::create_dir_all(projects_dir.join("work/tool")).unwrap();
+        fs::write(projects_dir.join("work/tool/.tags"), "rust\n").unwrap();
+        let index_file = temp_dir.path().join("index.json");
+
+        let mut config = test_config(&projects_dir, 2);
+        config.index_file = index_file.clone();
+        config.dry_run = true;
+        let projects = ProjectIndexer::new(config, None)
+            .index_projects(&NoopReporter)
+            .await
+            .unwrap();
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].category, "work");
+        assert!(projects[0].tags.is_empty());
+        assert!(!index_file.exists());
+        assert!(!crate::indexer::job::job_ledger_path(&index_file).exists());
+    }
+
+    #[tokio::test]
+    async fn test_index_projects_depth_range() {
+        let temp_dir = tempdir().unwrap();
+        for project in ["tool", "app", "work/nested"] {
+            fs::create_dir_all(temp_dir.path().join(project)).unwrap();
+        }
+
+        // A flat directory of projects is indexed at depth 1
+        let mut config = test_config(temp_dir.path(), 1);
+        let candidates = ProjectIndexer::new(config.clone(), None)
+            .discover_projects()
+            .unwrap();
+        assert_eq!(candidates.len(), 3);
+
+        config.min_depth = 2;
+        let error = ProjectIndexer::new(config, None)
+            .index_projects(&NoopReporter)
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind(), "validation");
+    }
+
+    #[tokio::test]
+    async fn test_index_projects_category_from_manifest() {
+        let temp_dir = tempdir().unwrap();
+        let tool = temp_dir.path().join("work").join("tool");
+        let script = temp_dir.path().join("work").join("script");
+        let empty = temp_dir.path().join("work").join("empty");
+        fs::create_dir_all(&tool).unwrap();
+        fs::create_dir_all(&script).unwrap();
+        fs::create_dir_all(&empty).unwrap();
+        fs::write(tool.join("Cargo.toml"), "[package]").unwrap();
+        fs::write(script.join("run.py"), "print(1)\n").unwrap();
+
+        let mut config = test_config(temp_dir.path(), 2);
+        config.category_from_manifest = true;
+
+        let indexer = ProjectIndexer::new(config, None);
+        let projects = indexer.index_projects(&NoopReporter).await.unwrap();
+        let mut categories: Vec<(&str, &str)> = projects
+            .iter()
+            .map(|p| (p.name.as_str(), p.category.as_str()))
+            .collect();
+        categories.sort();
+        assert_eq!(
+            categories,
+            vec![
+                ("empty", "uncategorized"),
+                ("script", "python"),
+                ("tool", "rust")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_detect_tests() {
+        let temp_dir = tempdir().unwrap();
+        let project = temp_dir.path();
+        fs::create_dir_all(project.join("src")).unwrap();
+        fs::write(project.join("src").join("main.rs"), "").unwrap();
+        fs::create_dir_all(project.join("node_modules").join("dep")).unwrap();
+        fs::write(
+            project.join("node_modules").join("dep").join("a.test.js"),
+            "",
+        )
+        .unwrap();
+        let exclude = vec!["node_modules".to_string()];
+        assert!(!detect_tests(project, &exclude, 100));
+
+        fs::write(project.join("src").join("parser_test.go"), "").unwrap();
+        assert!(detect_tests(project, &exclude, 100));
+
+        assert!(is_test_file_name("test_models.py"));
+        assert!(is_test_file_name("button.spec.tsx"));
+        assert!(is_test_file_name("user_spec.rb"));
+        assert!(!is_test_file_name("latest.rs"));
+        assert!(!is_test_file_name("test_"));
+
+        let temp_dir = tempdir().unwrap();
+        fs::create_dir_all(temp_dir.path().join("tests")).unwrap();
+        assert!(detect_tests(temp_dir.path(), &exclude, 100));
+    }
+
+    #[tokio::test]
+    async fn test_index_projects_flatten_categories() {
+        let temp_dir = tempdir().unwrap();
+        fs::create_dir_all(temp_dir.path().join("ml").join("model")).unwrap();
+        fs::create_dir_all(temp_dir.path().join("ops").join("infra")).unwrap();
+
+        let mut config = test_config(temp_dir.path(), 2);
+        config.flatten_categories = true;
+
+        let indexer = ProjectIndexer::new(config, None);
+        let projects = indexer.index_projects(&NoopReporter).await.unwrap();
+        let categories: Vec<(&str, &str)> = projects
+            .iter()
+            .map(|p| (p.name.as_str(), p.category.as_str()))
+            .collect();
+        assert_eq!(categories, vec![("infra", "all"), ("model", "all")]);
+    }
+
+    #[tokio::test]
+    async fn test_index_projects_category_aliases() {
+        let temp_dir = tempdir().unwrap();
+        fs::create_dir_all(temp_dir.path().join("ml").join("model")).unwrap();
+        fs::create_dir_all(temp_dir.path().join("ops").join("infra")).unwrap();
+
+        let mut config = test_config(temp_dir.path(), 2);
+        config.category_aliases =
+            HashMap::from([("ml".to_string(), "Machine Learning".to_string())]);
+
+        let indexer = ProjectIndexer::new(config, None);
+        let projects = indexer.index_projects(&NoopReporter).await.unwrap();
+        let categories: Vec<(&str, &str)> = projects
+            .iter()
+            .map(|p| (p.name.as_str(), p.category.as_str()))
+            .collect();
+        assert_eq!(
+            categories,
+            vec![("model", "Machine Learning"), ("infra", "ops")]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_index_projects_name_overrides() {
+        let temp_dir = tempdir().unwrap();
+        let tool = temp_dir.path().join("work").join("tool");
+        fs::create_dir_all(&tool).unwrap();
+        fs::create_dir_all(temp_dir.path().join("work").join("other")).unwrap();
+
+        let mut config = test_config(temp_dir.path(), 2);
+        config.name_overrides = NameOverrides::from([(tool, "My Tool".to_string())]);
+
+        let indexer = ProjectIndexer::new(config, None);
+        let projects = indexer.index_projects(&NoopReporter).await.unwrap();
+        let names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["My Tool", "other"]);
+    }
+
+    #[tokio::test]
+    async fn test_index_projects_since_commit() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path().join("repo");
+        for project in ["changed", "unchanged", "new"] {
+            fs::create_dir_all(root.join("work").join(project)).unwrap();
+            fs::write(root.join("work").join(project).join("README"), "v1").unwrap();
+        }
+        git(&root, &["init", "-q"]);
+        git(&root, &["add", "."]);
+        git(&root, &["commit", "-q", "-m", "initial"]);
+
+        let mut config = test_config(&root, 2);
+        config.index_file = temp_dir.path().join("index.json");
+        config.exclude = ".git,node_modules".to_string();
+        let mut unchanged =
+            Project::new("unchanged".to_string(), root.join("work").join("unchanged"));
+        unchanged.category = "work".to_string();
+        unchanged.tags = vec!["kept".to_string()];
+        save_index(&config.index_file, &[unchanged]).unwrap();
+
+        fs::write(root.join("work").join("changed").join("README"), "v2").unwrap();
+        git(&root, &["commit", "-q", "-am", "update"]);
+        config.since_commit = Some("HEAD~1".to_string());
+
+        let indexer = ProjectIndexer::new(config.clone(), None);
+        let projects = indexer.index_projects(&NoopReporter).await.unwrap();
+        let names: Vec<(&str, usize)> = projects
+            .iter()
+            .map(|p| (p.name.as_str(), p.tags.len()))
+            .collect();
+        assert_eq!(names, vec![("changed", 0), ("new", 0), ("unchanged", 1)]);
+
+        // Outside a git repository every project is indexed
+        config.projects_dir = root.join("work");
+        fs::remove_dir_all(root.join(".git")).unwrap();
+        config.min_depth = 1;
+        config.max_depth = 1;
+        let indexer = ProjectIndexer::new(config, None);
+        let projects = indexer.index_projects(&NoopReporter).await.unwrap();
+        assert_eq!(projects.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_index_projects_skips_index_dir() {
+        let temp_dir = tempdir().unwrap();
+        fs::create_dir_all(temp_dir.path().join("work").join("tool")).unwrap();
+        fs::create_dir_all(temp_dir.path().join("work").join("out")).unwrap();
+        let mut config = test_config(temp_dir.path(), 2);
+        config.index_file = temp_dir.path().join("work").join("out").join("index.json");
+
+        let projects = ProjectIndexer::new(config, None)
+            .index_projects(&NoopReporter)
+            .await
+            .unwrap();
+        let names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["tool"]);
+    }
+
+    #[tokio::test]
+    async fn test_index_projects_preserve_manual_tags() {
+        let temp_dir = tempdir().unwrap();
+        let tool = temp_dir.path().join("work").join("tool");
+        fs::create_dir_all(&tool).unwrap();
+        fs::write(tool.join(".tags"), "cli").unwrap();
+        let index_file = temp_dir.path().join("index.json");
+        let mut previous = Project::new("tool".to_string(), tool.clone());
+        previous.tags = vec!["cli".into(), "mine".into(), "generated".into()];
+        previous.generated_tags = vec!["generated".into()];
+        save_index(&index_file, &[previous]).unwrap();
+
+        let config = |preserve_manual_tags| {
+            let mut config = test_config(temp_dir.path(), 2);
+            config.preserve_manual_tags = preserve_manual_tags;
+            config
+        };
+
+        let projects = ProjectIndexer::new(config(true), None)
+            .index_projects(&NoopReporter)
+            .await
+            .unwrap();
+        assert_eq!(projects[0].tags, vec!["cli", "mine"]);
+
+        let projects = ProjectIndexer::new(config(false), None)
+            .index_projects(&NoopReporter)
+            .await
+            .unwrap();
+        assert_eq!(projects[0].tags, vec!["cli"]);
+    }
+
+    #[tokio::test]
+    async fn test_stream() {
+        let temp_dir = tempdir().unwrap();
+        fs::create_dir_all(temp_dir.path().join("work").join("tool")).unwrap();
+        fs::create_dir_all(temp_dir.path().join("games").join("pong")).unwrap();
+        let index_file = temp_dir.path().join("index.json");
+        let config = test_config(temp_dir.path(), 2);
+
+        let indexer = ProjectIndexer::new(config, None);
+        let reporter = RecordingReporter::default();
+        let projects: Vec<Project> = indexer
+            .stream(&reporter)
+            .map(|project| project.unwrap())
+            .collect()
+            .await;
+        let mut names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, vec!["pong", "tool"]);
+        assert!(!index_file.exists());
+        let events = reporter.events.lock().unwrap();
+        assert_eq!(events[..2], ["start", "total:2"]);
+        assert_eq!(events.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_index_projects_git_only() {
+        let temp_dir = tempdir().unwrap();
+        let category = temp_dir.path().join("work").join("rust");
+        fs::create_dir_all(category.join("repo").join(".git")).unwrap();
+        fs::create_dir_all(category.join("scratch")).unwrap();
+        let mut config = test_config(temp_dir.path(), 3);
+        config.git_only = true;
+
+        let indexer = ProjectIndexer::new(config, None);
+        let projects = indexer.index_projects(&NoopReporter).await.unwrap();
+        let names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["repo"]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_index_projects_skips_non_utf8_paths() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        let temp_dir = tempdir().unwrap();
+        let invalid = temp_dir.path().join(OsStr::from_bytes(b"caf\xe9"));
+        fs::create_dir_all(temp_dir.path().join("work").join("tool")).unwrap();
+        fs::create_dir_all(invalid.join("app")).unwrap();
+        let index_file = temp_dir.path().join("index.json");
+        let config = test_config(temp_dir.path(), 2);
+
+        let projects = ProjectIndexer::new(config, None)
+            .index_projects(&NoopReporter)
+            .await
+            .unwrap();
+        let names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["tool"]);
+        assert_eq!(load_index(&index_file).unwrap().len(), 1);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_index_projects_skips_unreadable_dirs() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempdir().unwrap();
+        let locked = temp_dir.path().join("locked");
+        fs::create_dir_all(temp_dir.path().join("open").join("rust").join("tool")).unwrap();
+        fs::create_dir_all(locked.join("inner")).unwrap();
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).unwrap();
+        if fs::read_dir(&locked).is_ok() {
+            // Permissions are not enforced for root, so there is nothing to test
+            eprintln!("skipping test_index_projects_skips_unreadable_dirs: running as root");
+            return;
+        }
+
+        let config = |abort_on_error| {
+            let mut config = test_config(temp_dir.path(), 3);
+            config.abort_on_error = abort_on_error;
+            config
+        };
+        let best_effort = ProjectIndexer::new(config(false), None)
+            .index_projects(&NoopReporter)
+            .await;
+        let strict = ProjectIndexer::new(config(true), None)
+            .index_projects(&NoopReporter)
+            .await;
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+
+        assert_eq!(best_effort.unwrap().len(), 1);
+        assert!(strict.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_tag_project_merges_file_tags() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(temp_dir.path().join(".tags"), "rust").unwrap();
+        let mut ollama = crate::testing::MockOllama::start().await;
+        ollama.mock_generate("Bevy, rust, ECS").await;
+
+        let config = IndexerConfig::new(PathBuf::new(), PathBuf::new(), 0, 0, String::new());
+        let indexer = ProjectIndexer::new(config, Some(ollama.client().unwrap()));
+        let mut project = Project::new("game".to_string(), temp_dir.path().to_path_buf());
+
+        assert!(indexer.tag_project(&mut project).await);
+        assert_eq!(project.tags, vec!["rust", "bevy", "ecs"]);
+    }
+
+    #[tokio::test]
+    async fn test_index_projects_embed_projects() {
+        let temp_dir = tempdir().unwrap();
+        fs::create_dir_all(temp_dir.path().join("work").join("tool")).unwrap();
+        let index_file = temp_dir.path().join("index.json");
+        let mut ollama = crate::testing::MockOllama::start().await;
+        ollama.mock_generate("rust, cli").await;
+        ollama.mock_embed_batch(&[vec![0.5, -1.0]]).await;
+
+        let mut config = test_config(temp_dir.path(), 2);
+        config.exclude = String::new();
+        config.embed_projects = true;
+        let indexer = ProjectIndexer::new(config.clone(), Some(ollama.client().unwrap()));
+        let projects = indexer.index_projects(&NoopReporter).await.unwrap();
+
+        assert_eq!(projects[0].embedding, Some(vec![0.5, -1.0]));
+        let cache = EmbeddingCache::load(&embedding_cache_path(&index_file), 16).unwrap();
+        assert_eq!(cache.len(), 1);
+
+        // Re-indexing reuses the cached embedding of the unchanged project
+        let cache_file = temp_dir.path().join("embeddings.json");
+        fs::copy(embedding_cache_path(&index_file), &cache_file).unwrap();
+        config.embedding_cache = Some(cache_file);
+        let mut ollama = crate::testing::MockOllama::start().await;
+        ollama.mock_generate("rust, cli").await;
+        let indexer = ProjectIndexer::new(config, Some(ollama.client().unwrap()));
+        let projects = indexer.index_projects(&NoopReporter).await.unwrap();
+        assert_eq!(projects[0].embedding, Some(vec![0.5, -1.0]));
+    }
+
+    #[tokio::test]
+    async fn test_tag_project_tag_delimiter() {
+        let temp_dir = tempdir().unwrap();
+        let mut ollama = crate::testing::MockOllama::start().await;
+        ollama.mock_generate("- Rust, 2021 edition\n- CLI").await;
+
+        let mut config = IndexerConfig::new(PathBuf::new(), PathBuf::new(), 0, 0, String::new());
+        config.tag_delimiter = TagDelimiter::Newline;
+        let indexer = ProjectIndexer::new(config, Some(ollama.client().unwrap()));
+        let mut project = Project::new("app".to_string(), temp_dir.path().to_path_buf());
+
+        assert!(indexer.tag_project(&mut project).await);
+        assert_eq!(project.tags, vec!["rust, 2021 edition", "cli"]);
+    }
+
+    #[tokio::test]
+    async fn test_tag_project_drops_truncated_tag() {
+        let temp_dir = tempdir().unwrap();
+        let mut ollama = crate::testing::MockOllama::start().await;
+        let _mock = ollama
+            .server()
+            .mock("POST", "/api/generate")
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"response": "rust, cli, asyn", "done": true, "done_reason": "length"}"#)
+            .create_async()
+            .await;
+
+        let config = IndexerConfig::new(PathBuf::new(), PathBuf::new(), 0, 0, String::new());
+        let indexer = ProjectIndexer::new(config, Some(ollama.client().unwrap()));
+        let mut project = Project::new("app".to_string(), temp_dir.path().to_path_buf());
+
+        assert!(indexer.tag_project(&mut project).await);
+        assert_eq!(project.tags, vec!["rust", "cli"]);
+    }
+
+    #[tokio::test]
+    async fn test_tag_project_default_tags() {
+        let temp_dir = tempdir().unwrap();
+        let client = OllamaClient::new(crate::ollama::ClientConfig {
+            base_url: "http://127.0.0.1:1".to_string(),
+            max_retries: 0,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let mut config = IndexerConfig::new(PathBuf::new(), PathBuf::new(), 0, 0, String::new());
+        config.default_tags = vec!["rust".to_string(), "cli".to_string()];
+        let indexer = ProjectIndexer::new(config, Some(client.clone()));
+        let mut project = Project::new("app".to_string(), temp_dir.path().to_path_buf());
+        assert!(!indexer.tag_project(&mut project).await);
+        assert_eq!(project.tags, vec!["rust", "cli"]);
+
+        let config = IndexerConfig::new(PathBuf::new(), PathBuf::new(), 0, 0, String::new());
+        let indexer = ProjectIndexer::new(config, Some(client));
+        let mut project = Project::new("app".to_string(), temp_dir.path().to_path_buf());
+        assert!(!indexer.tag_project(&mut project).await);
+        assert!(project.tags.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_tag_project_reports_failure() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(temp_dir.path().join(".tags"), "rust").unwrap();
+        let client = OllamaClient::new(crate::ollama::ClientConfig {
+            base_url: "http://127.0.0.1:1".to_string(),
+            max_retries: 0,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let config = IndexerConfig::new(PathBuf::new(), PathBuf::new(), 0, 0, String::new());
+        let indexer = ProjectIndexer::new(config, Some(client));
+        let mut project = Project::new("app".to_string(), temp_dir.path().to_path_buf());
+
+        assert!(!indexer.tag_project(&mut project).await);
+        assert_eq!(project.tags, vec!["rust"]);
+    }
+
+    #[test]
+    fn test_measure_project_size_truncates() {
+        let temp_dir = tempdir().unwrap();
+        for i in 0..5 {
+            fs::write(temp_dir.path().join(format!("file{}.txt", i)), "1234").unwrap();
+        }
+        fs::create_dir(temp_dir.path().join("target")).unwrap();
+        fs::write(
+            temp_dir.path().join("target").join("big.bin"),
+            "x".repeat(100),
+        )
+        .unwrap();
+
+        let excludes = vec!["target".to_string()];
+        assert_eq!(
+            measure_project_size(temp_dir.path(), &excludes, 10),
+            (20, false)
+        );
+        assert_eq!(
+            measure_project_size(temp_dir.path(), &excludes, 3),
+            (12, true)
+        );
+    }
+
+    #[test]
+    fn test_tree_mtime_honors_excludes() {
+        let temp_dir = tempdir().unwrap();
+        let old = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000);
+        let new = std::time::UNIX_EPOCH + std::time::Duration::from_secs(2_000_000);
+        let newest = std::time::UNIX_EPOCH + std::time::Duration::from_secs(3_000_000);
+
+        fs::write(temp_dir.path().join("a.txt"), "a").unwrap();
+        fs::write(temp_dir.path().join("b.txt"), "b").unwrap();
+        fs::create_dir(temp_dir.path().join("target")).unwrap();
+        fs::write(temp_dir.path().join("target").join("out"), "c").unwrap();
+
+        let set_mtime = |name: &Path, time| {
+            fs::File::options()
+                .write(true)
+                .open(name)
+                .unwrap()
+                .set_modified(time)
+                .unwrap();
+        };
+        set_mtime(&temp_dir.path().join("a.txt"), old);
+        set_mtime(&temp_dir.path().join("b.txt"), new);
+        set_mtime(&temp_dir.path().join("target").join("out"), newest);
+
+        let excludes = vec!["target".to_string()];
+        assert_eq!(
+            tree_mtime(temp_dir.path(), &excludes),
+            Some(DateTime::<Utc>::from(new))
+        );
+    }
+
+    #[test]
+    fn test_parse_exclude_dirs() {
+        assert_eq!(parse_exclude_dirs("target, dist,,"), vec!["target", "dist"]);
+        assert!(parse_exclude_dirs("").is_empty());
+        let dirs = parse_exclude_dirs("+dist,target");
+        assert_eq!(dirs.len(), 7);
+        assert_eq!(dirs.first().map(String::as_str), Some(".git"));
+        assert_eq!(dirs.last().map(String::as_str), Some("dist"));
+    }
+
+    #[test]
+    fn test_read_tags_file() {
+        let temp_dir = tempdir().unwrap();
+        assert!(read_tags_file(temp_dir.path()).is_empty());
+
+        fs::write(temp_dir.path().join(".tags"), "Rust, cli\nweb\n\ncli,\n").unwrap();
+        assert_eq!(read_tags_file(temp_dir.path()), vec!["rust", "cli", "web"]);
+    }
+
+    #[test]
+    fn test_status_for_age() {
+        let threshold = DEFAULT_ARCHIVE_THRESHOLD_DAYS;
+        let days = |days: u32| chrono::Duration::days(i64::from(days));
+        assert_eq!(status_for_age(days(2), threshold), ProjectStatus::Active);
+        assert_eq!(
+            status_for_age(chrono::Duration::days(ACTIVE_WINDOW_DAYS + 1), threshold),
+            ProjectStatus::Stale
+        );
+        assert_eq!(
+            status_for_age(days(threshold), threshold),
+            ProjectStatus::Stale
+        );
+        assert_eq!(
+            status_for_age(days(threshold + 1), threshold),
+            ProjectStatus::Archived
+        );
+        // A threshold within the active window takes precedence
+        assert_eq!(status_for_age(days(10), 7), ProjectStatus::Archived);
+    }
+
+    #[tokio::test]
+    async fn test_archived_marker_overrides_git_status() {
+        let temp_dir = tempdir().unwrap();
+        let repo = temp_dir.path();
+        git(repo, &["init", "-q"]);
+        git(
+            repo,
+            &["commit", "-q", "--allow-empty", "-m", "Bump dependencies"],
+        );
+
+        let config = test_config(repo, 1);
+        let indexer = ProjectIndexer::new(config, None);
+        let project = indexer.process_project(repo, None).await.unwrap();
+        assert_eq!(project.status, ProjectStatus::Active);
+
+        fs::write(repo.join("Archived.md"), "Done, see the successor.").unwrap();
+        let project = indexer.process_project(repo, None).await.unwrap();
+        assert_eq!(project.status, ProjectStatus::Archived);
+    }
+
+    #[tokio::test]
+    async fn test_process_project_last_commit_date() {
+        let temp_dir = tempdir().unwrap();
+        let repo = temp_dir.path().join("repo");
+        let scratch = temp_dir.path().join("scratch");
+        fs::create_dir_all(&repo).unwrap();
+        fs::create_dir_all(&scratch).unwrap();
+        git(&repo, &["init", "-q"]);
+        git_with_env(
+            &repo,
+            &["commit", "-q", "--allow-empty", "-m", "initial"],
+            &[("GIT_COMMITTER_DATE", "2021-03-04T05:06:07Z")],
+        );
+
+        let mut config = test_config(temp_dir.path(), 1);
+        config.mtime_source = MtimeSource::Dir;
+        let indexer = ProjectIndexer::new(config, None);
+
+        let project = indexer.process_project(&repo, None).await.unwrap();
+        let committed = Utc.with_ymd_and_hms(2021, 3, 4, 5, 6, 7).unwrap();
+        assert_eq!(project.last_commit_date, Some(committed));
+        assert_ne!(project.last_modified, committed);
+        assert_eq!(project.status, ProjectStatus::Archived);
+
+        let project = indexer.process_project(&scratch, None).await.unwrap();
+        assert_eq!(project.last_commit_date, dir_mtime(&scratch));
+        assert_eq!(project.status, ProjectStatus::Unknown);
+    }
+
+    #[tokio::test]
+    async fn test_recent_commit_messages() {
+        let temp_dir = tempdir().unwrap();
+        let repo = temp_dir.path();
+        let timeout = Duration::from_secs(DEFAULT_GIT_TIMEOUT_SECS);
+        assert!(recent_commit_messages(repo, 5, timeout).await.is_empty());
+
+        git(repo, &["init", "-q"]);
+        assert!(recent_commit_messages(repo, 5, timeout).await.is_empty());
+
+        for subject in ["Initial commit", "Add\tHTTP   server", "Fix parser"] {
+            git(repo, &["commit", "-q", "--allow-empty", "-m", subject]);
+        }
+        assert_eq!(
+            recent_commit_messages(repo, 2, timeout).await,
+            vec!["Fix parser", "Add HTTP server"]
+        );
+        assert_eq!(
+            sanitize_commit_message(&"x".repeat(500)).map(|m| m.len()),
+            Some(MAX_COMMIT_MESSAGE_CHARS)
+        );
+        assert_eq!(sanitize_commit_message(" \u{1b} "), None);
+    }
+
+    #[tokio::test]
+    async fn test_run_git_timeout() {
+        let temp_dir = tempdir().unwrap();
+        let version = run_git(temp_dir.path(), &["--version"], Duration::from_secs(10)).await;
+        assert!(version.unwrap().starts_with("git version"));
+        // `git --version` can finish before a zero timeout is even checked
+        let slow = ["-c", "alias.slow=!sleep 5", "slow"];
+        assert!(run_git(temp_dir.path(), &slow, Duration::from_millis(100))
+            .await
+            .is_none());
+    }
+
+    #[test]
+    fn test_raw_response_name() {
+        let root = Path::new("/projects");
+        assert_eq!(
+            raw_response_name(root, Path::new("/projects/work/tool")),
+            "work__tool"
+        );
+        assert_ne!(
+            raw_response_name(root, Path::new("/projects/work/tool")),
+            raw_response_name(root, Path::new("/projects/misc/tool"))
+        );
+        assert_eq!(
+            raw_response_name(root, Path::new("/projects/my app/ü")),
+            "my_app___"
+        );
+        assert_eq!(
+            raw_response_name(root, Path::new("/elsewhere/x")),
+            "elsewhere__x"
+        );
+        assert_eq!(raw_response_name(root, root), "project");
+    }
+
+    #[test]
+    fn test_project_category() {
+        let root = Path::new("/projects");
+        let category = |project: &str, depth| {
+            project_category(Path::new(project), root, depth, "uncategorized")
+        };
+
+        // Two levels of nesting
+        assert_eq!(category("/projects/work/rust/tool", 1), "rust");
+        assert_eq!(category("/projects/work/rust/tool", 2), "work");
+        assert_eq!(category("/projects/work/rust/tool", 3), "uncategorized");
+        assert_eq!(category("/projects/work/rust/tool", 0), "uncategorized");
+
+        // One level of nesting
+        assert_eq!(category("/projects/work/tool", 1), "work");
+        assert_eq!(category("/projects/work/tool", 2), "uncategorized");
+
+        // Direct children of the projects directory
+        assert_eq!(category("/projects/tool", 1), "uncategorized");
+        assert_eq!(
+            project_category(Path::new("/projects/tool"), root, 1, "root"),
+            "root"
+        );
+    }
+
+    #[test]
+    fn test_detect_container_files() {
+        let temp_dir = tempdir().unwrap();
+        assert_eq!(detect_container_files(temp_dir.path()), (false, false));
+
+        fs::write(temp_dir.path().join("Dockerfile.dev"), "FROM rust").unwrap();
+        assert_eq!(detect_container_files(temp_dir.path()), (true, false));
+
+        fs::write(temp_dir.path().join("compose.yaml"), "services: {}").unwrap();
+        assert_eq!(detect_container_files(temp_dir.path()), (true, true));
     }
 }
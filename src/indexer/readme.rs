@@ -0,0 +1,156 @@
+//! README inspection
+//!
+//! This module looks at the README at a project's root for two cheap
+//! documentation signals: whether it shows status badges (CI, coverage,
+//! package registries, ...) and the first link it points readers to, which
+//! is usually the project's homepage, documentation or repository.
+
+use regex::Regex;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+/// Largest README read, in bytes; longer files are cut off
+const MAX_README_BYTES: usize = 256 * 1024;
+
+/// What a project's README reveals about its documentation
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReadmeInfo {
+    /// Whether the README shows status badges
+    pub has_badges: bool,
+    /// First link in the README outside of its badges
+    pub primary_link: Option<String>,
+}
+
+/// Inspect the README at the root of a project
+///
+/// The README is the first file whose name starts with `readme`, ignoring
+/// case. A project without a readable README gets an empty [`ReadmeInfo`].
+pub fn inspect_readme(path: &Path) -> ReadmeInfo {
+    let Some(readme) = find_readme(path) else {
+        return ReadmeInfo::default();
+    };
+    let Ok(bytes) = fs::read(&readme) else {
+        return ReadmeInfo::default();
+    };
+    let len = bytes.len().min(MAX_README_BYTES);
+    parse_readme(&String::from_utf8_lossy(&bytes[..len]))
+}
+
+/// Find the README file at the root of a project
+fn find_readme(path: &Path) -> Option<PathBuf> {
+    let mut readmes: Vec<_> = fs::read_dir(path)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_file()))
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .to_lowercase()
+                .starts_with("readme")
+        })
+        .map(|entry| entry.path())
+        .collect();
+    // Prefer the shortest name, e.g. README.md over README.fr.md
+    readmes.sort_by_key(|path| (path.as_os_str().len(), path.clone()));
+    readmes.into_iter().next()
+}
+
+/// Find the badges and primary link in README text
+fn parse_readme(text: &str) -> ReadmeInfo {
+    let mut info = ReadmeInfo::default();
+    for line in text.lines() {
+        // Badge lines also link to CI pages and registries, not the project
+        if badge_regex().is_match(line) {
+            info.has_badges = true;
+            continue;
+        }
+        if info.primary_link.is_none() {
+            info.primary_link = link_regex()
+                .find_iter(line)
+                .map(|m| m.as_str().trim_end_matches(['.', ',']))
+                .find(|url| !is_image_url(url))
+                .map(str::to_string);
+        }
+    }
+    info
+}
+
+/// Regex matching the image URL of a status badge
+fn badge_regex() -> &'static Regex {
+    static BADGE: OnceLock<Regex> = OnceLock::new();
+    BADGE.get_or_init(|| {
+        Regex::new(
+            r"(?i)https?://[^\s)]*(shields\.io|badge\.fury\.io|badgen\.net|codecov\.io|coveralls\.io|travis-ci\.(org|com)|circleci\.com|/badge\.svg|/badge/|docs\.rs/[^\s)]*badge)",
+        )
+        .expect("badge regex is valid")
+    })
+}
+
+/// Regex matching an http(s) URL
+fn link_regex() -> &'static Regex {
+    static LINK: OnceLock<Regex> = OnceLock::new();
+    LINK.get_or_init(|| Regex::new(r#"https?://[^\s)\]>"'<`]+"#).expect("link regex is valid"))
+}
+
+/// Check whether a URL points to an image
+fn is_image_url(url: &str) -> bool {
+    let url = url.to_lowercase();
+    [".svg", ".png", ".jpg", ".jpeg", ".gif", ".webp"]
+        .iter()
+        .any(|extension| {
+            url.split(['?', '#'])
+                .next()
+                .unwrap_or_default()
+                .ends_with(extension)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_parse_readme() {
+        let readme = "\
+# Tool
+
+[![CI](https://github.com/me/tool/actions/workflows/ci.yml/badge.svg)](https://github.com/me/tool/actions)
+[![crates.io](https://img.shields.io/crates/v/tool.svg)](https://crates.io/crates/tool)
+
+![screenshot](https://example.com/screenshot.png)
+
+Documentation lives at https://tool.example.com/docs.
+See also https://example.com/other.
+";
+        assert_eq!(
+            parse_readme(readme),
+            ReadmeInfo {
+                has_badges: true,
+                primary_link: Some("https://tool.example.com/docs".to_string()),
+            }
+        );
+        assert_eq!(parse_readme("# Tool\n\nA tool."), ReadmeInfo::default());
+    }
+
+    #[test]
+    fn test_inspect_readme() {
+        let temp_dir = tempdir().unwrap();
+        assert_eq!(inspect_readme(temp_dir.path()), ReadmeInfo::default());
+
+        fs::write(
+            temp_dir.path().join("README.fr.md"),
+            "https://fr.example.com",
+        )
+        .unwrap();
+        fs::write(temp_dir.path().join("Readme.md"), "See https://example.com").unwrap();
+        assert_eq!(
+            inspect_readme(temp_dir.path()).primary_link.as_deref(),
+            Some("https://example.com")
+        );
+    }
+}
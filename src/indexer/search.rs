@@ -0,0 +1,523 @@
+//! Search matching
+//!
+//! Searches compare a query with project names, tags and categories. By
+//! default both sides are normalized first, lowercased and stripped of
+//! diacritics, so "cafe" finds "Café" and "ML" finds "ml". Exact matching
+//! compares the raw strings instead.
+//!
+//! Fuzzy searches score every project instead of filtering on substrings,
+//! so typos ("projcets") and abbreviations ("idxr") still find a project,
+//! and return the best scoring ones first. Semantic searches rank projects
+//! by the similarity of their stored embedding with the embedding of the
+//! query, which finds related projects sharing no words with it.
+
+use super::{require_index, stream_index_with};
+use crate::{
+    error::{OllamaError, Result},
+    models::Project,
+};
+use serde::Serialize;
+use std::path::Path;
+
+/// Lowercase letters with diacritics and the letters they fold to
+///
+/// Covers the Latin-1 Supplement and Latin Extended-A blocks, which hold
+/// the accented letters of most European languages.
+const DIACRITIC_FOLDS: &[(&str, &str)] = &[
+    ("àáâãäåāăą", "a"),
+    ("çćĉċč", "c"),
+    ("ďđ", "d"),
+    ("èéêëēĕėęě", "e"),
+    ("ĝğġģ", "g"),
+    ("ĥħ", "h"),
+    ("ìíîïĩīĭįı", "i"),
+    ("ĵ", "j"),
+    ("ķ", "k"),
+    ("ĺļľŀł", "l"),
+    ("ñńņňŉ", "n"),
+    ("òóôõöøōŏő", "o"),
+    ("ŕŗř", "r"),
+    ("śŝşš", "s"),
+    ("ţťŧ", "t"),
+    ("ùúûüũūŭůűų", "u"),
+    ("ŵ", "w"),
+    ("ýÿŷ", "y"),
+    ("źżž", "z"),
+    ("æ", "ae"),
+    ("œ", "oe"),
+    ("ß", "ss"),
+    ("þ", "th"),
+];
+
+/// Normalize text for forgiving comparisons
+///
+/// The text is lowercased and letters with diacritics are replaced with
+/// their base letters, e.g. `"Café Ørsted"` becomes `"cafe orsted"`.
+/// Combining diacritical marks (U+0300 to U+036F), as found in decomposed
+/// text such as `"Cafe\u{301}"`, are dropped.
+pub fn normalize(text: &str) -> String {
+    let mut normalized = String::with_capacity(text.len());
+    for c in text.chars().flat_map(char::to_lowercase) {
+        if ('\u{300}'..='\u{36f}').contains(&c) {
+            continue;
+        }
+        match DIACRITIC_FOLDS
+            .iter()
+            .find(|(accented, _)| accented.contains(c))
+        {
+            Some((_, base)) => normalized.push_str(base),
+            None => normalized.push(c),
+        }
+    }
+    normalized
+}
+
+/// Check whether `target` contains `query`
+///
+/// Unless `exact` is set, both are compared in their [`normalize`]d form.
+pub fn matches(query: &str, target: &str, exact: bool) -> bool {
+    if exact {
+        target.contains(query)
+    } else {
+        normalize(target).contains(&normalize(query))
+    }
+}
+
+/// Project fields a search looks at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchFields {
+    pub name: bool,
+    pub tags: bool,
+    pub category: bool,
+}
+
+impl SearchFields {
+    /// All fields: name, tags and category
+    pub const ALL: Self = Self {
+        name: true,
+        tags: true,
+        category: true,
+    };
+
+    /// Fields selected by the `--tags-only` and `--category-only` flags
+    ///
+    /// Without either flag all fields are searched; with both, tags and
+    /// categories are.
+    pub fn from_flags(tags_only: bool, category_only: bool) -> Self {
+        if !tags_only && !category_only {
+            return Self::ALL;
+        }
+        Self {
+            name: false,
+            tags: tags_only,
+            category: category_only,
+        }
+    }
+}
+
+impl Default for SearchFields {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+/// Check whether any of the searched fields of a project match a query
+pub fn project_matches(project: &Project, query: &str, fields: SearchFields, exact: bool) -> bool {
+    (fields.name && matches(query, &project.name, exact))
+        || (fields.tags && project.tags.iter().any(|tag| matches(query, tag, exact)))
+        || (fields.category && matches(query, &project.category, exact))
+}
+
+/// Order projects by their latest commit, most recent first
+///
+/// Projects without a `last_commit_date` come last; the sort is stable, so
+/// they and projects with equal dates keep their relative order.
+pub fn sort_by_recency(projects: &mut [Project]) {
+    projects.sort_by_key(|project| std::cmp::Reverse(project.last_commit_date));
+}
+
+/// Lowest [`fuzzy_score`] a project needs to be returned by a fuzzy search
+pub const FUZZY_MIN_SCORE: f64 = 0.5;
+
+/// Number of edits (insertions, deletions, substitutions) turning `a` into `b`
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Similarity of two strings, from 0 (nothing alike) to 1 (equal)
+fn similarity(a: &[char], b: &[char]) -> f64 {
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+    1.0 - levenshtein(a, b) as f64 / longest as f64
+}
+
+/// Score of `query` as an in-order subsequence of `target`
+///
+/// The score is the share of `target` spanned by the query characters, so
+/// `"pidx"` scores higher against `"p-idx"` than against `"projets-indexer"`.
+/// Without a subsequence match the score is 0.
+fn subsequence_score(query: &[char], target: &[char]) -> f64 {
+    let mut remaining = query.iter().peekable();
+    for c in target {
+        if remaining.peek() == Some(&c) {
+            remaining.next();
+        }
+    }
+    if remaining.peek().is_some() {
+        return 0.0;
+    }
+    query.len() as f64 / target.len() as f64
+}
+
+/// Score how well `query` matches `target`, from 0 (no match) to 1
+///
+/// A target containing the query scores 1. Otherwise the score is the best
+/// of the edit-distance similarity with the whole target or with one of its
+/// words, which forgives typos, and of the subsequence score, which forgives
+/// left-out letters. Unless `exact` is set, both are compared in their
+/// [`normalize`]d form.
+pub fn fuzzy_score(query: &str, target: &str, exact: bool) -> f64 {
+    let (query, target) = if exact {
+        (query.to_string(), target.to_string())
+    } else {
+        (normalize(query), normalize(target))
+    };
+    if query.is_empty() {
+        return 0.0;
+    }
+    if target.contains(&query) {
+        return 1.0;
+    }
+
+    let query: Vec<char> = query.chars().collect();
+    let words = target
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.chars().collect::<Vec<_>>());
+    let target: Vec<char> = target.chars().collect();
+    words
+        .map(|word| similarity(&query, &word))
+        .fold(similarity(&query, &target), f64::max)
+        .max(subsequence_score(&query, &target))
+}
+
+/// Best [`fuzzy_score`] of a query across the searched fields of a project
+pub fn project_score(project: &Project, query: &str, fields: SearchFields, exact: bool) -> f64 {
+    let mut targets: Vec<&str> = Vec::new();
+    if fields.name {
+        targets.push(&project.name);
+    }
+    if fields.tags {
+        targets.extend(project.tags.iter().map(String::as_str));
+    }
+    if fields.category {
+        targets.push(&project.category);
+    }
+    targets
+        .into_iter()
+        .map(|target| fuzzy_score(query, target, exact))
+        .fold(0.0, f64::max)
+}
+
+/// A project found by a fuzzy or semantic search, with its score
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchMatch {
+    pub score: f64,
+    #[serde(flatten)]
+    pub project: Project,
+}
+
+/// Find the projects of an index file best matching a query
+///
+/// Projects scoring at least [`FUZZY_MIN_SCORE`] are returned best first,
+/// equal scores in index order, keeping at most `limit` of them. A missing
+/// index file is reported as described in [`require_index`].
+pub fn fuzzy_search_index(
+    path: &Path,
+    query: &str,
+    fields: SearchFields,
+    exact: bool,
+    strict: bool,
+    limit: usize,
+) -> Result<Vec<SearchMatch>> {
+    require_index(path)?;
+
+    let mut found = Vec::new();
+    for project in stream_index_with(path, strict)? {
+        let project = project?;
+        let score = project_score(&project, query, fields, exact);
+        if score >= FUZZY_MIN_SCORE {
+            found.push(SearchMatch { score, project });
+        }
+    }
+    found.sort_by(|a, b| b.score.total_cmp(&a.score));
+    found.truncate(limit);
+    Ok(found)
+}
+
+/// Text a project's embedding is computed from: its name, tags and type
+pub fn embedding_text(project: &Project) -> String {
+    let mut text = project.name.clone();
+    if !project.tags.is_empty() {
+        text.push_str(&format!("\ntags: {}", project.tags.join(", ")));
+    }
+    if let Some(project_type) = &project.project_type {
+        text.push_str(&format!("\ntype: {}", project_type));
+    }
+    text
+}
+
+/// Cosine similarity of two embeddings, from -1 to 1
+///
+/// Embeddings of different lengths, which come from different models, and
+/// zero vectors have a similarity of 0.
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f64 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let (mut dot, mut norm_a, mut norm_b) = (0.0, 0.0, 0.0);
+    for (x, y) in a.iter().zip(b) {
+        let (x, y) = (f64::from(*x), f64::from(*y));
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    // Rounding can take parallel vectors slightly past 1
+    (dot / (norm_a.sqrt() * norm_b.sqrt())).clamp(-1.0, 1.0)
+}
+
+/// Find the projects of an index file closest to a query embedding
+///
+/// Projects are ranked by the [`cosine_similarity`] of their embedding with
+/// `query`, most similar first, keeping at most `limit` of them. Projects
+/// without an embedding are left out; an index where none has one is a
+/// validation error, since it was built without embeddings. A missing index
+/// file is reported as described in [`require_index`].
+pub fn semantic_search_index(
+    path: &Path,
+    query: &[f32],
+    strict: bool,
+    limit: usize,
+) -> Result<Vec<SearchMatch>> {
+    require_index(path)?;
+
+    let mut found = Vec::new();
+    for project in stream_index_with(path, strict)? {
+        let project = project?;
+        if let Some(embedding) = &project.embedding {
+            let score = cosine_similarity(query, embedding);
+            found.push(SearchMatch { score, project });
+        }
+    }
+    if found.is_empty() {
+        return Err(OllamaError::ValidationError(format!(
+            "No project in {} has an embedding; index with --ollama --embed first",
+            path.display()
+        ))
+        .into());
+    }
+    found.sort_by(|a, b| b.score.total_cmp(&a.score));
+    found.truncate(limit);
+    Ok(found)
+}
+
+/// Find the projects of an index file matching a query
+///
+/// Projects are returned in index order. A missing index file is reported
+/// as described in [`require_index`].
+pub fn search_index(
+    path: &Path,
+    query: &str,
+    fields: SearchFields,
+    exact: bool,
+    strict: bool,
+) -> Result<Vec<Project>> {
+    require_index(path)?;
+
+    let mut found = Vec::new();
+    for project in stream_index_with(path, strict)? {
+        let project = project?;
+        if project_matches(&project, query, fields, exact) {
+            found.push(project);
+        }
+    }
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{error::AppError, indexer::save_index};
+    use std::{io, path::PathBuf};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("Café Ørsted"), "cafe orsted");
+        assert_eq!(normalize("ÉCOLE"), "ecole");
+        assert_eq!(normalize("Straße"), "strasse");
+        assert_eq!(normalize("rust-cli"), "rust-cli");
+        assert_eq!(normalize("Cafe\u{301} Zu\u{308}rich"), "cafe zurich");
+        assert_eq!(normalize("a\u{2ff}\u{36f}\u{371}"), "a\u{2ff}\u{371}");
+    }
+
+    #[test]
+    fn test_matches() {
+        assert!(matches("cafe", "café", false));
+        assert!(matches("ML", "ml-pipeline", false));
+        assert!(matches("Zürich", "zurich-transit", false));
+        assert!(matches("café", "Cafe\u{301}-app", false));
+        assert!(!matches("cafe", "café", true));
+        assert!(!matches("ML", "ml-pipeline", true));
+        assert!(matches("ml", "ml-pipeline", true));
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("index", "projets-indexer", false), 1.0);
+        assert_eq!(fuzzy_score("Cafe", "café", false), 1.0);
+        assert_eq!(fuzzy_score("", "rust", false), 0.0);
+        // One typo against the "projets" word of the name
+        assert_eq!(fuzzy_score("projcets", "projets-indexer", false), 0.875);
+        // Left-out letters
+        assert!(fuzzy_score("idxr", "indexer", false) >= FUZZY_MIN_SCORE);
+        assert!(fuzzy_score("idxr", "indexer", false) < 1.0);
+        assert!(fuzzy_score("python", "rust-cli", false) < FUZZY_MIN_SCORE);
+        assert!(fuzzy_score("Cafe", "café", true) < 1.0);
+    }
+
+    #[test]
+    fn test_fuzzy_search_index() {
+        let temp_dir = tempdir().unwrap();
+        let index_file = temp_dir.path().join("index.json");
+        let mut indexer = Project::new("projets-indexer".to_string(), PathBuf::from("/p/i"));
+        indexer.tags = vec!["cli".to_string()];
+        let projects = Project::new("projects".to_string(), PathBuf::from("/p/p"));
+        let web = Project::new("web".to_string(), PathBuf::from("/p/web"));
+        save_index(&index_file, &[indexer, projects, web]).unwrap();
+
+        let found = fuzzy_search_index(&index_file, "projets", SearchFields::ALL, false, false, 10)
+            .unwrap();
+        let names: Vec<_> = found.iter().map(|m| m.project.name.as_str()).collect();
+        assert_eq!(names, ["projets-indexer", "projects"]);
+        assert_eq!(found[0].score, 1.0);
+        assert!(found[1].score < 1.0);
+
+        let found =
+            fuzzy_search_index(&index_file, "projets", SearchFields::ALL, false, false, 1).unwrap();
+        assert_eq!(found.len(), 1);
+        let found = fuzzy_search_index(
+            &index_file,
+            "projets",
+            SearchFields::from_flags(true, false),
+            false,
+            false,
+            10,
+        )
+        .unwrap();
+        assert!(found.is_empty());
+    }
+
+    #[test]
+    fn test_sort_by_recency() {
+        let project = |name: &str, days_ago: Option<i64>| {
+            let mut project = Project::new(name.to_string(), PathBuf::from(name));
+            project.last_commit_date =
+                days_ago.map(|days| chrono::Utc::now() - chrono::Duration::days(days));
+            project
+        };
+        let mut projects = vec![
+            project("undated", None),
+            project("old", Some(90)),
+            project("fresh", Some(1)),
+            project("also-undated", None),
+        ];
+        sort_by_recency(&mut projects);
+        let names: Vec<_> = projects.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["fresh", "old", "undated", "also-undated"]);
+    }
+
+    #[test]
+    fn test_cosine_similarity() {
+        assert!((cosine_similarity(&[1.0, 2.0], &[2.0, 4.0]) - 1.0).abs() < 1e-9);
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[0.0, 3.0]), 0.0);
+        assert!((cosine_similarity(&[1.0, 0.0], &[-1.0, 0.0]) + 1.0).abs() < 1e-9);
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[1.0]), 0.0);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+    }
+
+    #[test]
+    fn test_semantic_search_index() {
+        let temp_dir = tempdir().unwrap();
+        let index_file = temp_dir.path().join("index.json");
+        let mut ml = Project::new("trainer".to_string(), PathBuf::from("/p/trainer"));
+        ml.embedding = Some(vec![0.9, 0.1]);
+        let mut web = Project::new("web".to_string(), PathBuf::from("/p/web"));
+        web.embedding = Some(vec![0.1, 0.9]);
+        let bare = Project::new("bare".to_string(), PathBuf::from("/p/bare"));
+        save_index(&index_file, &[web, ml, bare.clone()]).unwrap();
+
+        let found = semantic_search_index(&index_file, &[1.0, 0.0], false, 10).unwrap();
+        let names: Vec<_> = found.iter().map(|m| m.project.name.as_str()).collect();
+        assert_eq!(names, ["trainer", "web"]);
+        assert!(found[0].score > found[1].score);
+        assert_eq!(
+            semantic_search_index(&index_file, &[1.0, 0.0], false, 1)
+                .unwrap()
+                .len(),
+            1
+        );
+
+        save_index(&index_file, &[bare]).unwrap();
+        let error = semantic_search_index(&index_file, &[1.0, 0.0], false, 10).unwrap_err();
+        assert!(error.to_string().contains("--embed"));
+    }
+
+    #[test]
+    fn test_search_index() {
+        let temp_dir = tempdir().unwrap();
+        let index_file = temp_dir.path().join("index.json");
+        let error = search_index(&index_file, "rust", SearchFields::ALL, false, false).unwrap_err();
+        assert!(matches!(&error, AppError::Io(e) if e.kind() == io::ErrorKind::NotFound));
+        assert!(error.to_string().contains("projets-indexer index"));
+
+        let mut tool = Project::new("Rusty".to_string(), PathBuf::from("/p/tool"));
+        tool.category = "tools".to_string();
+        let mut web = Project::new("web".to_string(), PathBuf::from("/p/web"));
+        web.category = "Rust".to_string();
+        web.tags = vec!["typescript".to_string()];
+        let mut cli = Project::new("cli".to_string(), PathBuf::from("/p/cli"));
+        cli.tags = vec!["rust".to_string()];
+        save_index(&index_file, &[tool, web, cli]).unwrap();
+
+        let names = |fields: SearchFields, exact: bool| -> Vec<String> {
+            search_index(&index_file, "rust", fields, exact, false)
+                .unwrap()
+                .into_iter()
+                .map(|project| project.name)
+                .collect()
+        };
+        assert_eq!(names(SearchFields::ALL, false), ["Rusty", "web", "cli"]);
+        assert_eq!(names(SearchFields::ALL, true), ["cli"]);
+        assert_eq!(names(SearchFields::from_flags(true, false), false), ["cli"]);
+        assert_eq!(names(SearchFields::from_flags(false, true), false), ["web"]);
+        assert_eq!(
+            names(SearchFields::from_flags(true, true), false),
+            ["web", "cli"]
+        );
+    }
+}
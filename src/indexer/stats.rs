@@ -0,0 +1,302 @@
+//! Index statistics
+//!
+//! [`ProjectStats`] aggregates the figures reported by the `stats` command:
+//! project counts by status, category, language and type, tag usage, recent
+//! commit activity, and the share of projects with tests, containers or
+//! README badges. It is also behind the summary of `index --dry-run` and the
+//! `/stats` endpoint of `serve`.
+//!
+//! [`activity_timeline`] counts projects by the month they were last
+//! modified, for `stats --timeline`.
+
+use crate::error::Result;
+use crate::models::{Project, ProjectStatus};
+use chrono::{Datelike, Duration, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Days within which a commit makes a project count as recently committed to
+pub const RECENT_COMMIT_DAYS: i64 = 30;
+
+/// Aggregated statistics over the projects of an index
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ProjectStats {
+    /// Number of projects
+    pub total_projects: usize,
+    /// Projects with recent commits
+    pub active_projects: usize,
+    /// Projects with commits, but none recent enough to be active
+    pub stale_projects: usize,
+    /// Projects without commits within the archive threshold, or marked as
+    /// archived
+    pub archived_projects: usize,
+    /// Projects whose status couldn't be determined, e.g. without git history
+    pub unknown_projects: usize,
+    /// Projects with a `last_commit_date`, which indexes predating it lack
+    pub projects_with_commit_date: usize,
+    /// Projects whose `last_commit_date` lies within the last
+    /// [`RECENT_COMMIT_DAYS`] days
+    pub recently_committed_projects: usize,
+    /// Number of tags across all projects, counting repeats
+    pub total_tags: usize,
+    /// Number of different tags across all projects
+    pub distinct_tags: usize,
+    /// Number of projects carrying each tag
+    pub projects_by_tag: HashMap<String, usize>,
+    /// Average number of tags per project
+    pub average_tags_per_project: f64,
+    /// Projects without any tags, e.g. because tagging failed or was disabled
+    pub untagged_projects: usize,
+    /// Projects with a Dockerfile
+    pub projects_with_dockerfile: usize,
+    /// Projects with a Docker Compose file
+    pub projects_with_compose: usize,
+    /// Projects that appear to contain tests
+    pub projects_with_tests: usize,
+    /// Projects whose README shows status badges
+    pub projects_with_readme_badges: usize,
+    /// Number of projects in each category
+    pub projects_by_category: HashMap<String, usize>,
+    /// Projects of each status, per category
+    pub status_by_category: HashMap<String, StatusCounts>,
+    /// Number of projects with each primary language
+    pub projects_by_language: HashMap<String, usize>,
+    /// Projects of each type, detected from build manifests
+    pub projects_by_type: HashMap<String, usize>,
+    /// Files per extension across all projects that collected file types
+    pub files_by_extension: HashMap<String, usize>,
+    /// Number of projects declaring each dependency
+    pub projects_by_dependency: HashMap<String, usize>,
+}
+
+/// Number of projects of each status
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct StatusCounts {
+    pub active: usize,
+    pub stale: usize,
+    pub archived: usize,
+    pub unknown: usize,
+}
+
+impl StatusCounts {
+    /// Count one more project of a status
+    pub fn add(&mut self, status: &ProjectStatus) {
+        match status {
+            ProjectStatus::Active => self.active += 1,
+            ProjectStatus::Stale => self.stale += 1,
+            ProjectStatus::Archived => self.archived += 1,
+            ProjectStatus::Unknown => self.unknown += 1,
+        }
+    }
+}
+
+impl ProjectStats {
+    /// Aggregate statistics over a list of projects
+    pub fn from_projects(projects: &[Project]) -> Self {
+        let mut stats = Self::default();
+        for project in projects {
+            stats.add(project);
+        }
+        stats
+    }
+
+    /// Count one more project
+    ///
+    /// This lets statistics be gathered while streaming an index, without
+    /// holding all of its projects in memory.
+    pub fn add(&mut self, project: &Project) {
+        self.total_projects += 1;
+        match project.status {
+            ProjectStatus::Active => self.active_projects += 1,
+            ProjectStatus::Stale => self.stale_projects += 1,
+            ProjectStatus::Archived => self.archived_projects += 1,
+            ProjectStatus::Unknown => self.unknown_projects += 1,
+        }
+        if let Some(date) = project.last_commit_date {
+            self.projects_with_commit_date += 1;
+            if Utc::now() - date <= Duration::days(RECENT_COMMIT_DAYS) {
+                self.recently_committed_projects += 1;
+            }
+        }
+        self.total_tags += project.tags.len();
+        if project.tags.is_empty() {
+            self.untagged_projects += 1;
+        }
+        self.projects_with_dockerfile += project.has_dockerfile as usize;
+        self.projects_with_compose += project.has_compose as usize;
+        self.projects_with_tests += project.has_tests as usize;
+        self.projects_with_readme_badges += project.has_readme_badges as usize;
+        // A tag listed twice on a project still counts the project once
+        for tag in project.tags.iter().collect::<HashSet<_>>() {
+            *self.projects_by_tag.entry(tag.clone()).or_insert(0) += 1;
+        }
+        self.status_by_category
+            .entry(project.category.clone())
+            .or_default()
+            .add(&project.status);
+        *self
+            .projects_by_category
+            .entry(project.category.clone())
+            .or_insert(0) += 1;
+        for (extension, count) in &project.file_types {
+            *self
+                .files_by_extension
+                .entry(extension.clone())
+                .or_insert(0) += count;
+        }
+        for dependency in &project.dependencies {
+            *self
+                .projects_by_dependency
+                .entry(dependency.clone())
+                .or_insert(0) += 1;
+        }
+        if let Some(project_type) = &project.project_type {
+            *self
+                .projects_by_type
+                .entry(project_type.clone())
+                .or_insert(0) += 1;
+        }
+        if let Some(language) = &project.primary_language {
+            *self
+                .projects_by_language
+                .entry(language.clone())
+                .or_insert(0) += 1;
+        }
+
+        self.distinct_tags = self.projects_by_tag.len();
+        self.average_tags_per_project = self.total_tags as f64 / self.total_projects as f64;
+    }
+}
+
+/// Count projects by the month they were last modified, oldest month first
+///
+/// Projects without a recorded modification time, loaded from indexes that
+/// predate it, are left out. With `include_empty`, months between the first
+/// and the last one in which no project was modified are listed with a zero
+/// count.
+pub fn activity_timeline(
+    projects: impl IntoIterator<Item = Result<Project>>,
+    include_empty: bool,
+) -> Result<Vec<(String, usize)>> {
+    let mut months: BTreeMap<(i32, u32), usize> = BTreeMap::new();
+    for project in projects {
+        let modified = project?.last_modified;
+        if modified.timestamp() == 0 {
+            continue;
+        }
+        *months
+            .entry((modified.year(), modified.month()))
+            .or_insert(0) += 1;
+    }
+
+    if include_empty {
+        if let (Some(&first), Some(&last)) = (months.keys().next(), months.keys().next_back()) {
+            let mut month = first;
+            while month < last {
+                months.entry(month).or_insert(0);
+                month = match month {
+                    (year, 12) => (year + 1, 1),
+                    (year, month) => (year, month + 1),
+                };
+            }
+        }
+    }
+
+    Ok(months
+        .into_iter()
+        .map(|((year, month), count)| (format!("{:04}-{:02}", year, month), count))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_project_stats() {
+        assert_eq!(ProjectStats::from_projects(&[]), ProjectStats::default());
+
+        let mut tool = Project::new("tool".to_string(), PathBuf::from("/p/tool"));
+        tool.category = "work".to_string();
+        tool.status = ProjectStatus::Active;
+        tool.tags = vec!["rust".to_string(), "cli".to_string(), "rust".to_string()];
+        tool.has_tests = true;
+        tool.project_type = Some("rust".to_string());
+        tool.last_commit_date = Some(Utc::now() - Duration::days(2));
+        let mut web = Project::new("web".to_string(), PathBuf::from("/p/web"));
+        web.category = "work".to_string();
+        web.status = ProjectStatus::Archived;
+        web.has_dockerfile = true;
+        web.dependencies = vec!["react".to_string()];
+        web.last_commit_date = Some(Utc::now() - Duration::days(400));
+        let mut old = Project::new("old".to_string(), PathBuf::from("/p/old"));
+        old.category = "misc".to_string();
+        old.tags = vec!["rust".to_string()];
+
+        let stats = ProjectStats::from_projects(&[tool, web, old]);
+        assert_eq!(stats.total_projects, 3);
+        assert_eq!(stats.active_projects, 1);
+        assert_eq!(stats.archived_projects, 1);
+        assert_eq!(stats.unknown_projects, 1);
+        assert_eq!(stats.projects_with_commit_date, 2);
+        assert_eq!(stats.recently_committed_projects, 1);
+        assert_eq!(stats.total_tags, 4);
+        assert_eq!(stats.distinct_tags, 2);
+        assert_eq!(stats.projects_by_tag["rust"], 2);
+        assert!((stats.average_tags_per_project - 4.0 / 3.0).abs() < f64::EPSILON);
+        assert_eq!(stats.untagged_projects, 1);
+        assert_eq!(stats.projects_with_tests, 1);
+        assert_eq!(stats.projects_with_dockerfile, 1);
+        assert_eq!(stats.projects_by_category["work"], 2);
+        assert_eq!(
+            stats.status_by_category["work"],
+            StatusCounts {
+                active: 1,
+                archived: 1,
+                ..StatusCounts::default()
+            }
+        );
+        assert_eq!(stats.projects_by_type["rust"], 1);
+        assert_eq!(stats.projects_by_dependency["react"], 1);
+    }
+
+    #[test]
+    fn test_activity_timeline() {
+        let project = |modified: Option<(i32, u32)>| {
+            let mut project = Project::new("p".to_string(), PathBuf::from("/p"));
+            project.last_modified = match modified {
+                Some((year, month)) => Utc.with_ymd_and_hms(year, month, 15, 12, 0, 0).unwrap(),
+                None => chrono::DateTime::UNIX_EPOCH,
+            };
+            Ok(project)
+        };
+        let projects = || {
+            vec![
+                project(Some((2024, 3))),
+                project(Some((2023, 11))),
+                project(None),
+                project(Some((2024, 3))),
+            ]
+        };
+
+        assert_eq!(
+            activity_timeline(projects(), false).unwrap(),
+            vec![("2023-11".to_string(), 1), ("2024-03".to_string(), 2)]
+        );
+        let months: Vec<(String, usize)> = activity_timeline(projects(), true).unwrap();
+        assert_eq!(
+            months,
+            [
+                ("2023-11", 1),
+                ("2023-12", 0),
+                ("2024-01", 0),
+                ("2024-02", 0),
+                ("2024-03", 2)
+            ]
+            .map(|(month, count)| (month.to_string(), count))
+        );
+        assert!(activity_timeline(Vec::new(), true).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,195 @@
+//! Index integrity checks
+//!
+//! This module backs the `verify` command. It reads an index file without
+//! assuming it is well-formed and reports every problem it finds, each with
+//! the line (`.jsonl`) or entry (JSON array) it was found at:
+//! - malformed JSON
+//! - entries that don't describe a valid project, e.g. an unknown status
+//! - a mix of absolute and relative project paths
+//! - duplicate project paths
+//! - empty tags
+
+use super::index_file::is_jsonl;
+use crate::{error::Result, models::Project};
+use serde_json::Value;
+use std::{collections::HashMap, fmt, fs, path::Path};
+
+/// A problem found in an index file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexProblem {
+    /// Where the problem was found, e.g. `line 3` or `entry 12`
+    pub location: String,
+    /// Description of the problem
+    pub message: String,
+}
+
+impl IndexProblem {
+    fn new(location: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            location: location.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for IndexProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
+/// Check an index file for problems
+///
+/// Returns every problem found; an empty list means the index is valid.
+/// Only failing to read the file at all is reported as an error.
+///
+/// # Arguments
+///
+/// * `path` - Path to the index file
+pub fn verify_index(path: &Path) -> Result<Vec<IndexProblem>> {
+    let contents = fs::read_to_string(path)?;
+    let mut problems = Vec::new();
+
+    let mut entries = Vec::new();
+    if is_jsonl(path) {
+        for (number, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let location = format!("line {}", number + 1);
+            match serde_json::from_str::<Value>(line) {
+                Ok(value) => entries.push((location, value)),
+                Err(e) => {
+                    problems.push(IndexProblem::new(location, format!("invalid JSON: {}", e)))
+                }
+            }
+        }
+    } else {
+        match serde_json::from_str::<Vec<Value>>(&contents) {
+            Ok(values) => entries.extend(
+                values
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, value)| (format!("entry {}", i + 1), value)),
+            ),
+            Err(e) => {
+                problems.push(IndexProblem::new(
+                    "index",
+                    format!("invalid JSON array: {}", e),
+                ));
+                return Ok(problems);
+            }
+        }
+    }
+
+    let mut seen_paths: HashMap<_, String> = HashMap::new();
+    let mut absolute_paths = None;
+    for (location, value) in entries {
+        let project: Project = match serde_json::from_value(value) {
+            Ok(project) => project,
+            Err(e) => {
+                problems.push(IndexProblem::new(
+                    location,
+                    format!("invalid project: {}", e),
+                ));
+                continue;
+            }
+        };
+
+        // The first project decides whether paths are expected to be absolute
+        let absolute = project.path.is_absolute();
+        match absolute_paths {
+            None => absolute_paths = Some(absolute),
+            Some(expected) if expected != absolute => problems.push(IndexProblem::new(
+                location.clone(),
+                format!(
+                    "path {} is {} while earlier paths are {}",
+                    project.path.display(),
+                    if absolute { "absolute" } else { "relative" },
+                    if expected { "absolute" } else { "relative" }
+                ),
+            )),
+            Some(_) => {}
+        }
+
+        if project.tags.iter().any(|tag| tag.trim().is_empty()) {
+            problems.push(IndexProblem::new(
+                location.clone(),
+                format!("project {} has an empty tag", project.name),
+            ));
+        }
+
+        match seen_paths.get(&project.path) {
+            Some(first) => problems.push(IndexProblem::new(
+                location,
+                format!(
+                    "duplicate path {} (first seen at {})",
+                    project.path.display(),
+                    first
+                ),
+            )),
+            None => {
+                seen_paths.insert(project.path, location);
+            }
+        }
+    }
+
+    Ok(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexer::save_index;
+    use std::path::PathBuf;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_verify_valid_index() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("index.json");
+        let mut project = Project::new("a".to_string(), PathBuf::from("/p/a"));
+        project.tags = vec!["rust".to_string()];
+        save_index(&path, &[project]).unwrap();
+
+        assert!(verify_index(&path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_verify_reports_problems() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("index.jsonl");
+        let lines = [
+            r#"{"name": "a", "path": "/p/a", "tags": ["rust"]}"#,
+            r#"{"name": "b", "path": "/p/b", "status": "sleeping"}"#,
+            r#"{"name": "c", "path": "/p/a"}"#,
+            "",
+            r#"{"name": "d", "path": "p/d", "tags": [" "]}"#,
+            r#"{"name": "e", "#,
+        ];
+        fs::write(&path, lines.join("\n")).unwrap();
+
+        let problems = verify_index(&path).unwrap();
+        let locations: Vec<&str> = problems.iter().map(|p| p.location.as_str()).collect();
+        assert_eq!(
+            locations,
+            vec!["line 6", "line 2", "line 3", "line 5", "line 5"]
+        );
+        assert!(problems[0].message.starts_with("invalid JSON"));
+        assert!(problems[1].message.contains("unknown variant `sleeping`"));
+        assert!(problems[2].message.contains("first seen at line 1"));
+        assert!(problems[3].message.contains("relative"));
+        assert!(problems[4].message.contains("empty tag"));
+    }
+
+    #[test]
+    fn test_verify_malformed_array() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("index.json");
+        fs::write(&path, r#"[{"name": "a", "path": "/p/a"},"#).unwrap();
+
+        let problems = verify_index(&path).unwrap();
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].location, "index");
+    }
+}
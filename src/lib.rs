@@ -55,6 +55,7 @@
 //!     let config = ClientConfig {
 //!         base_url: server.url(),
 //!         timeout: Duration::from_secs(30),
+//!         ..ClientConfig::default()
 //!     };
 //!     let client = OllamaClient::new(config)?;
 //!     let response = client.generate_tags("my-project").await?;
@@ -72,6 +73,7 @@
 //! - `ollama`: Ollama API client and integration
 //! - `ui`: User interface components and formatting
 //! - `error`: Error types and handling
+//! - `testing`: Fake Ollama server for tests (requires the `testing` feature)
 
 pub mod cli;
 pub mod config;
@@ -79,10 +81,12 @@ pub mod error;
 pub mod indexer;
 pub mod models;
 pub mod ollama;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod ui;
 
 pub use error::{AppError, Result};
-pub use indexer::ProjectIndexer;
+pub use indexer::{NoopReporter, ProgressReporter, ProjectIndexer};
 pub use models::{Project, ProjectStatus};
 pub use ollama::{ClientConfig, OllamaClient};
 
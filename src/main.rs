@@ -1,51 +1,156 @@
+use futures_util::StreamExt;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
 use std::path::PathBuf;
+use std::pin::pin;
 
 mod config;
 mod error;
 mod indexer;
 mod models;
 mod ollama;
+#[cfg(feature = "server")]
+mod server;
+#[cfg(test)]
+mod testing;
 mod ui;
 
-use clap::Parser;
-use error::AppError;
-use indexer::ProjectIndexer;
-use ollama::{check_ollama_installation, ensure_model_available, ClientConfig, OllamaClient};
-use ui::{print_banner, print_error, print_info, print_success};
-
-// Import CLI module
-use crate::cli::Cli;
+use error::{AppError, OllamaError};
+use indexer::{JsonReporter, ProgressReporter, ProjectIndexer, ProjectStats};
+use models::Project;
+use ollama::{
+    ensure_model_available, model_matches, normalize_base_url, parse_tag_examples, ApiStyle,
+    ClientConfig, OllamaClient, MAX_TAG_EXAMPLES,
+};
+use ui::{
+    create_spinner, format_count, is_interactive, print_banner, print_breakdown,
+    print_category_status, print_detailed_stats, print_error, print_index_diff, print_info,
+    print_match_score, print_project_details, print_readme_badges, print_recent_activity,
+    print_success, print_tag_comparison, print_tag_coverage, print_test_presence, print_timeline,
+    print_warning, prompt_confirm, prompt_select, IndicatifReporter,
+};
 
 mod cli;
 
 #[tokio::main]
 async fn main() -> Result<(), AppError> {
     // Parse command line arguments
-    let cli = Cli::parse();
+    let cli = cli::parse_args()?;
 
-    // Print banner
-    print_banner();
+    // Scripts reading JSON output get failures as JSON too
+    let json = cli.json_output();
+    // JSON results replace the decorated output
+    ui::set_quiet(cli.quiet || json);
+    match run(cli).await {
+        Err(e) if json => {
+            print_json_error(&e);
+            std::process::exit(1);
+        }
+        result => result,
+    }
+}
 
-    // Check for Ollama and model if needed
-    if cli.ollama {
-        if let Err(e) = ensure_model_available().await {
-            print_error(&format!("Ollama setup failed: {}", e));
-            return Err(e.into());
+/// Print an error to stderr as `{"error": {"kind": ..., "message": ...}}`
+fn print_json_error(error: &AppError) {
+    let error = serde_json::json!({
+        "error": {
+            "kind": error.kind(),
+            "message": error.to_string(),
+        }
+    });
+    eprintln!("{}", error);
+}
+
+/// Run the command given on the command line
+async fn run(cli: cli::Cli) -> Result<(), AppError> {
+    let json = cli.json_output();
+
+    // Auto-detection is console's default behavior
+    match cli.color_choice() {
+        cli::ColorChoice::Auto => {}
+        cli::ColorChoice::Always => {
+            console::set_colors_enabled(true);
+            console::set_colors_enabled_stderr(true);
         }
-        print_success("Ollama and required model are ready");
+        cli::ColorChoice::Never => {
+            console::set_colors_enabled(false);
+            console::set_colors_enabled_stderr(false);
+        }
+    }
+
+    // Keep stdout clean for machine-readable output
+    if !json
+        && !matches!(
+            cli.command,
+            cli::Commands::Stats {
+                timeline: true,
+                format: cli::TimelineFormat::Csv,
+                ..
+            } | cli::Commands::Export { output: None, .. }
+        )
+    {
+        print_banner();
+    }
+
+    // Settle whether the index may be written before any Ollama setup,
+    // which can pull and load a large model
+    if !confirm_overwrite(&cli.command, json)? {
+        return Ok(());
     }
 
+    // Check for Ollama and model if needed
+    // Re-tagging always needs Ollama
+    // Dry runs never generate tags
+    // Semantic searches embed their query
+    let use_ollama = (cli.ollama
+        && !matches!(cli.command, cli::Commands::Index { dry_run: true, .. }))
+        || matches!(
+            cli.command,
+            cli::Commands::Retag { .. }
+                | cli::Commands::GenerateTags { .. }
+                | cli::Commands::Search { semantic: true, .. }
+        );
+
     // Initialize Ollama client if needed
-    let ollama_client = if cli.ollama {
+    let ollama_client = if use_ollama {
+        let mut urls = cli.ollama_url.iter().map(|url| normalize_base_url(url));
         let config = ClientConfig {
-            base_url: cli.ollama_url.clone(),
-            timeout: std::time::Duration::from_secs(30),
+            base_url: urls.next().unwrap_or_default(),
+            timeout: std::time::Duration::from_secs(cli.ollama_timeout_secs),
+            model: cli.model.clone(),
+            api_key: cli.ollama_api_key.clone(),
+            api_style: cli.api_style,
+            extra_base_urls: urls.collect(),
+            max_retries: cli.ollama_retries,
+            retry_base_delay: std::time::Duration::from_millis(cli.ollama_retry_delay_ms),
         };
 
         match OllamaClient::new(config) {
-            Ok(client) => Some(client),
+            Ok(mut client) => {
+                let can_pull = cli.api_style == ApiStyle::Ollama;
+                if let Err(e) = ensure_configured_model(&mut client, can_pull).await {
+                    if !json {
+                        print_error(&format!("{}", e));
+                    }
+                    return Err(e);
+                }
+                if cli.warm_model {
+                    let spinner =
+                        create_spinner(&format!("Warming up model '{}'...", client.model()));
+                    let warmed = client.warm_up().await;
+                    spinner.finish_and_clear();
+                    // A failed warm-up only costs the load time later on
+                    if let Err(e) = warmed {
+                        print_warning(&format!("Model warm-up failed: {}", e));
+                    }
+                }
+                Some(client)
+            }
             Err(e) => {
-                print_error(&format!("Failed to initialize Ollama client: {}", e));
+                if !json {
+                    print_error(&format!("Failed to initialize Ollama client: {}", e));
+                }
                 return Err(e);
             }
         }
@@ -58,60 +163,922 @@ async fn main() -> Result<(), AppError> {
         cli::Commands::Index {
             projects_dir,
             output,
+            format,
             max_depth,
             min_depth,
+            projects_dir_depth,
             exclude,
+            exclude_paths,
+            max_files_per_project,
+            save_raw_responses,
+            mtime_source,
+            git_timeout_secs,
+            archive_threshold_days,
+            git_only,
+            abort_on_error,
+            parallel_walk,
+            max_concurrency,
+            progress_json,
+            category_depth,
+            category_from_manifest,
+            default_category,
+            detect_primary_language,
+            collect_file_types,
+            collect_dependencies,
+            clean_names,
+            tagging,
+            reuse_context,
+            embed,
+            embedding_cache,
+            preserve_manual_tags,
+            sort_tags,
+            since_commit,
+            resume,
+            incremental,
+            stream_write,
+            dry_run,
+            // Checked by `confirm_overwrite`
+            force: _,
         } => {
+            if embed && !cli.ollama {
+                return Err(OllamaError::ValidationError(
+                    "--embed needs --ollama to compute embeddings".to_string(),
+                )
+                .into());
+            }
+
+            let format = indexer::OutputFormat::resolve(format, &output);
+
             // Create indexer config
-            let config = indexer::project_indexer::IndexerConfig::new(
-                projects_dir,
-                output,
-                max_depth,
-                min_depth,
-                exclude,
-            );
+            let name_overrides = indexer::names::load_name_overrides(&output)?;
+            let mut config = tagging_config(
+                indexer::project_indexer::IndexerConfig::new(
+                    projects_dir,
+                    output.clone(),
+                    max_depth,
+                    min_depth,
+                    exclude,
+                ),
+                tagging,
+            )?;
+            match projects_dir_depth {
+                Some(cli::ProjectsDirDepth::Fixed(depth)) => {
+                    config.min_depth = depth;
+                    config.max_depth = depth;
+                }
+                Some(cli::ProjectsDirDepth::Auto) => {
+                    let exclude_dirs =
+                        indexer::project_indexer::parse_exclude_dirs(&config.exclude);
+                    match indexer::depth::detect_project_depth(
+                        &config.projects_dir,
+                        &exclude_dirs,
+                        indexer::depth::MAX_PROBE_DEPTH,
+                    ) {
+                        Some(depth) => {
+                            print_info(&format!("Detected projects at depth {}", depth));
+                            config.min_depth = depth as u32;
+                            config.max_depth = depth as u32;
+                        }
+                        None => print_warning(&format!(
+                            "No projects found while probing {}, scanning depths {} to {}",
+                            config.projects_dir.display(),
+                            config.min_depth,
+                            config.max_depth
+                        )),
+                    }
+                }
+                None => {}
+            }
+            config.exclude_paths = exclude_paths
+                .iter()
+                .map(|pattern| indexer::glob::PathGlob::new(pattern))
+                .collect::<error::Result<_>>()?;
+            config.max_files_per_project = max_files_per_project;
+            config.save_raw_responses = save_raw_responses;
+            config.mtime_source = mtime_source;
+            config.git_timeout = std::time::Duration::from_secs(git_timeout_secs);
+            config.archive_threshold_days = archive_threshold_days;
+            config.git_only = git_only;
+            config.abort_on_error = abort_on_error;
+            config.parallel_walk = parallel_walk;
+            config.max_concurrency = max_concurrency;
+            config.category_depth = category_depth;
+            config.category_from_manifest = category_from_manifest;
+            config.default_category = default_category;
+            config.name_overrides = name_overrides;
+            config.detect_primary_language = detect_primary_language;
+            config.collect_file_types = collect_file_types;
+            config.collect_dependencies = collect_dependencies;
+            config.clean_names = clean_names;
+            config.preserve_manual_tags = preserve_manual_tags;
+            config.reuse_generation_context = reuse_context;
+            config.embed_projects = embed;
+            config.embedding_cache = embedding_cache;
+            config.since_commit = since_commit;
+            config.resume = resume;
+            config.incremental = incremental;
+            config.output_format = format;
+            config.dry_run = dry_run;
+            config.sort_tags = sort_tags;
 
             // Create project indexer
             let indexer = ProjectIndexer::new(config, ollama_client);
 
             print_info("Starting project indexing...");
-            let projects = indexer.index_projects(|msg| print_info(msg)).await?;
-            print_success(&format!("Successfully indexed {} projects", projects.len()));
+            let reporter = progress_reporter(progress_json);
+            let (count, truncated) = if stream_write {
+                let mut writer = indexer::IndexWriter::create(&output)?;
+                let mut truncated = Vec::new();
+                let mut projects = pin!(indexer.stream(&*reporter));
+                while let Some(project) = projects.next().await {
+                    let project = project?;
+                    writer.write(&project)?;
+                    if project.size_truncated {
+                        truncated.push(project.name);
+                    }
+                }
+                let count = writer.count();
+                writer.finish()?;
+                indexer.save_embedding_cache()?;
+                reporter.on_finish(count);
+                (count, truncated)
+            } else if dry_run {
+                let projects = indexer.index_projects(&*reporter).await?;
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&projects)?);
+                    return Ok(());
+                }
+                for project in &projects {
+                    print_project_details(
+                        &project.name,
+                        &project.category,
+                        &format!("{:?}", project.status).to_lowercase(),
+                        &project.tags,
+                        &project.path.display().to_string(),
+                        project.last_modified,
+                    );
+                }
+                let report = ProjectStats::from_projects(&projects);
+                print_detailed_stats(
+                    report.total_projects,
+                    report.active_projects,
+                    report.stale_projects,
+                    report.archived_projects,
+                    report.unknown_projects,
+                    &report.projects_by_category,
+                    report.total_tags,
+                );
+                print_warning(&format!(
+                    "Dry run: {} projects would be indexed, {} left unchanged",
+                    projects.len(),
+                    output.display()
+                ));
+                return Ok(());
+            } else {
+                let projects = indexer.index_projects(&*reporter).await?;
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&projects)?);
+                    return Ok(());
+                }
+                let truncated = projects
+                    .iter()
+                    .filter(|p| p.size_truncated)
+                    .map(|p| p.name.clone())
+                    .collect();
+                (projects.len(), truncated)
+            };
+            for name in truncated {
+                print_warning(&format!(
+                    "{} has more than {} files; its size is only approximate",
+                    name, max_files_per_project
+                ));
+            }
+            print_success(&format!("Successfully indexed {} projects", count));
+
+            let usage = indexer.usage();
+            if usage.responses > 0 {
+                print_info(&format!(
+                    "Generated {} tokens in {:.0}s across {} projects",
+                    format_count(usage.tokens),
+                    usage.duration.as_secs_f64(),
+                    usage.responses
+                ));
+            }
+            if let Some(stats) = indexer.client_stats() {
+                if let Some(latency) = stats.average_latency() {
+                    print_info(&format!(
+                        "Made {} requests to the model ({} failed), {:.1}s on average",
+                        stats.requests,
+                        stats.failures,
+                        latency.as_secs_f64()
+                    ));
+                }
+            }
         }
         cli::Commands::Search {
             query,
             index_file,
             tags_only,
             category_only,
+            exact,
+            fuzzy,
+            semantic,
+            recent,
+            limit,
+            strict_json,
         } => {
-            // TODO: Implement search functionality
-            println!("Search functionality coming soon!");
-            println!("Query: {}", query);
-            println!("Index file: {}", index_file.display());
-            println!("Tags only: {}", tags_only);
-            println!("Category only: {}", category_only);
+            let fields = indexer::search::SearchFields::from_flags(tags_only, category_only);
+            if fuzzy || semantic {
+                let found = if semantic {
+                    indexer::require_index(&index_file)?;
+                    let client = ollama_client.as_ref().ok_or_else(|| {
+                        OllamaError::ValidationError(
+                            "--semantic needs an Ollama server to embed the query".to_string(),
+                        )
+                    })?;
+                    let embedding = client.create_embedding(&query).await?;
+                    indexer::search::semantic_search_index(
+                        &index_file,
+                        &embedding,
+                        strict_json,
+                        limit,
+                    )?
+                } else {
+                    indexer::search::fuzzy_search_index(
+                        &index_file,
+                        &query,
+                        fields,
+                        exact,
+                        strict_json,
+                        limit,
+                    )?
+                };
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&found)?);
+                    return Ok(());
+                }
+                if found.is_empty() {
+                    print_info(&format!("No projects closely match '{}'", query));
+                    return Ok(());
+                }
+                for found in &found {
+                    let project = &found.project;
+                    print_project_details(
+                        &project.name,
+                        &project.category,
+                        &format!("{:?}", project.status).to_lowercase(),
+                        &project.tags,
+                        &project.path.display().to_string(),
+                        project.last_modified,
+                    );
+                    print_match_score(found.score);
+                }
+                println!();
+                print_success(&format!(
+                    "Found {} close match{}",
+                    found.len(),
+                    if found.len() == 1 { "" } else { "es" }
+                ));
+                return Ok(());
+            }
+            let mut found =
+                indexer::search::search_index(&index_file, &query, fields, exact, strict_json)?;
+            if recent {
+                indexer::search::sort_by_recency(&mut found);
+            }
+            if json {
+                println!("{}", serde_json::to_string_pretty(&found)?);
+                return Ok(());
+            }
+            if found.is_empty() {
+                print_info(&format!("No projects match '{}'", query));
+                return Ok(());
+            }
+            for project in &found {
+                print_project_details(
+                    &project.name,
+                    &project.category,
+                    &format!("{:?}", project.status).to_lowercase(),
+                    &project.tags,
+                    &project.path.display().to_string(),
+                    project.last_modified,
+                );
+            }
+            println!();
+            print_success(&format!(
+                "Found {} matching project{}",
+                found.len(),
+                if found.len() == 1 { "" } else { "s" }
+            ));
         }
         cli::Commands::Stats {
             index_file,
             detailed,
+            top_tags,
+            timeline,
+            format,
+            empty_months,
+            strict_json,
         } => {
-            // TODO: Implement stats functionality
-            println!("Stats functionality coming soon!");
-            println!("Index file: {}", index_file.display());
-            println!("Detailed: {}", detailed);
+            indexer::require_index(&index_file)?;
+            let projects = indexer::stream_index_with(&index_file, strict_json)?;
+            if timeline {
+                let months = indexer::stats::activity_timeline(projects, empty_months)?;
+                match format {
+                    cli::TimelineFormat::Csv => {
+                        println!("year-month,count");
+                        for (month, count) in &months {
+                            println!("{},{}", month, count);
+                        }
+                    }
+                    cli::TimelineFormat::Text => print_timeline(&months),
+                }
+                return Ok(());
+            }
+
+            let mut report = ProjectStats::default();
+            for project in projects {
+                report.add(&project?);
+            }
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+                return Ok(());
+            }
+
+            print_detailed_stats(
+                report.total_projects,
+                report.active_projects,
+                report.stale_projects,
+                report.archived_projects,
+                report.unknown_projects,
+                &report.projects_by_category,
+                report.total_tags,
+            );
+            if detailed && !report.projects_by_tag.is_empty() {
+                let mut tags: Vec<_> = report.projects_by_tag.iter().collect();
+                tags.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+                let top: HashMap<String, usize> = tags
+                    .into_iter()
+                    .take(top_tags)
+                    .map(|(tag, count)| (tag.clone(), *count))
+                    .collect();
+                print_breakdown("Most Common Tags", &top);
+            }
+            if detailed {
+                let mut categories: Vec<_> = report
+                    .status_by_category
+                    .iter()
+                    .map(|(category, counts)| {
+                        (
+                            category.as_str(),
+                            [counts.active, counts.stale, counts.archived, counts.unknown],
+                        )
+                    })
+                    .collect();
+                categories.sort();
+                print_category_status(&categories);
+            }
+            print_tag_coverage(
+                report.distinct_tags,
+                report.average_tags_per_project,
+                report.untagged_projects,
+            );
+            print_test_presence(report.projects_with_tests, report.total_projects);
+            if report.projects_with_commit_date > 0 {
+                print_recent_activity(
+                    report.recently_committed_projects,
+                    report.projects_with_commit_date,
+                    indexer::stats::RECENT_COMMIT_DAYS,
+                );
+            }
+            print_readme_badges(report.projects_with_readme_badges, report.total_projects);
+            if report.projects_with_dockerfile + report.projects_with_compose > 0 {
+                let containers = HashMap::from([
+                    ("Dockerfile".to_string(), report.projects_with_dockerfile),
+                    ("Docker Compose".to_string(), report.projects_with_compose),
+                ]);
+                print_breakdown("Containerized Projects", &containers);
+            }
+            if !report.projects_by_type.is_empty() {
+                print_breakdown("Projects by Type", &report.projects_by_type);
+            }
+            if !report.projects_by_language.is_empty() {
+                print_breakdown("Projects by Primary Language", &report.projects_by_language);
+            }
+            if !report.files_by_extension.is_empty() {
+                let mut extensions: Vec<_> = report.files_by_extension.iter().collect();
+                extensions.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+                let top: HashMap<String, usize> = extensions
+                    .into_iter()
+                    .take(10)
+                    .map(|(extension, count)| (extension.clone(), *count))
+                    .collect();
+                print_breakdown("Most Common File Types", &top);
+            }
+            if !report.projects_by_dependency.is_empty() {
+                let mut dependencies: Vec<_> = report.projects_by_dependency.iter().collect();
+                dependencies.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+                let top: HashMap<String, usize> = dependencies
+                    .into_iter()
+                    .take(10)
+                    .map(|(dependency, count)| (dependency.clone(), *count))
+                    .collect();
+                print_breakdown("Most Common Dependencies", &top);
+            }
         }
         cli::Commands::GenerateTags {
             project_dir,
             output,
+            tags_format,
+            default_tags,
+            compare_models,
+        } => {
+            let config = indexer::project_indexer::IndexerConfig::new(
+                PathBuf::new(),
+                PathBuf::new(),
+                0,
+                0,
+                String::new(),
+            );
+            let name = project_dir
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned();
+
+            if !compare_models.is_empty() {
+                let mut results = Vec::with_capacity(compare_models.len());
+                for model in compare_models {
+                    let client = ollama_client.clone().map(|mut client| {
+                        client.set_model(model.clone());
+                        client
+                    });
+                    let indexer = ProjectIndexer::new(config.clone(), client);
+                    let mut project = Project::new(name.clone(), project_dir.clone());
+                    let spinner = create_spinner(&format!(
+                        "Generating tags for {} with {}...",
+                        project.name, model
+                    ));
+                    let tagged = indexer.tag_project(&mut project).await;
+                    spinner.finish_and_clear();
+                    results.push((model, tagged.then_some(project.tags)));
+                }
+                if results.iter().all(|(_, tags)| tags.is_none()) {
+                    return Err(OllamaError::Generation(format!(
+                        "request for {} failed with every model",
+                        name
+                    ))
+                    .into());
+                }
+
+                match output {
+                    Some(output) => {
+                        let models: Vec<_> = results
+                            .iter()
+                            .map(
+                                |(model, tags)| serde_json::json!({ "model": model, "tags": tags }),
+                            )
+                            .collect();
+                        let rendered = serde_json::to_string_pretty(&serde_json::json!({
+                            "project": name,
+                            "models": models,
+                        }))?;
+                        fs::write(&output, format!("{}\n", rendered))?;
+                        print_success(&format!(
+                            "Saved tags from {} models to {}",
+                            results.len(),
+                            output.display()
+                        ));
+                    }
+                    None => print_tag_comparison(&results),
+                }
+                return Ok(());
+            }
+
+            // Tag the project anyway when the server is down, just without Ollama
+            let unreachable = match &ollama_client {
+                Some(client) => match client.list_models().await {
+                    Err(AppError::Ollama(OllamaError::ConnectionError(e))) => Some(e),
+                    _ => None,
+                },
+                None => None,
+            };
+            let ollama_client = match &unreachable {
+                Some(e) => {
+                    print_warning(&format!(
+                        "Ollama is unreachable ({}); falling back to the default tags: {}",
+                        e,
+                        default_tags.join(", ")
+                    ));
+                    None
+                }
+                None => ollama_client,
+            };
+
+            let indexer = ProjectIndexer::new(config, ollama_client);
+            let mut project = Project::new(name, project_dir);
+
+            let spinner = create_spinner(&format!("Generating tags for {}...", project.name));
+            let tagged = indexer.tag_project(&mut project).await;
+            spinner.finish_and_clear();
+            if unreachable.is_some() && project.tags.is_empty() {
+                project.tags = default_tags;
+            }
+            if !tagged {
+                return Err(OllamaError::Generation(format!(
+                    "request for {} failed",
+                    project.name
+                ))
+                .into());
+            }
+
+            let rendered = render_tags(&project, tags_format)?;
+            match output {
+                Some(output) => {
+                    fs::write(&output, format!("{}\n", rendered))?;
+                    print_success(&format!(
+                        "Saved {} tags to {}",
+                        project.tags.len(),
+                        output.display()
+                    ));
+                }
+                None => println!("{}", rendered),
+            }
+        }
+        #[cfg(feature = "server")]
+        cli::Commands::Serve { index_file, addr } => {
+            print_info(&format!(
+                "Serving {} on http://{}",
+                index_file.display(),
+                addr
+            ));
+            server::serve(index_file, addr).await?;
+        }
+        cli::Commands::Retag {
+            index_file,
+            missing_only,
+            progress_json,
+            tagging,
+        } => {
+            let mut projects = indexer::load_index(&index_file)?;
+            let config = tagging_config(
+                indexer::project_indexer::IndexerConfig::new(
+                    PathBuf::new(),
+                    index_file.clone(),
+                    0,
+                    0,
+                    String::new(),
+                ),
+                tagging,
+            )?;
+            let indexer = ProjectIndexer::new(config, ollama_client);
+
+            let reporter = progress_reporter(progress_json);
+            reporter.on_scan_start();
+            reporter.on_scan_complete(
+                projects
+                    .iter()
+                    .filter(|p| !missing_only || p.tags.is_empty())
+                    .count(),
+            );
+            let (mut retagged, mut failed) = (0, 0);
+            for project in projects
+                .iter_mut()
+                .filter(|p| !missing_only || p.tags.is_empty())
+            {
+                reporter.on_project(&project.name);
+                let previous = (project.tags.clone(), project.generated_tags.clone());
+                if indexer.tag_project(project).await {
+                    retagged += 1;
+                } else {
+                    (project.tags, project.generated_tags) = previous;
+                    failed += 1;
+                }
+            }
+            reporter.on_finish(retagged);
+
+            indexer::save_index(&index_file, &projects)?;
+            if failed > 0 {
+                print_warning(&format!(
+                    "Failed to generate tags for {} projects; they keep their previous tags",
+                    failed
+                ));
+            }
+            print_success(&format!("Re-tagged {} projects", retagged));
+        }
+        cli::Commands::Verify { index_file } => {
+            let problems = indexer::verify::verify_index(&index_file)?;
+            if problems.is_empty() {
+                print_success(&format!("No problems found in {}", index_file.display()));
+                return Ok(());
+            }
+
+            for problem in &problems {
+                print_error(&problem.to_string());
+            }
+            return Err(OllamaError::ValidationError(format!(
+                "{} problem(s) found in {}",
+                problems.len(),
+                index_file.display()
+            ))
+            .into());
+        }
+        cli::Commands::Diff { old, new, format } => {
+            let diff = indexer::diff::diff_indexes(
+                &indexer::load_index(&old)?,
+                &indexer::load_index(&new)?,
+            );
+            match format {
+                cli::DiffFormat::Json => println!("{}", serde_json::to_string_pretty(&diff)?),
+                cli::DiffFormat::Text => print_index_diff(&diff),
+            }
+        }
+        cli::Commands::Export {
+            index_file,
+            format,
+            output,
         } => {
-            // TODO: Implement tag generation functionality
-            println!("Tag generation functionality coming soon!");
-            println!("Project directory: {}", project_dir.display());
-            if let Some(output) = output {
-                println!("Output file: {}", output.display());
+            indexer::require_index(&index_file)?;
+            let projects = indexer::load_index(&index_file)?;
+            match output {
+                Some(output) => {
+                    let format = indexer::OutputFormat::resolve(format, &output);
+                    indexer::save_index_as(&output, &projects, format)?;
+                    print_success(&format!(
+                        "Exported {} projects to {}",
+                        projects.len(),
+                        output.display()
+                    ));
+                }
+                None => print!(
+                    "{}",
+                    indexer::render_index(&projects, format.unwrap_or_default())?
+                ),
+            }
+        }
+        cli::Commands::SetName {
+            path,
+            name,
+            index_file,
+        } => {
+            let mut projects = indexer::load_index(&index_file)?;
+            let Some(project) = indexer::names::find_project_mut(&mut projects, &path) else {
+                return Err(OllamaError::ValidationError(format!(
+                    "No project at {} in {}",
+                    path.display(),
+                    index_file.display()
+                ))
+                .into());
+            };
+            project.name = name.clone();
+            let project_path = project.path.clone();
+
+            let mut overrides = indexer::names::load_name_overrides(&index_file)?;
+            overrides.insert(project_path.clone(), name.clone());
+            indexer::names::save_name_overrides(&index_file, &overrides)?;
+            indexer::save_index(&index_file, &projects)?;
+            print_success(&format!("Renamed {} to {}", project_path.display(), name));
+        }
+        cli::Commands::Prune {
+            under,
+            index_file,
+            dry_run,
+        } => {
+            let mut projects = indexer::load_index(&index_file)?;
+            let removed = indexer::maintenance::remove_projects_under(&mut projects, &under);
+
+            if removed.is_empty() {
+                print_info(&format!("No indexed projects under {}", under.display()));
+                return Ok(());
+            }
+
+            for project in &removed {
+                print_info(&format!("{} ({})", project.name, project.path.display()));
+            }
+
+            if dry_run {
+                print_warning(&format!(
+                    "Dry run: {} projects would be removed, {} kept, index left unchanged",
+                    removed.len(),
+                    projects.len()
+                ));
+            } else {
+                indexer::save_index(&index_file, &projects)?;
+                print_success(&format!(
+                    "Removed {} projects from {}, {} kept",
+                    removed.len(),
+                    index_file.display(),
+                    projects.len()
+                ));
+            }
+        }
+        cli::Commands::PruneTags {
+            index_file,
+            below,
+            dry_run,
+        } => {
+            let mut projects = indexer::load_index(&index_file)?;
+            let pruned = indexer::maintenance::prune_rare_tags(&mut projects, below);
+
+            if pruned.is_empty() {
+                print_info(&format!(
+                    "No tags are used by fewer than {} projects",
+                    below
+                ));
+                return Ok(());
+            }
+
+            for (tag, count) in &pruned {
+                print_info(&format!("{} (used by {} project(s))", tag, count));
+            }
+
+            if dry_run {
+                print_warning(&format!(
+                    "Dry run: {} tags would be pruned, index left unchanged",
+                    pruned.len()
+                ));
+            } else {
+                indexer::save_index(&index_file, &projects)?;
+                print_success(&format!(
+                    "Pruned {} tags from {}",
+                    pruned.len(),
+                    index_file.display()
+                ));
             }
         }
     }
 
     Ok(())
 }
+
+/// Check whether `index` may write its output file
+///
+/// An existing index is never clobbered without the user's consent;
+/// incremental runs update it rather than replacing it, and dry runs leave it
+/// alone. Without a terminal to ask on, `--force` is required. Returns
+/// `Ok(false)` when the user declines to overwrite the index.
+fn confirm_overwrite(command: &cli::Commands, json: bool) -> Result<bool, AppError> {
+    let cli::Commands::Index {
+        output,
+        force,
+        since_commit,
+        resume,
+        incremental,
+        dry_run,
+        ..
+    } = command
+    else {
+        return Ok(true);
+    };
+    if !output.exists() || *force || since_commit.is_some() || *resume || *incremental || *dry_run {
+        return Ok(true);
+    }
+
+    if !is_interactive() {
+        let message = format!(
+            "{} already exists; pass --force to overwrite it",
+            output.display()
+        );
+        if !json {
+            print_error(&message);
+        }
+        return Err(io::Error::new(io::ErrorKind::AlreadyExists, message).into());
+    }
+    if !prompt_confirm(&format!(
+        "{} already exists. Overwrite it?",
+        output.display()
+    )) {
+        print_info("Indexing cancelled, existing index left untouched");
+        return Ok(false);
+    }
+    Ok(true)
+}
+
+/// Apply the tag generation settings shared by `index` and `retag`
+fn tagging_config(
+    mut config: indexer::project_indexer::IndexerConfig,
+    tagging: cli::TaggingArgs,
+) -> Result<indexer::project_indexer::IndexerConfig, AppError> {
+    config.prefer_file_tags = tagging.prefer_file_tags;
+    config.flatten_categories = tagging.flatten_categories;
+    config.category_aliases = tagging.category_aliases;
+    config.tag_delimiter = tagging.tag_delimiter;
+    config.tag_min_confidence = tagging.tag_min_confidence;
+    config.tag_from_commits = tagging.tag_from_commits;
+    config.default_tags = tagging
+        .default_tags
+        .iter()
+        .map(|tag| tag.trim().to_lowercase())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    load_prompt_settings(&mut config, tagging.tag_examples, tagging.system_prompt)?;
+    Ok(config)
+}
+
+/// Read the tag examples and system prompt given for tag generation
+///
+/// A system prompt starting with `@` is read from the file it names.
+fn load_prompt_settings(
+    config: &mut indexer::project_indexer::IndexerConfig,
+    tag_examples: Option<PathBuf>,
+    system_prompt: Option<String>,
+) -> Result<(), AppError> {
+    if let Some(path) = tag_examples {
+        config.tag_examples = parse_tag_examples(&fs::read_to_string(&path)?);
+        if config.tag_examples.len() > MAX_TAG_EXAMPLES {
+            print_warning(&format!(
+                "{} contains {} examples; only the first {} are used",
+                path.display(),
+                config.tag_examples.len(),
+                MAX_TAG_EXAMPLES
+            ));
+        }
+    }
+    config.system_prompt = match system_prompt {
+        Some(prompt) => match prompt.strip_prefix('@') {
+            Some(path) => Some(fs::read_to_string(path)?.trim().to_string()),
+            None => Some(prompt),
+        },
+        None => None,
+    };
+    Ok(())
+}
+
+/// Progress reporter for indexing commands
+///
+/// The spinner is always drawn; with `progress_json`, events are also
+/// written to stderr as JSON lines.
+fn progress_reporter(progress_json: bool) -> Box<dyn ProgressReporter> {
+    if progress_json {
+        Box::new((IndicatifReporter::new(), JsonReporter::stderr()))
+    } else {
+        Box::new(IndicatifReporter::new())
+    }
+}
+
+/// Render a project's tags in the shape requested for `generate-tags`
+fn render_tags(project: &Project, format: cli::TagsFormat) -> Result<String, AppError> {
+    Ok(match format {
+        cli::TagsFormat::JsonArray => serde_json::to_string_pretty(&project.tags)?,
+        cli::TagsFormat::JsonObject => serde_json::to_string_pretty(&serde_json::json!({
+            "project": project.name,
+            "path": project.path,
+            "tags": project.tags,
+        }))?,
+        cli::TagsFormat::Lines => project.tags.join("\n"),
+        cli::TagsFormat::Comma => project.tags.join(", "),
+    })
+}
+
+/// Make sure the client's model is installed on the Ollama server
+///
+/// When the model is missing and we are attached to a terminal, the user is
+/// offered the installed models to pick from, and with `can_pull` to pull the
+/// model with `ollama pull`. Otherwise this fails with a hint on how to install
+/// the model. Nothing is pulled unless the user picks it.
+async fn ensure_configured_model(
+    client: &mut OllamaClient,
+    can_pull: bool,
+) -> Result<(), AppError> {
+    let installed = match client.list_models().await {
+        Ok(models) => models,
+        // Ollama is unreachable; tag generation reports its own errors
+        Err(_) => return Ok(()),
+    };
+
+    if installed
+        .iter()
+        .any(|model| model_matches(&model.name, client.model()))
+    {
+        return Ok(());
+    }
+
+    let names: Vec<String> = installed.into_iter().map(|model| model.name).collect();
+    if is_interactive() && !ui::is_quiet() && (can_pull || !names.is_empty()) {
+        print_warning(&format!("Model '{}' is not installed", client.model()));
+        let mut choices = names.clone();
+        if can_pull {
+            choices.push(format!("Pull '{}' with `ollama pull`", client.model()));
+        }
+        match prompt_select("Pick a model to use for tagging", &choices) {
+            Some(choice) if choice < names.len() => {
+                client.set_model(names[choice].clone());
+                print_success(&format!("Using model '{}'", client.model()));
+                return Ok(());
+            }
+            Some(_) => return Ok(ensure_model_available(client.model()).await?),
+            None => {}
+        }
+    }
+
+    let mut message = format!(
+        "Model '{}' is not installed. Pull it with `ollama pull {}`",
+        client.model(),
+        client.model()
+    );
+    if !names.is_empty() {
+        message.push_str(&format!(" (installed models: {})", names.join(", ")));
+    }
+    Err(OllamaError::Setup(message).into())
+}
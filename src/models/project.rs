@@ -4,7 +4,7 @@
 //! and their metadata in the indexer.
 
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::{collections::HashMap, path::PathBuf};
 
 /// Project status based on git repository state
 ///
@@ -18,7 +18,7 @@ use std::path::PathBuf;
 ///
 /// let status = ProjectStatus::Active;
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum ProjectStatus {
     /// Project is actively maintained
@@ -27,6 +27,12 @@ pub enum ProjectStatus {
     /// actively developed or maintained.
     Active,
 
+    /// Project is cooling off
+    ///
+    /// This status indicates that the project has had commits within the
+    /// archive threshold, but none recent enough for it to count as active.
+    Stale,
+
     /// Project is archived or no longer maintained
     ///
     /// This status indicates that the project has been archived or is no longer
@@ -43,6 +49,7 @@ pub enum ProjectStatus {
     /// - The directory is not a git repository
     /// - Git commands fail to execute
     /// - The repository is inaccessible
+    #[default]
     Unknown,
 }
 
@@ -64,6 +71,7 @@ pub enum ProjectStatus {
 ///     tags: vec!["test".to_string()],
 ///     category: "development".to_string(),
 ///     last_modified: chrono::Utc::now(),
+///     ..Project::new(String::new(), PathBuf::new())
 /// };
 ///
 /// assert_eq!(project.name, "my-project");
@@ -86,17 +94,26 @@ pub struct Project {
     /// This is used for navigation and file system operations.
     pub path: PathBuf,
 
+    /// Name of the project directory
+    ///
+    /// Only recorded when the indexer cleans up directory names into display
+    /// names, since `name` is the directory name otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dir_name: Option<String>,
+
     /// Category of the project
     ///
     /// The category is determined by the parent directory name in the
     /// projects directory structure. This helps organize projects into
     /// logical groups.
+    #[serde(default = "default_category")]
     pub category: String,
 
     /// Status of the project
     ///
     /// The current status of the project as determined by git repository
     /// analysis and other heuristics.
+    #[serde(default)]
     pub status: ProjectStatus,
 
     /// Technical tags for the project
@@ -106,12 +123,112 @@ pub struct Project {
     /// - Generated using Ollama AI
     /// - Manually specified
     /// - Default tags when AI generation is disabled
+    #[serde(default)]
     pub tags: Vec<String>,
 
+    /// Tags among `tags` that were generated by the model
+    ///
+    /// The remaining tags came from the project's `.tags` file or were added
+    /// by hand. Indexes that predate this field load with every tag counted
+    /// as manual.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub generated_tags: Vec<String>,
+
     /// Last modified date of the project
     ///
-    /// This field represents the last time the project's metadata was updated.
+    /// By default this is the time of the latest git commit, or the directory's
+    /// modification time for projects outside git. The indexer's `mtime_source`
+    /// setting selects how it is computed. Indexes that predate this field
+    /// load with the Unix epoch.
+    #[serde(default, alias = "lastModified")]
     pub last_modified: chrono::DateTime<chrono::Utc>,
+
+    /// Time of the latest git commit, or the directory's modification time
+    /// for projects outside git
+    ///
+    /// Unlike `last_modified`, this doesn't depend on the indexer's
+    /// `mtime_source`. Indexes that predate this field load without it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_commit_date: Option<chrono::DateTime<chrono::Utc>>,
+
+    /// Total size of the project's files in bytes
+    ///
+    /// Excluded directories are not counted. When `size_truncated` is set,
+    /// this is only the size of the files seen before the scan stopped.
+    #[serde(default, alias = "sizeBytes")]
+    pub size_bytes: u64,
+
+    /// Whether the size scan stopped early
+    ///
+    /// Set when the project holds more files than the configured
+    /// `max_files_per_project`, in which case `size_bytes` is a lower bound.
+    #[serde(default, alias = "sizeTruncated")]
+    pub size_truncated: bool,
+
+    /// Language with the most lines of code in the project
+    ///
+    /// Only populated when the indexer runs with primary language detection.
+    #[serde(
+        default,
+        alias = "primaryLanguage",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub primary_language: Option<String>,
+
+    /// Type of the project, from the build manifest at its root
+    ///
+    /// For example `rust` for a `Cargo.toml` or `node` for a `package.json`.
+    /// Unlike `primary_language`, it is always detected, since only the
+    /// project root is checked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_type: Option<String>,
+
+    /// Whether the project has a Dockerfile at its root
+    #[serde(default, alias = "hasDockerfile")]
+    pub has_dockerfile: bool,
+
+    /// Whether the project has a Docker Compose file at its root
+    #[serde(default, alias = "hasCompose")]
+    pub has_compose: bool,
+
+    /// Whether the project appears to contain tests
+    ///
+    /// Set when the project has a test directory such as `tests/` or
+    /// `spec/`, or files named like tests (`test_*.py`, `*_test.go`,
+    /// `*.spec.ts`, ...).
+    #[serde(default)]
+    pub has_tests: bool,
+
+    /// Whether the project's README shows status badges (CI, coverage, ...)
+    #[serde(default)]
+    pub has_readme_badges: bool,
+
+    /// First link in the project's README outside of its badges, usually
+    /// its homepage or documentation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub primary_link: Option<String>,
+
+    /// Number of files per extension, excluding build and dependency directories
+    ///
+    /// Only populated when the indexer runs with file type collection.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub file_types: HashMap<String, usize>,
+
+    /// Names of the dependencies declared in the project's build manifest
+    ///
+    /// Only populated when the indexer runs with dependency collection.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<String>,
+
+    /// Embedding of the project's name, tags and type, for semantic search
+    ///
+    /// Only populated when the indexer runs with embeddings enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding: Option<Vec<f32>>,
+}
+
+fn default_category() -> String {
+    "uncategorized".to_string()
 }
 
 impl Project {
@@ -119,12 +236,34 @@ impl Project {
         Self {
             name,
             path,
+            dir_name: None,
             category: "uncategorized".to_string(),
             status: ProjectStatus::Unknown,
             tags: Vec::new(),
+            generated_tags: Vec::new(),
             last_modified: chrono::Utc::now(),
+            last_commit_date: None,
+            size_bytes: 0,
+            size_truncated: false,
+            primary_language: None,
+            project_type: None,
+            has_dockerfile: false,
+            has_compose: false,
+            has_tests: false,
+            has_readme_badges: false,
+            primary_link: None,
+            file_types: HashMap::new(),
+            dependencies: Vec::new(),
+            embedding: None,
         }
     }
+
+    /// Tags that weren't generated by the model
+    pub fn manual_tags(&self) -> impl Iterator<Item = &String> {
+        self.tags
+            .iter()
+            .filter(|tag| !self.generated_tags.contains(tag))
+    }
 }
 
 #[cfg(test)]
@@ -143,4 +282,28 @@ mod tests {
         assert!(matches!(project.status, ProjectStatus::Unknown));
         assert!(project.tags.is_empty());
     }
+
+    #[test]
+    fn test_deserialize_legacy_project() {
+        let json = r#"{
+            "name": "legacy",
+            "path": "/path/to/legacy",
+            "category": "tools",
+            "status": "archived",
+            "tags": ["rust"]
+        }"#;
+
+        let project: Project = serde_json::from_str(json).unwrap();
+        assert_eq!(project.name, "legacy");
+        assert_eq!(project.status, ProjectStatus::Archived);
+        assert_eq!(project.tags, vec!["rust"]);
+        assert_eq!(project.last_modified.timestamp(), 0);
+        assert_eq!(project.size_bytes, 0);
+        assert_eq!(project.primary_language, None);
+        assert!(!project.has_dockerfile);
+
+        let minimal: Project = serde_json::from_str(r#"{"name": "a", "path": "/a"}"#).unwrap();
+        assert_eq!(minimal.category, "uncategorized");
+        assert_eq!(minimal.status, ProjectStatus::Unknown);
+    }
 }
@@ -1,7 +1,50 @@
-use crate::error::{OllamaError, Result};
-use reqwest::Client;
-use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use crate::error::{AppError, OllamaError, Result};
+use futures_util::{
+    future,
+    stream::{self, BoxStream},
+    Future, Stream, StreamExt, TryStreamExt,
+};
+use reqwest::{
+    header::{HeaderMap, HeaderValue, AUTHORIZATION},
+    Client, Response, StatusCode,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    fmt::Display,
+    iter,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+    time::{Duration, Instant},
+};
+
+/// Model used for generation when none is configured
+pub const DEFAULT_MODEL: &str = "mistral";
+
+/// Default time limit for a single request, in seconds
+pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// Number of times a failed generate request is retried by default
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// Default delay before the first retry of a generate request, in
+/// milliseconds; each further retry waits twice as long, up to
+/// [`MAX_RETRY_DELAY`]
+pub const DEFAULT_RETRY_BASE_DELAY_MS: u64 = 500;
+
+/// Longest wait between two attempts of a generate request
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// Maximum number of few-shot examples included in a tagging prompt
+pub const MAX_TAG_EXAMPLES: usize = 5;
+
+/// Number of embedding requests kept in flight by
+/// [`OllamaClient::embed_batch`] on servers without batch embeddings
+pub const EMBED_CONCURRENCY: usize = 4;
+
+/// Maximum number of commit messages included in a tagging prompt
+pub const MAX_COMMIT_MESSAGES: usize = 20;
 
 /// Configuration for the Ollama client
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -10,17 +53,98 @@ pub struct ClientConfig {
     pub base_url: String,
     /// Request timeout
     pub timeout: Duration,
+    /// Model used for generation requests
+    pub model: String,
+    /// Bearer token sent with every request, for servers behind an
+    /// authenticating proxy
+    #[serde(default, skip_serializing)]
+    pub api_key: Option<String>,
+    /// API flavor spoken by the server
+    #[serde(default)]
+    pub api_style: ApiStyle,
+    /// Further servers sharing the generate requests with `base_url`
+    ///
+    /// Requests go to each server in turn; a server that can't be reached is
+    /// skipped in favor of the next one.
+    #[serde(default)]
+    pub extra_base_urls: Vec<String>,
+    /// Number of times a generate request failing with a transient error is
+    /// retried
+    ///
+    /// Connection errors, timeouts and server errors (5xx) are transient;
+    /// errors the server reports about the request itself (4xx) are not.
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for each further retry up to
+    /// 30 seconds
+    #[serde(default = "default_retry_base_delay")]
+    pub retry_base_delay: Duration,
+}
+
+fn default_max_retries() -> u32 {
+    DEFAULT_MAX_RETRIES
+}
+
+fn default_retry_base_delay() -> Duration {
+    Duration::from_millis(DEFAULT_RETRY_BASE_DELAY_MS)
+}
+
+/// API flavor spoken by the server the client talks to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiStyle {
+    /// Ollama's native API (`/api/generate`, `/api/tags`)
+    #[default]
+    Ollama,
+    /// OpenAI-compatible API (`/v1/chat/completions`, `/v1/models`), as served
+    /// by llama.cpp and most gateways
+    Openai,
+}
+
+/// How tags are separated in a model response
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum TagDelimiter {
+    /// One tag list per line, each split on whichever of `,` and `;` is more
+    /// common in the response
+    #[default]
+    Auto,
+    /// Tags separated by commas
+    Comma,
+    /// One tag per line, as in bullet lists
+    Newline,
+    /// Tags separated by semicolons
+    Semicolon,
 }
 
 impl Default for ClientConfig {
     fn default() -> Self {
         Self {
             base_url: "http://localhost:11434".to_string(),
-            timeout: Duration::from_secs(30),
+            timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
+            model: DEFAULT_MODEL.to_string(),
+            api_key: None,
+            api_style: ApiStyle::default(),
+            extra_base_urls: Vec::new(),
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_base_delay: default_retry_base_delay(),
         }
     }
 }
 
+/// Turn an Ollama address into a base URL for the client
+///
+/// Accepts the `host:port` form used by `OLLAMA_HOST` as well as full URLs,
+/// defaulting to `http://` when no scheme is given.
+pub fn normalize_base_url(address: &str) -> String {
+    let address = address.trim().trim_end_matches('/');
+    if address.contains("://") {
+        address.to_string()
+    } else {
+        format!("http://{}", address)
+    }
+}
+
 /// Options for generating text
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GenerateOptions {
@@ -46,8 +170,20 @@ pub struct GenerateRequest {
     pub model: String,
     /// Prompt to generate text from
     pub prompt: String,
+    /// System prompt conditioning the model
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
     /// Generation options
     pub options: GenerateOptions,
+    /// Output format to enforce (Ollama accepts `"json"`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// Whether the reply is sent in chunks as it is generated
+    #[serde(default)]
+    pub stream: bool,
+    /// Context returned by a previous response, to continue from it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<Vec<u32>>,
 }
 
 /// Response from the Ollama API
@@ -55,6 +191,444 @@ pub struct GenerateRequest {
 pub struct GenerateResponse {
     /// Generated text
     pub response: String,
+    /// Total time spent on the request, in nanoseconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_duration: Option<u64>,
+    /// Number of tokens generated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eval_count: Option<u64>,
+    /// Time spent generating tokens, in nanoseconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eval_duration: Option<u64>,
+    /// Whether generation has finished
+    ///
+    /// Servers that don't report it are assumed to have finished.
+    #[serde(default = "default_done")]
+    pub done: bool,
+    /// Why generation stopped, e.g. `stop`, or `length` when the token limit
+    /// was reached
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub done_reason: Option<String>,
+    /// Encoding of the conversation so far, which a following request can
+    /// pass back to skip re-processing it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<Vec<u32>>,
+}
+
+fn default_done() -> bool {
+    true
+}
+
+impl GenerateResponse {
+    /// Whether the model was cut off before finishing its answer
+    ///
+    /// The end of a truncated response may be a partial word.
+    pub fn is_truncated(&self) -> bool {
+        !self.done || self.done_reason.as_deref() == Some("length")
+    }
+}
+
+/// Token and timing totals accumulated over several generate responses
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GenerationUsage {
+    /// Number of responses recorded
+    pub responses: usize,
+    /// Total tokens generated
+    pub tokens: u64,
+    /// Total time reported by the model
+    pub duration: Duration,
+}
+
+impl GenerationUsage {
+    /// Add the counters of a response to the totals
+    ///
+    /// Servers that don't report timings (such as OpenAI-compatible ones)
+    /// only contribute their token count.
+    pub fn record(&mut self, response: &GenerateResponse) {
+        self.responses += 1;
+        self.tokens += response.eval_count.unwrap_or(0);
+        self.duration += Duration::from_nanos(response.total_duration.unwrap_or(0));
+    }
+}
+
+/// Request counters of an [`OllamaClient`], shared by all its clones
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ClientStats {
+    /// Number of generate requests made
+    pub requests: usize,
+    /// Number of generate requests that failed
+    pub failures: usize,
+    /// Wall-clock time spent waiting for generate responses, including
+    /// failover to further servers
+    pub total_latency: Duration,
+}
+
+impl ClientStats {
+    /// Count a finished generate request
+    pub fn record(&mut self, succeeded: bool, latency: Duration) {
+        self.requests += 1;
+        if !succeeded {
+            self.failures += 1;
+        }
+        self.total_latency += latency;
+    }
+
+    /// Average time per request, or `None` before the first request
+    pub fn average_latency(&self) -> Option<Duration> {
+        let requests = u32::try_from(self.requests).ok()?;
+        self.total_latency.checked_div(requests)
+    }
+}
+
+/// Structured tag list requested from the model in JSON mode
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagsResponse {
+    /// Generated tags
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Project information used to build a tagging prompt
+#[derive(Debug, Clone, Default)]
+pub struct TagContext {
+    /// Path or name identifying the project
+    pub project: String,
+    /// Category the project is filed under, if known
+    pub category: Option<String>,
+    /// Example projects and their tags, shown to the model as few-shot examples
+    pub examples: Vec<TagExample>,
+    /// System prompt replacing the built-in tagging instruction
+    pub system_prompt: Option<String>,
+    /// Ask for a confidence score with each tag, as JSON
+    ///
+    /// The response is then read with [`parse_scored_tags`].
+    pub scored: bool,
+    /// Subjects of the project's recent commits, most recent first
+    pub commit_messages: Vec<String>,
+    /// Context primed with the system prompt by [`OllamaClient::prime_context`]
+    ///
+    /// The request then continues from it instead of sending the system
+    /// prompt, so Ollama doesn't need to process it again.
+    pub generation_context: Option<Vec<u32>>,
+}
+
+/// An example project and the tags it should receive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagExample {
+    /// Path or name identifying the example project
+    pub project: String,
+    /// Tags the example project is labeled with
+    pub tags: Vec<String>,
+}
+
+impl TagContext {
+    /// Create a context for a project without a category
+    pub fn new(project: impl Into<String>) -> Self {
+        Self {
+            project: project.into(),
+            category: None,
+            examples: Vec::new(),
+            system_prompt: None,
+            scored: false,
+            commit_messages: Vec::new(),
+            generation_context: None,
+        }
+    }
+
+    /// Build the system prompt for this project
+    ///
+    /// A configured `system_prompt` is used verbatim instead.
+    fn system_prompt(&self) -> String {
+        if let Some(system) = &self.system_prompt {
+            return system.clone();
+        }
+
+        let mut system = String::from(if self.scored {
+            "You are an assistant that labels software projects with short technical tags \
+            and rates how confident you are that each tag applies. \
+            Output ONLY JSON, no explanations or additional text."
+        } else {
+            "You are an assistant that labels software projects with short technical tags. \
+            Output ONLY comma-separated tags, no explanations or additional text."
+        });
+        if let Some(category) = &self.category {
+            system.push_str(&format!(
+                " The project belongs to the '{}' category; favor tags that matter for \
+                projects of that kind.",
+                category
+            ));
+        }
+        system
+    }
+
+    /// Build the task prompt for this project
+    ///
+    /// At most [`MAX_TAG_EXAMPLES`] examples are prepended to the prompt, and
+    /// at most [`MAX_COMMIT_MESSAGES`] commit messages are listed after it.
+    fn prompt(&self) -> String {
+        let mut prompt = String::new();
+        if !self.examples.is_empty() {
+            prompt.push_str("Here are example projects and their tags:\n");
+            for example in self.examples.iter().take(MAX_TAG_EXAMPLES) {
+                prompt.push_str(&format!(
+                    "Project: {}\nTags: {}\n",
+                    example.project,
+                    example.tags.join(", ")
+                ));
+            }
+            prompt.push('\n');
+        }
+        prompt.push_str(&format!(
+            "Generate 3-5 technical tags for this project: {}.",
+            self.project
+        ));
+        if let Some(category) = &self.category {
+            prompt.push_str(&format!(" Category: {}.", category));
+        }
+        if !self.commit_messages.is_empty() {
+            prompt.push_str(" Its recent commit messages:\n");
+            for message in self.commit_messages.iter().take(MAX_COMMIT_MESSAGES) {
+                prompt.push_str(&format!("- {}\n", message));
+            }
+        }
+        if self.scored {
+            prompt.push_str(
+                " Respond with a JSON object of the form \
+                {\"tags\": [{\"tag\": \"rust\", \"confidence\": 0.9}]}, \
+                where confidence is between 0 and 1.",
+            );
+        } else {
+            prompt
+                .push_str(" Output ONLY comma-separated tags, no explanations or additional text.");
+        }
+        prompt
+    }
+}
+
+/// A model installed on the Ollama server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelInfo {
+    /// Model name, including its tag (e.g. `mistral:latest`)
+    pub name: String,
+}
+
+/// Response from the `/api/tags` endpoint
+#[derive(Debug, Clone, Deserialize)]
+struct ModelsResponse {
+    #[serde(default)]
+    models: Vec<ModelInfo>,
+}
+
+/// Request for the OpenAI-compatible `/v1/chat/completions` endpoint
+#[derive(Debug, Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: Vec<ChatMessage<'a>>,
+    temperature: f64,
+    max_tokens: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatMessage<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+/// Response from the OpenAI-compatible `/v1/chat/completions` endpoint
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+    #[serde(default)]
+    usage: Option<ChatUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatUsage {
+    #[serde(default)]
+    completion_tokens: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+    message: ChatResponseMessage,
+    #[serde(default)]
+    finish_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponseMessage {
+    #[serde(default)]
+    content: String,
+}
+
+/// Response from the OpenAI-compatible `/v1/models` endpoint
+#[derive(Debug, Deserialize)]
+struct OpenAiModelsResponse {
+    #[serde(default)]
+    data: Vec<OpenAiModel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiModel {
+    id: String,
+}
+
+/// Response from the `/api/embeddings` endpoint
+#[derive(Debug, Deserialize)]
+struct EmbeddingResponse {
+    embedding: Vec<f32>,
+}
+
+/// Response from the batch `/api/embed` endpoint
+#[derive(Debug, Deserialize)]
+struct EmbedBatchResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
+/// Response from the OpenAI-compatible `/v1/embeddings` endpoint
+#[derive(Debug, Deserialize)]
+struct OpenAiEmbeddingResponse {
+    data: Vec<OpenAiEmbedding>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiEmbedding {
+    embedding: Vec<f32>,
+    /// Position of the input the embedding belongs to
+    #[serde(default)]
+    index: usize,
+}
+
+/// Extract tags from a raw model response
+///
+/// Tags are split on newlines and on whichever of commas and semicolons the
+/// response uses most. See [`parse_tags_with`].
+pub fn parse_tags(response: &str) -> Vec<String> {
+    parse_tags_with(response, TagDelimiter::Auto)
+}
+
+/// Extract tags from a raw model response using the given delimiter
+///
+/// Bullet and numbering markers (`- `, `* `, `1. `) are removed, and tags are
+/// lowercased and stripped of stray punctuation. Returns no tags when nothing
+/// usable is found.
+pub fn parse_tags_with(response: &str, delimiter: TagDelimiter) -> Vec<String> {
+    let response = response.trim();
+    let items: Vec<&str> = match delimiter {
+        TagDelimiter::Auto => {
+            let separator = if response.matches(';').count() > response.matches(',').count() {
+                ';'
+            } else {
+                ','
+            };
+            response
+                .lines()
+                .flat_map(|line| line.split(separator))
+                .collect()
+        }
+        TagDelimiter::Comma => response.split(',').collect(),
+        TagDelimiter::Newline => response.lines().collect(),
+        TagDelimiter::Semicolon => response.split(';').collect(),
+    };
+
+    items
+        .into_iter()
+        .map(|tag| strip_list_marker(tag.trim()).trim().to_lowercase())
+        .map(|tag| tag.replace(&['*', ':', '.', '(', ')', '[', ']', '{', '}'][..], ""))
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+/// A tag in a scored response: either a bare tag or a tag with its confidence
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ScoredTag {
+    Plain(String),
+    Scored {
+        tag: String,
+        #[serde(default)]
+        confidence: Option<f32>,
+    },
+}
+
+/// Scored tags, either wrapped in a `tags` object or as a bare array
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ScoredTags {
+    Wrapped { tags: Vec<ScoredTag> },
+    List(Vec<ScoredTag>),
+}
+
+/// Extract the tags with a confidence of at least `min_confidence` from a
+/// JSON response requested with [`TagContext::scored`]
+///
+/// Tags the model gave no confidence for are kept. Returns `None` when the
+/// response isn't JSON of the expected shape, so callers can fall back to
+/// [`parse_tags_with`] for models that ignore the requested format.
+pub fn parse_scored_tags(response: &str, min_confidence: f32) -> Option<Vec<String>> {
+    let scored = match serde_json::from_str(response.trim()).ok()? {
+        ScoredTags::Wrapped { tags } | ScoredTags::List(tags) => tags,
+    };
+
+    let mut tags: Vec<String> = Vec::new();
+    for item in scored {
+        let (tag, confidence) = match item {
+            ScoredTag::Plain(tag) => (tag, None),
+            ScoredTag::Scored { tag, confidence } => (tag, confidence),
+        };
+        let tag = tag.trim().to_lowercase();
+        if tag.is_empty() || tags.contains(&tag) {
+            continue;
+        }
+        if confidence.is_none_or(|confidence| confidence >= min_confidence) {
+            tags.push(tag);
+        }
+    }
+    Some(tags)
+}
+
+/// Remove a leading bullet (`-`, `*`, `+`, `•`) or number (`1.`, `1)`) marker
+fn strip_list_marker(item: &str) -> &str {
+    for bullet in ["- ", "* ", "+ ", "• "] {
+        if let Some(rest) = item.strip_prefix(bullet) {
+            return rest;
+        }
+    }
+
+    let digits = item.len() - item.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits > 0 {
+        let rest = &item[digits..];
+        if let Some(rest) = rest.strip_prefix(". ").or_else(|| rest.strip_prefix(") ")) {
+            return rest;
+        }
+    }
+    item
+}
+
+/// Parse few-shot tag examples
+///
+/// Each non-empty line has the form `project: tag1, tag2`. Lines starting with
+/// `#` and lines without tags are skipped.
+pub fn parse_tag_examples(contents: &str) -> Vec<TagExample> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (project, tags) = line.rsplit_once(':')?;
+            let tags: Vec<String> = tags
+                .split(',')
+                .map(|tag| tag.trim().to_lowercase())
+                .filter(|tag| !tag.is_empty())
+                .collect();
+            (!tags.is_empty()).then(|| TagExample {
+                project: project.trim().to_string(),
+                tags,
+            })
+        })
+        .collect()
 }
 
 /// Client for interacting with the Ollama API
@@ -62,68 +636,946 @@ pub struct GenerateResponse {
 pub struct OllamaClient {
     config: ClientConfig,
     client: Client,
+    /// Rotation counter over the configured servers, shared between clones
+    next_server: Arc<AtomicUsize>,
+    /// Request counters, shared between clones
+    stats: Arc<Mutex<ClientStats>>,
 }
 
 impl OllamaClient {
     /// Create a new Ollama client with the given configuration
     pub fn new(config: ClientConfig) -> Result<Self> {
+        let mut headers = HeaderMap::new();
+        if let Some(api_key) = &config.api_key {
+            let mut value = HeaderValue::from_str(&format!("Bearer {}", api_key))
+                .map_err(|_| OllamaError::ValidationError("Invalid API key".to_string()))?;
+            value.set_sensitive(true);
+            headers.insert(AUTHORIZATION, value);
+        }
+
         let client = Client::builder()
             .timeout(config.timeout)
+            .default_headers(headers)
             .build()
             .map_err(|e| OllamaError::ConnectionError(e.to_string()))?;
 
-        Ok(Self { config, client })
+        Ok(Self {
+            config,
+            client,
+            next_server: Arc::new(AtomicUsize::new(0)),
+            stats: Arc::new(Mutex::new(ClientStats::default())),
+        })
+    }
+
+    /// Name of the model used for generation
+    pub fn model(&self) -> &str {
+        &self.config.model
+    }
+
+    /// Change the model used for generation
+    pub fn set_model(&mut self, model: impl Into<String>) {
+        self.config.model = model.into();
+    }
+
+    /// Counters of the generate requests made so far
+    ///
+    /// Clones of a client share their counters, so this covers requests made
+    /// through any of them.
+    pub fn stats(&self) -> ClientStats {
+        *self.lock_stats()
+    }
+
+    fn lock_stats(&self) -> MutexGuard<'_, ClientStats> {
+        self.stats.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// List the models installed on the Ollama server
+    pub async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        let request = match self.config.api_style {
+            ApiStyle::Ollama => self
+                .client
+                .get(format!("{}/api/tags", self.config.base_url)),
+            ApiStyle::Openai => self
+                .client
+                .get(format!("{}/v1/models", self.config.base_url)),
+        };
+        let response = request
+            .send()
+            .await
+            .map_err(|e| OllamaError::ConnectionError(e.to_string()))?;
+
+        match self.config.api_style {
+            ApiStyle::Ollama => {
+                let response = response
+                    .json::<ModelsResponse>()
+                    .await
+                    .map_err(|e| OllamaError::ParseError(e.to_string()))?;
+                Ok(response.models)
+            }
+            ApiStyle::Openai => {
+                let response = response
+                    .json::<OpenAiModelsResponse>()
+                    .await
+                    .map_err(|e| OllamaError::ParseError(e.to_string()))?;
+                Ok(response
+                    .data
+                    .into_iter()
+                    .map(|model| ModelInfo { name: model.id })
+                    .collect())
+            }
+        }
+    }
+
+    /// Compute an embedding vector for a piece of text with the configured model
+    pub async fn create_embedding(&self, text: &str) -> Result<Vec<f32>> {
+        self.embed_one(&self.config.model, text).await
+    }
+
+    /// Compute embedding vectors for several pieces of text with `model`
+    ///
+    /// Embeddings are returned in the order of `inputs`. Ollama servers are
+    /// sent all inputs at once through `/api/embed`; servers too old to have
+    /// it are sent one `/api/embeddings` request per input instead, at most
+    /// [`EMBED_CONCURRENCY`] at a time. OpenAI-compatible servers always get
+    /// a single request.
+    pub async fn embed_batch(&self, model: &str, inputs: &[String]) -> Result<Vec<Vec<f32>>> {
+        if inputs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let embeddings = match self.config.api_style {
+            ApiStyle::Ollama => {
+                let response = self
+                    .client
+                    .post(format!("{}/api/embed", self.config.base_url))
+                    .json(&serde_json::json!({ "model": model, "input": inputs }))
+                    .send()
+                    .await
+                    .map_err(|e| OllamaError::ConnectionError(e.to_string()))?;
+                if response.status() == StatusCode::NOT_FOUND {
+                    return stream::iter(inputs)
+                        .map(|input| self.embed_one(model, input))
+                        .buffered(EMBED_CONCURRENCY)
+                        .try_collect()
+                        .await;
+                }
+                response
+                    .json::<EmbedBatchResponse>()
+                    .await
+                    .map_err(|e| OllamaError::ParseError(e.to_string()))?
+                    .embeddings
+            }
+            ApiStyle::Openai => self.embed_openai(model, inputs).await?,
+        };
+
+        if embeddings.len() != inputs.len() {
+            return Err(OllamaError::ParseError(format!(
+                "Expected {} embeddings, got {}",
+                inputs.len(),
+                embeddings.len()
+            ))
+            .into());
+        }
+        Ok(embeddings)
+    }
+
+    /// Compute the embedding of a single piece of text with `model`
+    async fn embed_one(&self, model: &str, text: &str) -> Result<Vec<f32>> {
+        match self.config.api_style {
+            ApiStyle::Ollama => {
+                let response = self
+                    .client
+                    .post(format!("{}/api/embeddings", self.config.base_url))
+                    .json(&serde_json::json!({ "model": model, "prompt": text }))
+                    .send()
+                    .await
+                    .map_err(|e| OllamaError::ConnectionError(e.to_string()))?;
+                Ok(response
+                    .json::<EmbeddingResponse>()
+                    .await
+                    .map_err(|e| OllamaError::ParseError(e.to_string()))?
+                    .embedding)
+            }
+            ApiStyle::Openai => self
+                .embed_openai(model, &[text])
+                .await?
+                .into_iter()
+                .next()
+                .ok_or_else(|| {
+                    OllamaError::ParseError("Response contained no embedding".to_string()).into()
+                }),
+        }
+    }
+
+    /// Compute embeddings through the OpenAI-compatible `/v1/embeddings`
+    /// endpoint, in the order of `inputs`
+    async fn embed_openai(&self, model: &str, inputs: &[impl Serialize]) -> Result<Vec<Vec<f32>>> {
+        let response = self
+            .client
+            .post(format!("{}/v1/embeddings", self.config.base_url))
+            .json(&serde_json::json!({ "model": model, "input": inputs }))
+            .send()
+            .await
+            .map_err(|e| OllamaError::ConnectionError(e.to_string()))?;
+        let mut data = response
+            .json::<OpenAiEmbeddingResponse>()
+            .await
+            .map_err(|e| OllamaError::ParseError(e.to_string()))?
+            .data;
+        data.sort_by_key(|embedding| embedding.index);
+        Ok(data.into_iter().map(|data| data.embedding).collect())
     }
 
     /// Generate tags for a project
     pub async fn generate_tags(&self, project_path: &str) -> Result<Vec<String>> {
+        self.generate_tags_with_context(&TagContext::new(project_path))
+            .await
+    }
+
+    /// Generate tags for a project, tailoring the prompt to its category
+    pub async fn generate_tags_with_context(&self, context: &TagContext) -> Result<Vec<String>> {
+        let response = self.generate_tags_response(context).await?;
+        Ok(parse_tags(&response.response))
+    }
+
+    /// Generate the raw model response for a tagging prompt, without parsing it
+    ///
+    /// The response carries the model's token counts and timings alongside
+    /// the generated text.
+    pub async fn generate_tags_response(&self, context: &TagContext) -> Result<GenerateResponse> {
+        let request = GenerateRequest {
+            model: self.config.model.clone(),
+            prompt: context.prompt(),
+            system: context
+                .generation_context
+                .is_none()
+                .then(|| context.system_prompt()),
+            options: GenerateOptions::default(),
+            format: context.scored.then(|| "json".to_string()),
+            stream: false,
+            context: context.generation_context.clone(),
+        };
+
+        self.send_generate(&request).await
+    }
+
+    /// Encode the system prompt of a tagging context once, for reuse
+    ///
+    /// Ollama only returns a context after generating, so the system prompt
+    /// is followed by a minimal prompt answered with a single token. The
+    /// context is the same for every project, which keeps their tags
+    /// independent of each other. Returns `None` for servers that don't
+    /// return a context, such as OpenAI-compatible ones.
+    pub async fn prime_context(&self, context: &TagContext) -> Result<Option<Vec<u32>>> {
+        let request = GenerateRequest {
+            model: self.config.model.clone(),
+            prompt: "ok".to_string(),
+            system: Some(context.system_prompt()),
+            options: GenerateOptions {
+                max_tokens: 1,
+                ..GenerateOptions::default()
+            },
+            format: None,
+            stream: false,
+            context: None,
+        };
+
+        Ok(self.send_generate(&request).await?.context)
+    }
+
+    /// Load the model into memory with a minimal generate request
+    ///
+    /// The first request to a model pays for loading it; sending a throwaway
+    /// prompt up front keeps that delay out of the first real request.
+    pub async fn warm_up(&self) -> Result<()> {
+        let request = GenerateRequest {
+            model: self.config.model.clone(),
+            prompt: "ok".to_string(),
+            system: None,
+            options: GenerateOptions {
+                max_tokens: 1,
+                ..GenerateOptions::default()
+            },
+            format: None,
+            stream: false,
+            context: None,
+        };
+
+        self.send_generate(&request).await?;
+        Ok(())
+    }
+
+    /// Send a generate request and stream the reply as it is generated
+    ///
+    /// The request is sent with `stream: true`, and each chunk of the reply
+    /// is yielded as soon as it arrives. Concatenating the `response` of the
+    /// chunks gives the full text; the last chunk has `done` set and carries
+    /// the token counts and timings. OpenAI-compatible servers answer in a
+    /// single chunk.
+    ///
+    /// Servers are tried in turn as for other requests; errors met once the
+    /// reply has started are yielded by the stream.
+    pub async fn generate_stream(
+        &self,
+        request: &GenerateRequest,
+    ) -> Result<BoxStream<'static, Result<GenerateResponse>>> {
+        let request = GenerateRequest {
+            stream: true,
+            ..request.clone()
+        };
+        let started = Instant::now();
+        let result = self
+            .with_failover(|base_url| {
+                let request = &request;
+                async move {
+                    match self.config.api_style {
+                        ApiStyle::Ollama => Ok(generate_chunks(
+                            self.post_ollama_generate(base_url, request).await?,
+                        )),
+                        ApiStyle::Openai => {
+                            let response = self.send_chat_completion(base_url, request).await?;
+                            Ok(stream::once(future::ready(Ok(response))).boxed())
+                        }
+                    }
+                }
+            })
+            .await;
+        self.lock_stats().record(result.is_ok(), started.elapsed());
+        result
+    }
+
+    /// Generate a response in JSON mode and deserialize it into `T`
+    ///
+    /// The request sets `format: "json"` so Ollama is constrained to emit valid
+    /// JSON, which is then parsed directly instead of being split as free text.
+    pub async fn generate_json<T: DeserializeOwned>(&self, prompt: &str) -> Result<T> {
+        let request = GenerateRequest {
+            model: self.config.model.clone(),
+            prompt: prompt.to_string(),
+            system: None,
+            options: GenerateOptions::default(),
+            format: Some("json".to_string()),
+            stream: false,
+            context: None,
+        };
+
+        let response = self.send_generate(&request).await?;
+        let parsed = serde_json::from_str(&response.response)
+            .map_err(|e| OllamaError::ParseError(e.to_string()))?;
+        Ok(parsed)
+    }
+
+    /// Generate tags for a project using structured JSON output
+    pub async fn generate_tags_json(&self, project_path: &str) -> Result<Vec<String>> {
         let prompt = format!(
             "Generate 3-5 technical tags for this project: {}. \
-            Output ONLY comma-separated tags, no explanations or additional text.",
+            Respond with a JSON object of the form {{\"tags\": [\"tag1\", \"tag2\"]}}.",
             project_path
         );
 
-        let request = GenerateRequest {
-            model: "mistral".to_string(),
-            prompt,
-            options: GenerateOptions::default(),
+        let response: TagsResponse = self.generate_json(&prompt).await?;
+        let mut tags: Vec<String> = Vec::new();
+        for tag in response.tags {
+            let tag = tag.trim().to_lowercase();
+            if !tag.is_empty() && !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        Ok(tags)
+    }
+
+    /// Servers to send the next generate request to, in the order to try them
+    ///
+    /// Each call starts one server further along, spreading requests across
+    /// all configured servers.
+    fn rotated_servers(&self) -> Vec<&str> {
+        let servers: Vec<&str> = iter::once(&self.config.base_url)
+            .chain(&self.config.extra_base_urls)
+            .map(String::as_str)
+            .collect();
+        let start = self.next_server.fetch_add(1, Ordering::Relaxed) % servers.len();
+        servers[start..]
+            .iter()
+            .chain(&servers[..start])
+            .copied()
+            .collect()
+    }
+
+    /// Send a generate request and decode the response body
+    ///
+    /// Servers that can't be reached are skipped; the request only fails
+    /// with a connection error once every server has been tried. Transient
+    /// failures are then retried up to `max_retries` times, with exponential
+    /// backoff capped at [`MAX_RETRY_DELAY`].
+    async fn send_generate(&self, request: &GenerateRequest) -> Result<GenerateResponse> {
+        let mut attempt = 0;
+        loop {
+            let started = Instant::now();
+            let result = self.send_generate_with_failover(request).await;
+            self.lock_stats().record(result.is_ok(), started.elapsed());
+            match result {
+                Err(e) if attempt < self.config.max_retries && is_transient(&e) => {
+                    let delay = retry_delay(self.config.retry_base_delay, attempt);
+                    attempt += 1;
+                    tracing::warn!(
+                        "Generate request failed ({}), retrying in {:?} ({}/{})",
+                        e,
+                        delay,
+                        attempt,
+                        self.config.max_retries
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Send a generate request to each server in turn until one is reachable
+    async fn send_generate_with_failover(
+        &self,
+        request: &GenerateRequest,
+    ) -> Result<GenerateResponse> {
+        self.with_failover(|base_url| async move {
+            match self.config.api_style {
+                ApiStyle::Ollama => self.send_ollama_generate(base_url, request).await,
+                ApiStyle::Openai => self.send_chat_completion(base_url, request).await,
+            }
+        })
+        .await
+    }
+
+    /// Call `send` with each server in turn until one is reachable
+    async fn with_failover<'a, T, F, Fut>(&'a self, send: F) -> Result<T>
+    where
+        F: Fn(&'a str) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut last_error = None;
+        for base_url in self.rotated_servers() {
+            match send(base_url).await {
+                Err(AppError::Ollama(OllamaError::ConnectionError(e))) => {
+                    tracing::warn!("Skipping unreachable server {}: {}", base_url, e);
+                    last_error = Some(OllamaError::ConnectionError(e));
+                }
+                result => return result,
+            }
+        }
+        Err(last_error
+            .unwrap_or_else(|| OllamaError::ConnectionError("No servers configured".to_string()))
+            .into())
+    }
+
+    /// Send a generate request to Ollama's native endpoint
+    ///
+    /// The chunks of a streamed reply are collected into a single response.
+    async fn send_ollama_generate(
+        &self,
+        base_url: &str,
+        request: &GenerateRequest,
+    ) -> Result<GenerateResponse> {
+        let response = self.post_ollama_generate(base_url, request).await?;
+        collect_chunks(generate_chunks(response)).await
+    }
+
+    /// Post a generate request to Ollama's native endpoint
+    async fn post_ollama_generate(
+        &self,
+        base_url: &str,
+        request: &GenerateRequest,
+    ) -> Result<Response> {
+        let response = self
+            .client
+            .post(format!("{}/api/generate", base_url))
+            .json(request)
+            .send()
+            .await
+            .map_err(|e| OllamaError::ConnectionError(e.to_string()))?;
+        check_status(response).await
+    }
+
+    /// Send a generate request to an OpenAI-compatible chat completions endpoint
+    async fn send_chat_completion(
+        &self,
+        base_url: &str,
+        request: &GenerateRequest,
+    ) -> Result<GenerateResponse> {
+        let mut messages = Vec::new();
+        if let Some(system) = &request.system {
+            messages.push(ChatMessage {
+                role: "system",
+                content: system,
+            });
+        }
+        messages.push(ChatMessage {
+            role: "user",
+            content: &request.prompt,
+        });
+
+        let chat = ChatRequest {
+            model: &request.model,
+            messages,
+            temperature: request.options.temperature,
+            max_tokens: request.options.max_tokens,
+            response_format: request
+                .format
+                .as_deref()
+                .filter(|format| *format == "json")
+                .map(|_| serde_json::json!({ "type": "json_object" })),
         };
 
         let response = self
             .client
-            .post(format!("{}/api/generate", self.config.base_url))
-            .json(&request)
+            .post(format!("{}/v1/chat/completions", base_url))
+            .json(&chat)
             .send()
             .await
-            .map_err(|e| OllamaError::ConnectionError(e.to_string()))?
-            .json::<GenerateResponse>()
+            .map_err(|e| OllamaError::ConnectionError(e.to_string()))?;
+        let response = check_status(response)
+            .await?
+            .json::<ChatResponse>()
             .await
             .map_err(|e| OllamaError::ParseError(e.to_string()))?;
 
-        // Clean up the response and extract tags
-        let tags: Vec<String> = response
-            .response
-            .trim()
-            .lines()
-            .flat_map(|line| line.split(','))
-            .map(|tag| tag.trim().to_lowercase())
-            .filter(|tag| !tag.is_empty())
-            .map(|tag| tag.replace(&['*', ':', '.', '(', ')', '[', ']', '{', '}'][..], ""))
-            .collect();
+        let eval_count = response.usage.map(|usage| usage.completion_tokens);
+        let choice =
+            response.choices.into_iter().next().ok_or_else(|| {
+                OllamaError::ParseError("Response contained no choices".to_string())
+            })?;
+        Ok(GenerateResponse {
+            response: choice.message.content,
+            total_duration: None,
+            eval_count,
+            eval_duration: None,
+            done: true,
+            done_reason: choice.finish_reason,
+            context: None,
+        })
+    }
+}
 
-        if tags.is_empty() {
-            Ok(vec!["rust".to_string(), "cli".to_string()])
-        } else {
-            Ok(tags)
-        }
+/// Turn an error status of a response into an API error
+///
+/// The message is the `error` field both Ollama and OpenAI-compatible
+/// servers put in their error bodies, or else the body itself.
+async fn check_status(response: Response) -> Result<Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    let message = serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|error| match &error["error"] {
+            serde_json::Value::String(message) => Some(message.clone()),
+            serde_json::Value::Object(error) => error
+                .get("message")
+                .and_then(|message| message.as_str())
+                .map(str::to_string),
+            _ => None,
+        })
+        .unwrap_or(body);
+    Err(OllamaError::ApiError {
+        message,
+        status_code: Some(status.as_u16()),
     }
+    .into())
+}
+
+/// Check whether a failed request may succeed when sent again
+///
+/// Connection errors, which include timeouts, and server errors (5xx) are
+/// transient; anything else would fail the same way again.
+fn is_transient(error: &AppError) -> bool {
+    match error {
+        AppError::Ollama(OllamaError::ConnectionError(_)) => true,
+        AppError::Ollama(OllamaError::ApiError {
+            status_code: Some(code),
+            ..
+        }) => *code >= 500,
+        _ => false,
+    }
+}
+
+/// Delay before retrying a request that failed `attempt` times before
+///
+/// The base delay doubles with each attempt, up to [`MAX_RETRY_DELAY`].
+fn retry_delay(base: Duration, attempt: u32) -> Duration {
+    base.checked_mul(2u32.saturating_pow(attempt))
+        .unwrap_or(MAX_RETRY_DELAY)
+        .min(MAX_RETRY_DELAY)
+}
+
+/// Stream the chunks of a generate reply as they arrive
+fn generate_chunks(response: Response) -> BoxStream<'static, Result<GenerateResponse>> {
+    let bytes = stream::unfold(response, |mut response| async move {
+        let chunk = response.chunk().await.transpose()?;
+        Some((chunk, response))
+    })
+    .boxed();
+    decode_chunks(bytes).boxed()
+}
+
+/// Decode the JSON objects of a generate reply from its body
+///
+/// A streamed reply holds one object per line, a complete reply a single
+/// object, possibly spread over several lines. Objects are yielded as soon
+/// as the bytes received hold them in full. The stream ends at the first
+/// error.
+fn decode_chunks<B, E>(
+    bytes: impl Stream<Item = std::result::Result<B, E>> + Unpin,
+) -> impl Stream<Item = Result<GenerateResponse>>
+where
+    B: AsRef<[u8]>,
+    E: Display,
+{
+    stream::unfold(
+        (bytes, Vec::new(), false),
+        |(mut bytes, mut buffer, mut finished)| async move {
+            loop {
+                let mut values =
+                    serde_json::Deserializer::from_slice(&buffer).into_iter::<GenerateResponse>();
+                match values.next() {
+                    Some(Ok(chunk)) => {
+                        let offset = values.byte_offset();
+                        buffer.drain(..offset);
+                        return Some((Ok(chunk), (bytes, buffer, finished)));
+                    }
+                    Some(Err(e)) if !e.is_eof() || finished => {
+                        let error = OllamaError::ParseError(e.to_string()).into();
+                        return Some((Err(error), (bytes, Vec::new(), true)));
+                    }
+                    None if finished => return None,
+                    // Wait for the rest of the object
+                    _ => {}
+                }
+                match bytes.next().await {
+                    Some(Ok(chunk)) => buffer.extend_from_slice(chunk.as_ref()),
+                    Some(Err(e)) => {
+                        let error = OllamaError::ConnectionError(e.to_string()).into();
+                        return Some((Err(error), (bytes, Vec::new(), true)));
+                    }
+                    None => finished = true,
+                }
+            }
+        },
+    )
+}
+
+/// Merge the chunks of a generate reply into a single response
+///
+/// The text of the chunks is concatenated; counters and timings come from
+/// the last chunk.
+async fn collect_chunks(
+    chunks: impl Stream<Item = Result<GenerateResponse>>,
+) -> Result<GenerateResponse> {
+    let mut chunks = std::pin::pin!(chunks);
+    let mut text = String::new();
+    let mut last = None;
+    while let Some(chunk) = chunks.try_next().await? {
+        text.push_str(&chunk.response);
+        last = Some(chunk);
+    }
+    let mut response =
+        last.ok_or_else(|| OllamaError::ParseError("Empty generate response".to_string()))?;
+    response.response = text;
+    Ok(response)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::MockOllama;
     use std::time::Duration;
 
+    #[test]
+    fn test_retry_delay() {
+        let base = Duration::from_millis(500);
+        assert_eq!(retry_delay(base, 0), base);
+        assert_eq!(retry_delay(base, 3), Duration::from_secs(4));
+        assert_eq!(retry_delay(base, 6), MAX_RETRY_DELAY);
+        assert_eq!(retry_delay(base, 40), MAX_RETRY_DELAY);
+        assert_eq!(retry_delay(Duration::MAX, 1), MAX_RETRY_DELAY);
+        assert_eq!(retry_delay(Duration::ZERO, 40), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_parse_tags() {
+        assert_eq!(
+            parse_tags("**Rust**, CLI\nweb (backend)\n"),
+            vec!["rust", "cli", "web backend"]
+        );
+        assert!(parse_tags("  \n**").is_empty());
+    }
+
+    #[test]
+    fn test_parse_tags_delimiters() {
+        let expected = vec!["rust", "web api", "cli"];
+        let responses = [
+            (TagDelimiter::Comma, "Rust, web API, CLI"),
+            (TagDelimiter::Newline, "- Rust\n- web API\n- CLI\n"),
+            (TagDelimiter::Newline, "1. Rust\n2) web API\n3. CLI"),
+            (TagDelimiter::Semicolon, "Rust; web API; CLI"),
+        ];
+        for (delimiter, response) in responses {
+            assert_eq!(
+                parse_tags_with(response, delimiter),
+                expected,
+                "{}",
+                response
+            );
+            assert_eq!(parse_tags_with(response, TagDelimiter::Auto), expected);
+        }
+
+        // An explicit delimiter keeps the others inside tags
+        assert_eq!(
+            parse_tags_with("rust, cli; web", TagDelimiter::Semicolon),
+            vec!["rust, cli", "web"]
+        );
+    }
+
+    #[test]
+    fn test_generation_usage() {
+        let mut usage = GenerationUsage::default();
+        let response: GenerateResponse = serde_json::from_str(
+            r#"{"response": "rust", "total_duration": 2000000000, "eval_count": 12}"#,
+        )
+        .unwrap();
+        usage.record(&response);
+        usage.record(&GenerateResponse {
+            response: String::new(),
+            total_duration: None,
+            eval_count: Some(3),
+            eval_duration: None,
+            done: true,
+            done_reason: None,
+            context: None,
+        });
+
+        assert_eq!(usage.responses, 2);
+        assert_eq!(usage.tokens, 15);
+        assert_eq!(usage.duration, Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_client_stats_shared_between_clones() {
+        let client = OllamaClient::new(ClientConfig {
+            base_url: "http://127.0.0.1:1".to_string(),
+            max_retries: 0,
+            ..ClientConfig::default()
+        })
+        .unwrap();
+        assert_eq!(client.stats().average_latency(), None);
+
+        let clone = client.clone();
+        assert!(clone.generate_tags("a").await.is_err());
+        assert!(client.generate_tags("b").await.is_err());
+
+        let stats = client.stats();
+        assert_eq!(stats.requests, 2);
+        assert_eq!(stats.failures, 2);
+        assert_eq!(clone.stats(), stats);
+        assert!(stats.average_latency().is_some());
+    }
+
+    #[test]
+    fn test_generate_response_truncation() {
+        let parse = |json: &str| serde_json::from_str::<GenerateResponse>(json).unwrap();
+
+        assert!(!parse(r#"{"response": "rust"}"#).is_truncated());
+        assert!(
+            !parse(r#"{"response": "rust", "done": true, "done_reason": "stop"}"#).is_truncated()
+        );
+        assert!(
+            parse(r#"{"response": "ru", "done": true, "done_reason": "length"}"#).is_truncated()
+        );
+        assert!(parse(r#"{"response": "ru", "done": false}"#).is_truncated());
+    }
+
+    #[tokio::test]
+    async fn test_decode_chunks() {
+        // Objects split across and sharing network chunks
+        let bytes = stream::iter(
+            [
+                "{\"response\": \"ru\", \"done\": false}\n{\"resp",
+                "onse\": \"st\", \"done\": false}\n",
+                "{\"response\": \"\", \"done\": true, \"eval_count\": 2}\n",
+            ]
+            .map(Ok::<_, String>),
+        );
+        let chunks: Vec<GenerateResponse> = decode_chunks(bytes).try_collect().await.unwrap();
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[1].response, "st");
+
+        let response = collect_chunks(stream::iter(chunks.into_iter().map(Ok)))
+            .await
+            .unwrap();
+        assert_eq!(response.response, "rust");
+        assert_eq!(response.eval_count, Some(2));
+        assert!(response.done);
+
+        // A complete reply spread over several lines
+        let bytes = stream::iter(["{\n  \"response\": \"rust\"\n}"].map(Ok::<_, String>));
+        let response = collect_chunks(decode_chunks(bytes)).await.unwrap();
+        assert_eq!(response.response, "rust");
+
+        // A reply cut off mid-object
+        let bytes = stream::iter(["{\"response\": \"ru"].map(Ok::<_, String>));
+        assert!(collect_chunks(decode_chunks(bytes)).await.is_err());
+    }
+
+    #[test]
+    fn test_is_transient() {
+        let api_error = |code| {
+            AppError::from(OllamaError::ApiError {
+                message: "error".to_string(),
+                status_code: Some(code),
+            })
+        };
+        assert!(is_transient(
+            &OllamaError::ConnectionError("timed out".to_string()).into()
+        ));
+        assert!(is_transient(&api_error(503)));
+        assert!(!is_transient(&api_error(400)));
+        assert!(!is_transient(
+            &OllamaError::ParseError("bad".to_string()).into()
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_custom_base_url() {
+        use tokio::{
+            io::{AsyncReadExt, AsyncWriteExt},
+            net::TcpListener,
+        };
+
+        // A one-shot server recording the request line it receives
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = vec![0; 8192];
+            let read = socket.read(&mut request).await.unwrap();
+            let body = r#"{"response": "rust, cli"}"#;
+            let reply = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(reply.as_bytes()).await.unwrap();
+            let request = String::from_utf8_lossy(&request[..read]).into_owned();
+            request.lines().next().unwrap_or_default().to_string()
+        });
+
+        let client = crate::OllamaClient::new(crate::ClientConfig {
+            base_url: format!("http://{}", address),
+            max_retries: 0,
+            ..crate::ClientConfig::default()
+        })
+        .unwrap();
+        let tags = client.generate_tags("/path/to/project").await.unwrap();
+        assert_eq!(tags, vec!["rust", "cli"]);
+        assert_eq!(server.await.unwrap(), "POST /api/generate HTTP/1.1");
+    }
+
+    #[test]
+    fn test_normalize_base_url() {
+        assert_eq!(
+            normalize_base_url("127.0.0.1:11434"),
+            "http://127.0.0.1:11434"
+        );
+        assert_eq!(
+            normalize_base_url("https://ollama.example.com/"),
+            "https://ollama.example.com"
+        );
+    }
+
+    #[test]
+    fn test_tag_examples_in_prompt() {
+        let examples =
+            parse_tag_examples("# comment\n~/projects/web/shop: React, TypeScript\n\nno-tags:\n");
+        assert_eq!(
+            examples,
+            vec![TagExample {
+                project: "~/projects/web/shop".to_string(),
+                tags: vec!["react".to_string(), "typescript".to_string()],
+            }]
+        );
+
+        let context = TagContext {
+            project: "/path/to/app".to_string(),
+            examples: vec![examples[0].clone(); MAX_TAG_EXAMPLES + 2],
+            ..TagContext::default()
+        };
+        let prompt = context.prompt();
+        assert!(prompt.starts_with("Here are example projects"));
+        assert_eq!(
+            prompt.matches("Tags: react, typescript").count(),
+            MAX_TAG_EXAMPLES
+        );
+        assert!(prompt.contains("this project: /path/to/app."));
+    }
+
+    #[test]
+    fn test_parse_scored_tags() {
+        let response = r#"{"tags": [
+            {"tag": "Rust", "confidence": 0.9},
+            {"tag": "web", "confidence": 0.3},
+            {"tag": "cli"},
+            "async",
+            {"tag": "rust", "confidence": 0.8}
+        ]}"#;
+        assert_eq!(
+            parse_scored_tags(response, 0.5),
+            Some(vec![
+                "rust".to_string(),
+                "cli".to_string(),
+                "async".to_string()
+            ])
+        );
+        assert_eq!(
+            parse_scored_tags(r#"[{"tag": "go", "confidence": 0.7}]"#, 0.5),
+            Some(vec!["go".to_string()])
+        );
+        assert_eq!(parse_scored_tags("rust, cli", 0.5), None);
+
+        let context = TagContext {
+            scored: true,
+            ..TagContext::new("/path/to/app")
+        };
+        assert!(context.prompt().contains("\"confidence\""));
+        assert!(context.system_prompt().contains("ONLY JSON"));
+    }
+
+    #[test]
+    fn test_prompt_commit_messages() {
+        let context = TagContext {
+            commit_messages: vec!["Add HTTP server".to_string(); MAX_COMMIT_MESSAGES + 3],
+            ..TagContext::new("/path/to/app")
+        };
+        let prompt = context.prompt();
+        assert!(prompt.contains("Its recent commit messages:\n- Add HTTP server\n"));
+        assert_eq!(
+            prompt.matches("- Add HTTP server").count(),
+            MAX_COMMIT_MESSAGES
+        );
+        assert!(!TagContext::new("/path/to/app")
+            .prompt()
+            .contains("commit messages"));
+    }
+
+    #[test]
+    fn test_system_prompt_override() {
+        let mut context = TagContext::new("/path/to/app");
+        assert!(context
+            .system_prompt()
+            .contains("ONLY comma-separated tags"));
+
+        context.system_prompt = Some("Reply with tags only.".to_string());
+        assert_eq!(context.system_prompt(), "Reply with tags only.");
+        assert!(context.prompt().contains("/path/to/app"));
+    }
+
     #[tokio::test]
     async fn test_generate_tags() -> std::result::Result<(), Box<dyn std::error::Error>> {
         let mock_response = r#"{
@@ -147,6 +1599,7 @@ mod tests {
         let config = ClientConfig {
             base_url: server.url(),
             timeout: Duration::from_secs(30),
+            ..ClientConfig::default()
         };
         let client = OllamaClient::new(config)?;
 
@@ -165,4 +1618,387 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_generate_tags_json() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let mock_response = r#"{
+            "model": "mistral",
+            "response": "{\"tags\": [\"Rust\", \" cli \", \"rust\"]}"
+        }"#;
+
+        let mut server = mockito::Server::new_async().await;
+
+        // Only match requests that ask for JSON output
+        let mock = server
+            .mock("POST", "/api/generate")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"format": "json"}"#.to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_response)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let config = ClientConfig {
+            base_url: server.url(),
+            timeout: Duration::from_secs(30),
+            ..ClientConfig::default()
+        };
+        let client = OllamaClient::new(config)?;
+
+        let tags = client.generate_tags_json("/path/to/project").await?;
+        assert_eq!(tags, vec!["rust", "cli"]);
+
+        let raw: TagsResponse = client.generate_json("anything").await?;
+        assert_eq!(raw.tags, vec!["Rust", " cli ", "rust"]);
+
+        mock.assert_async().await;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_generate_stream() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let mut ollama = MockOllama::start().await;
+        let mock = ollama
+            .server()
+            .mock("POST", "/api/generate")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"stream": true}"#.to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/x-ndjson")
+            .with_body(
+                "{\"response\": \"rust\", \"done\": false}\n\
+                 {\"response\": \", cli\", \"done\": true, \"done_reason\": \"stop\"}\n",
+            )
+            .create_async()
+            .await;
+
+        let request = GenerateRequest {
+            model: DEFAULT_MODEL.to_string(),
+            prompt: "Tags?".to_string(),
+            system: None,
+            options: GenerateOptions::default(),
+            format: None,
+            stream: false,
+            context: None,
+        };
+        let chunks: Vec<GenerateResponse> = ollama
+            .client()?
+            .generate_stream(&request)
+            .await?
+            .try_collect()
+            .await?;
+        let text: Vec<&str> = chunks.iter().map(|chunk| chunk.response.as_str()).collect();
+        assert_eq!(text, ["rust", ", cli"]);
+        assert!(chunks[1].done);
+        mock.assert_async().await;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_generate_retries() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let mut ollama = MockOllama::start().await;
+        let client = OllamaClient::new(ClientConfig {
+            base_url: ollama.url(),
+            max_retries: 2,
+            retry_base_delay: Duration::from_millis(1),
+            ..ClientConfig::default()
+        })?;
+
+        // Server errors are retried, then reported
+        let mock = ollama
+            .server()
+            .mock("POST", "/api/generate")
+            .with_status(503)
+            .with_body(r#"{"error": "model is loading"}"#)
+            .expect(3)
+            .create_async()
+            .await;
+        let error = client.generate_tags("/p/tool").await.unwrap_err();
+        assert!(error.to_string().contains("model is loading"));
+        mock.assert_async().await;
+        mock.remove_async().await;
+
+        // Errors about the request are not
+        let mock = ollama
+            .server()
+            .mock("POST", "/api/generate")
+            .with_status(404)
+            .with_body(r#"{"error": "model 'mistral' not found"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        assert!(client.generate_tags("/p/tool").await.is_err());
+        mock.assert_async().await;
+        assert_eq!(client.stats().requests, 4);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_generation_context() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let mut ollama = MockOllama::start().await;
+        let mock = ollama
+            .server()
+            .mock("POST", "/api/generate")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"context": [1, 2, 3]}"#.to_string(),
+            ))
+            .with_status(200)
+            .with_body(r#"{"response": "rust", "context": [1, 2, 3, 4]}"#)
+            .create_async()
+            .await;
+
+        let prime = ollama
+            .server()
+            .mock("POST", "/api/generate")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"prompt": "ok", "options": {"max_tokens": 1}}"#.to_string(),
+            ))
+            .with_status(200)
+            .with_body(r#"{"response": "ok", "context": [1, 2, 3]}"#)
+            .create_async()
+            .await;
+
+        let client = ollama.client()?;
+        let generation_context = client
+            .prime_context(&TagContext::new("/path/to/app"))
+            .await?;
+        assert_eq!(generation_context, Some(vec![1, 2, 3]));
+        let context = TagContext {
+            generation_context,
+            ..TagContext::new("/path/to/app")
+        };
+        let response = client.generate_tags_response(&context).await?;
+        assert_eq!(response.context, Some(vec![1, 2, 3, 4]));
+        mock.assert_async().await;
+        prime.assert_async().await;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_warm_up() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let mut ollama = MockOllama::start().await;
+        let mock = ollama
+            .server()
+            .mock("POST", "/api/generate")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"prompt": "ok"}"#.to_string(),
+            ))
+            .with_status(200)
+            .with_body(r#"{"response": "ok"}"#)
+            .create_async()
+            .await;
+
+        ollama.client()?.warm_up().await?;
+        mock.assert_async().await;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_extra_base_urls() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let mut first = MockOllama::start().await;
+        first.mock_generate("rust").await;
+        let mut second = MockOllama::start().await;
+        second.mock_generate("python").await;
+
+        // Requests alternate between the servers
+        let client = OllamaClient::new(ClientConfig {
+            base_url: first.url(),
+            extra_base_urls: vec![second.url()],
+            ..ClientConfig::default()
+        })?;
+        assert_eq!(client.generate_tags("a").await?, vec!["rust"]);
+        assert_eq!(client.generate_tags("b").await?, vec!["python"]);
+        assert_eq!(client.generate_tags("c").await?, vec!["rust"]);
+
+        // An unreachable server is skipped
+        let client = OllamaClient::new(ClientConfig {
+            base_url: "http://127.0.0.1:1".to_string(),
+            extra_base_urls: vec![second.url()],
+            ..ClientConfig::default()
+        })?;
+        assert_eq!(client.generate_tags("a").await?, vec!["python"]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_embedding() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let mut ollama = MockOllama::start().await;
+        ollama.mock_embeddings(&[0.5, -1.0]).await;
+
+        let embedding = ollama.client()?.create_embedding("a rust cli").await?;
+        assert_eq!(embedding, vec![0.5, -1.0]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_embed_batch() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let mut ollama = MockOllama::start().await;
+        ollama
+            .mock_embed_batch(&[vec![1.0, 0.0], vec![0.0, 1.0]])
+            .await;
+        let client = ollama.client()?;
+
+        let inputs = vec!["a rust cli".to_string(), "a web app".to_string()];
+        let embeddings = client.embed_batch(DEFAULT_MODEL, &inputs).await?;
+        assert_eq!(embeddings, vec![vec![1.0, 0.0], vec![0.0, 1.0]]);
+        assert!(client.embed_batch(DEFAULT_MODEL, &[]).await?.is_empty());
+        assert!(client
+            .embed_batch(DEFAULT_MODEL, &inputs[..1])
+            .await
+            .is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_embed_batch_without_batch_endpoint(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let mut ollama = MockOllama::start().await;
+        let batch = ollama
+            .server()
+            .mock("POST", "/api/embed")
+            .with_status(404)
+            .create_async()
+            .await;
+        ollama.mock_embeddings(&[0.5]).await;
+
+        let inputs = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let embeddings = ollama.client()?.embed_batch(DEFAULT_MODEL, &inputs).await?;
+        assert_eq!(embeddings, vec![vec![0.5]; 3]);
+        batch.assert_async().await;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_api_key_header() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let mut ollama = MockOllama::start().await;
+        let mock = ollama
+            .server()
+            .mock("GET", "/api/tags")
+            .match_header("authorization", "Bearer secret")
+            .with_status(200)
+            .with_body(r#"{"models": []}"#)
+            .create_async()
+            .await;
+
+        let client = OllamaClient::new(ClientConfig {
+            base_url: ollama.url(),
+            api_key: Some("secret".to_string()),
+            ..ClientConfig::default()
+        })?;
+        client.list_models().await?;
+        mock.assert_async().await;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_openai_api_style() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let mut ollama = MockOllama::start().await;
+        let chat = ollama
+            .server()
+            .mock("POST", "/v1/chat/completions")
+            .match_body(mockito::Matcher::Regex(r#""role":"system""#.to_string()))
+            .with_status(200)
+            .with_body(
+                r#"{"choices": [{"message": {"role": "assistant", "content": "rust, cli"}}]}"#,
+            )
+            .create_async()
+            .await;
+        let models = ollama
+            .server()
+            .mock("GET", "/v1/models")
+            .with_status(200)
+            .with_body(r#"{"object": "list", "data": [{"id": "qwen2.5"}]}"#)
+            .create_async()
+            .await;
+
+        let client = OllamaClient::new(ClientConfig {
+            base_url: ollama.url(),
+            api_style: ApiStyle::Openai,
+            ..ClientConfig::default()
+        })?;
+        assert_eq!(
+            client.generate_tags("/path/to/project").await?,
+            vec!["rust", "cli"]
+        );
+        let names: Vec<String> = client
+            .list_models()
+            .await?
+            .into_iter()
+            .map(|m| m.name)
+            .collect();
+        assert_eq!(names, vec!["qwen2.5"]);
+
+        chat.assert_async().await;
+        models.assert_async().await;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_models() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let mut ollama = MockOllama::start().await;
+        ollama.mock_models(&["mistral:latest", "llama3:8b"]).await;
+
+        let names: Vec<String> = ollama
+            .client()?
+            .list_models()
+            .await?
+            .into_iter()
+            .map(|m| m.name)
+            .collect();
+        assert_eq!(names, vec!["mistral:latest", "llama3:8b"]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_generate_tags_with_category(
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let mut server = mockito::Server::new_async().await;
+
+        // The category must reach both the task prompt and the system prompt
+        let mock = server
+            .mock("POST", "/api/generate")
+            .match_body(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::Regex(r#""prompt":"[^"]*Category: games"#.to_string()),
+                mockito::Matcher::Regex(r#""system":"[^"]*'games' category"#.to_string()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"response": "bevy, ecs"}"#)
+            .create_async()
+            .await;
+
+        let config = ClientConfig {
+            base_url: server.url(),
+            ..ClientConfig::default()
+        };
+        let client = OllamaClient::new(config)?;
+
+        let context = TagContext {
+            project: "/path/to/game".to_string(),
+            category: Some("games".to_string()),
+            ..TagContext::default()
+        };
+        let tags = client.generate_tags_with_context(&context).await?;
+        assert_eq!(tags, vec!["bevy", "ecs"]);
+
+        mock.assert_async().await;
+
+        Ok(())
+    }
 }
@@ -1,5 +1,13 @@
 mod client;
 mod setup;
 
-pub use client::{ClientConfig, GenerateOptions, GenerateRequest, GenerateResponse, OllamaClient};
-pub use setup::{check_model_availability, check_ollama_installation, ensure_model_available};
+pub use client::{
+    normalize_base_url, parse_scored_tags, parse_tag_examples, parse_tags, parse_tags_with,
+    ApiStyle, ClientConfig, ClientStats, GenerateOptions, GenerateRequest, GenerateResponse,
+    GenerationUsage, ModelInfo, OllamaClient, TagContext, TagDelimiter, TagExample, TagsResponse,
+    DEFAULT_MAX_RETRIES, DEFAULT_MODEL, DEFAULT_RETRY_BASE_DELAY_MS, DEFAULT_TIMEOUT_SECS,
+    EMBED_CONCURRENCY, MAX_COMMIT_MESSAGES, MAX_TAG_EXAMPLES,
+};
+pub use setup::{
+    check_model_availability, check_ollama_installation, ensure_model_available, model_matches,
+};
@@ -1,8 +1,6 @@
 use crate::error::OllamaError;
 use std::process::Command;
 
-const REQUIRED_MODEL: &str = "mistral";
-
 /// Checks if Ollama is installed and accessible
 pub fn check_ollama_installation() -> Result<bool, OllamaError> {
     let output = Command::new("ollama")
@@ -13,39 +11,66 @@ pub fn check_ollama_installation() -> Result<bool, OllamaError> {
     Ok(output.status.success())
 }
 
-/// Checks if the required model is pulled
-pub fn check_model_availability() -> Result<bool, OllamaError> {
+/// Checks if a model is pulled
+///
+/// The model is looked up by name in the output of `ollama list`, see
+/// [`model_matches`].
+pub fn check_model_availability(model: &str) -> Result<bool, OllamaError> {
     let output = Command::new("ollama")
         .arg("list")
         .output()
         .map_err(|e| OllamaError::Setup(format!("Failed to list Ollama models: {}", e)))?;
 
     let output_str = String::from_utf8_lossy(&output.stdout);
-    Ok(output_str.contains(REQUIRED_MODEL))
+    let available = installed_models(&output_str).any(|installed| model_matches(installed, model));
+    Ok(available)
+}
+
+/// Names of the models listed by `ollama list`, skipping its header
+fn installed_models(list_output: &str) -> impl Iterator<Item = &str> {
+    list_output
+        .lines()
+        .skip(1)
+        .filter_map(|line| line.split_whitespace().next())
+}
+
+/// Checks whether an installed model name refers to the wanted model
+///
+/// Ollama reports installed models with their tag (e.g. `mistral:latest`),
+/// while users usually configure the bare name, which implies `:latest`.
+pub fn model_matches(installed: &str, wanted: &str) -> bool {
+    if installed == wanted {
+        return true;
+    }
+    match (installed.split_once(':'), wanted.contains(':')) {
+        (Some((name, "latest")), false) => name == wanted,
+        _ => false,
+    }
 }
 
-/// Pulls the required model if it's not already available
-pub async fn ensure_model_available() -> Result<(), OllamaError> {
+/// Pulls a model if it's not already available
+pub async fn ensure_model_available(model: &str) -> Result<(), OllamaError> {
     if !check_ollama_installation()? {
         return Err(OllamaError::Setup(
             "Ollama is not installed. Please install it first.".to_string(),
         ));
     }
 
-    if !check_model_availability()? {
-        println!("Pulling required model '{}'...", REQUIRED_MODEL);
+    if !check_model_availability(model)? {
+        println!("Pulling model '{}'...", model);
         let status = Command::new("ollama")
             .arg("pull")
-            .arg(REQUIRED_MODEL)
+            .arg(model)
             .status()
             .map_err(|e| OllamaError::Setup(format!("Failed to pull model: {}", e)))?;
 
         if !status.success() {
-            return Err(OllamaError::Setup(
-                "Failed to pull the required model".to_string(),
-            ));
+            return Err(OllamaError::Setup(format!(
+                "Failed to pull the model '{}'",
+                model
+            )));
         }
-        println!("Model '{}' pulled successfully!", REQUIRED_MODEL);
+        println!("Model '{}' pulled successfully!", model);
     }
 
     Ok(())
@@ -61,9 +86,28 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_model_matches() {
+        assert!(model_matches("mistral:latest", "mistral"));
+        assert!(model_matches("llama3:8b", "llama3:8b"));
+        assert!(!model_matches("llama3:8b", "llama3"));
+        assert!(!model_matches("mistral:latest", "mistral:7b"));
+    }
+
+    #[test]
+    fn test_installed_models() {
+        let output = "\
+NAME              ID              SIZE      MODIFIED
+mistral:latest    f974a74358d6    4.1 GB    2 weeks ago
+llama3:8b         365c0bd3c000    4.7 GB    3 days ago
+";
+        let models: Vec<&str> = installed_models(output).collect();
+        assert_eq!(models, vec!["mistral:latest", "llama3:8b"]);
+    }
+
     #[test]
     fn test_model_availability_check() {
-        let result = check_model_availability();
+        let result = check_model_availability(crate::ollama::DEFAULT_MODEL);
         assert!(result.is_ok());
     }
 }
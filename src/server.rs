@@ -0,0 +1,190 @@
+//! Read-only HTTP API over a project index
+//!
+//! This module backs the `serve` command. It exposes the projects of an
+//! index file and their statistics as JSON:
+//! - `GET /projects`: all projects, or only those carrying `?tag=NAME`
+//! - `GET /stats`: the same aggregate reported by the `stats` command
+//!
+//! The index file is polled for changes and reloaded in the background, so a
+//! re-run of `index` is picked up without restarting the server.
+
+use crate::{
+    error::Result,
+    indexer::{load_index, ProjectStats},
+    models::Project,
+    ui::{print_info, print_warning},
+};
+use hyper::{
+    header,
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use serde::Serialize;
+use std::{
+    convert::Infallible,
+    fs,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+use tokio::sync::RwLock;
+
+/// How often the index file is checked for changes
+const RELOAD_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Projects currently served, along with the file time they were loaded at
+struct IndexState {
+    projects: Vec<Project>,
+    modified: Option<SystemTime>,
+}
+
+impl IndexState {
+    fn load(path: &Path) -> Result<Self> {
+        Ok(Self {
+            modified: modified_time(path),
+            projects: load_index(path)?,
+        })
+    }
+}
+
+type SharedState = Arc<RwLock<IndexState>>;
+
+/// Serve an index file over HTTP until the process is stopped
+///
+/// # Arguments
+///
+/// * `index_file` - Path to the index file to serve
+/// * `addr` - Address to listen on
+pub async fn serve(index_file: PathBuf, addr: SocketAddr) -> Result<()> {
+    let state: SharedState = Arc::new(RwLock::new(IndexState::load(&index_file)?));
+    tokio::spawn(watch_index(index_file, state.clone()));
+
+    let make_service = make_service_fn(move |_conn| {
+        let state = state.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                let state = state.clone();
+                async move { Ok::<_, Infallible>(handle(request, &state).await) }
+            }))
+        }
+    });
+
+    Server::try_bind(&addr)?.serve(make_service).await?;
+    Ok(())
+}
+
+/// Reload the index whenever its modification time changes
+async fn watch_index(path: PathBuf, state: SharedState) {
+    let mut interval = tokio::time::interval(RELOAD_INTERVAL);
+    loop {
+        interval.tick().await;
+
+        let modified = modified_time(&path);
+        if modified == state.read().await.modified {
+            continue;
+        }
+
+        // Keep serving the previous index if the new one can't be read, e.g.
+        // while it is still being written
+        match load_index(&path) {
+            Ok(projects) => {
+                print_info(&format!("Reloaded {} projects", projects.len()));
+                *state.write().await = IndexState { projects, modified };
+            }
+            Err(e) => print_warning(&format!("Failed to reload {}: {}", path.display(), e)),
+        }
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Route a request to the matching endpoint
+async fn handle(request: Request<Body>, state: &SharedState) -> Response<Body> {
+    if request.method() != Method::GET {
+        return error_response(StatusCode::METHOD_NOT_ALLOWED, "only GET is supported");
+    }
+
+    let state = state.read().await;
+    match request.uri().path() {
+        "/projects" => {
+            let tag = request.uri().query().and_then(|query| {
+                url::form_urlencoded::parse(query.as_bytes())
+                    .find(|(key, _)| key == "tag")
+                    .map(|(_, value)| value.into_owned())
+            });
+            let projects: Vec<&Project> = state
+                .projects
+                .iter()
+                .filter(|project| match &tag {
+                    Some(tag) => project.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)),
+                    None => true,
+                })
+                .collect();
+            json_response(&projects)
+        }
+        "/stats" => json_response(&ProjectStats::from_projects(&state.projects)),
+        _ => error_response(StatusCode::NOT_FOUND, "not found"),
+    }
+}
+
+fn json_response<T: Serialize>(value: &T) -> Response<Body> {
+    match serde_json::to_vec(value) {
+        Ok(body) => Response::builder()
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap_or_default(),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    }
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response<Body> {
+    let body = serde_json::json!({ "error": message }).to_string();
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn get(state: &SharedState, uri: &str) -> (StatusCode, serde_json::Value) {
+        let request = Request::get(uri).body(Body::empty()).unwrap();
+        let response = handle(request, state).await;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_endpoints() {
+        let mut tagged = Project::new("a".to_string(), PathBuf::from("/p/a"));
+        tagged.tags = vec!["rust".to_string()];
+        let untagged = Project::new("b".to_string(), PathBuf::from("/p/b"));
+        let state: SharedState = Arc::new(RwLock::new(IndexState {
+            projects: vec![tagged, untagged],
+            modified: None,
+        }));
+
+        let (status, body) = get(&state, "/projects").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body.as_array().unwrap().len(), 2);
+
+        let (_, body) = get(&state, "/projects?tag=Rust").await;
+        assert_eq!(body.as_array().unwrap().len(), 1);
+        assert_eq!(body[0]["name"], "a");
+
+        let (status, body) = get(&state, "/stats").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["total_projects"], 2);
+        assert_eq!(body["untagged_projects"], 1);
+
+        let (status, _) = get(&state, "/missing").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
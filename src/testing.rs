@@ -0,0 +1,103 @@
+//! Test helpers
+//!
+//! This module provides [`MockOllama`], a fake Ollama server that answers
+//! with canned responses, so the real [`OllamaClient`] code paths can be
+//! exercised without a running Ollama. It is compiled for the crate's own
+//! tests and, for examples and downstream tests, with the `testing` feature.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use projets_indexer::testing::MockOllama;
+//!
+//! #[tokio::main]
+//! async fn main() -> projets_indexer::Result<()> {
+//!     let mut ollama = MockOllama::start().await;
+//!     ollama.mock_generate("rust, cli").await;
+//!
+//!     let tags = ollama.client()?.generate_tags("/path/to/project").await?;
+//!     assert_eq!(tags, vec!["rust", "cli"]);
+//!     Ok(())
+//! }
+//! ```
+
+use crate::{
+    error::Result,
+    ollama::{ClientConfig, OllamaClient, DEFAULT_MODEL},
+};
+use mockito::{Mock, Server, ServerGuard};
+use serde_json::json;
+
+/// A fake Ollama server serving canned responses
+///
+/// Responses are registered with the `mock_*` methods and stay active for as
+/// long as the `MockOllama` is alive.
+pub struct MockOllama {
+    server: ServerGuard,
+    mocks: Vec<Mock>,
+}
+
+impl MockOllama {
+    /// Start a fake server with no endpoints mocked
+    pub async fn start() -> Self {
+        Self {
+            server: Server::new_async().await,
+            mocks: Vec::new(),
+        }
+    }
+
+    /// Base URL of the fake server
+    pub fn url(&self) -> String {
+        self.server.url()
+    }
+
+    /// Create a client pointed at the fake server
+    pub fn client(&self) -> Result<OllamaClient> {
+        OllamaClient::new(ClientConfig {
+            base_url: self.url(),
+            ..ClientConfig::default()
+        })
+    }
+
+    /// Answer `POST /api/generate` with the given model output
+    pub async fn mock_generate(&mut self, response: &str) {
+        let body = json!({ "model": DEFAULT_MODEL, "response": response, "done": true });
+        self.mock_json("POST", "/api/generate", body).await;
+    }
+
+    /// Answer `GET /api/tags` with the given installed model names
+    pub async fn mock_models(&mut self, names: &[&str]) {
+        let models: Vec<_> = names.iter().map(|name| json!({ "name": name })).collect();
+        self.mock_json("GET", "/api/tags", json!({ "models": models }))
+            .await;
+    }
+
+    /// Answer `POST /api/embeddings` with the given embedding vector
+    pub async fn mock_embeddings(&mut self, embedding: &[f32]) {
+        self.mock_json("POST", "/api/embeddings", json!({ "embedding": embedding }))
+            .await;
+    }
+
+    /// Answer `POST /api/embed` with the given embedding vectors, one per input
+    pub async fn mock_embed_batch(&mut self, embeddings: &[Vec<f32>]) {
+        self.mock_json("POST", "/api/embed", json!({ "embeddings": embeddings }))
+            .await;
+    }
+
+    /// Access the underlying server to register custom mocks
+    pub fn server(&mut self) -> &mut ServerGuard {
+        &mut self.server
+    }
+
+    async fn mock_json(&mut self, method: &str, path: &str, body: serde_json::Value) {
+        let mock = self
+            .server
+            .mock(method, path)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(body.to_string())
+            .create_async()
+            .await;
+        self.mocks.push(mock);
+    }
+}
@@ -3,11 +3,19 @@
 //! This module provides user-friendly terminal UI components for displaying
 //! progress and status information during project indexing.
 
+use crate::indexer::{diff::IndexDiff, ProgressReporter};
+use chrono::{DateTime, Utc};
 use console::{style, Emoji};
+use dialoguer::{theme::ColorfulTheme, Confirm, Select};
 use indicatif::{ProgressBar, ProgressStyle};
 use std::collections::HashMap;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 
+/// Whether decorative output is suppressed, see [`set_quiet`]
+static QUIET: AtomicBool = AtomicBool::new(false);
+
 static LOOKING_GLASS: Emoji<'_, '_> = Emoji("🔍 ", "");
 static SPARKLES: Emoji<'_, '_> = Emoji("✨ ", "");
 static ROCKET: Emoji<'_, '_> = Emoji("🚀 ", "");
@@ -19,9 +27,27 @@ static TAG: Emoji<'_, '_> = Emoji("🏷️  ", "");
 static CHART: Emoji<'_, '_> = Emoji("📊 ", "");
 static GEAR: Emoji<'_, '_> = Emoji("⚙️  ", "");
 static CLOCK: Emoji<'_, '_> = Emoji("🕒 ", "");
+static SNOWFLAKE: Emoji<'_, '_> = Emoji("❄️  ", "");
+
+/// Suppress decorative output
+///
+/// In quiet mode the banner, section headers, info messages, per-project
+/// details and progress spinners are not shown; success, warning and error
+/// messages and command results still are.
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+/// Check whether decorative output is suppressed
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
 
 /// ASCII art banner for the project indexer
 pub fn print_banner() {
+    if is_quiet() {
+        return;
+    }
     println!(
         "{}",
         style(
@@ -54,6 +80,9 @@ pub fn print_banner() {
 
 /// Create a progress bar for directory scanning
 pub fn create_scan_progress() -> ProgressBar {
+    if is_quiet() {
+        return ProgressBar::hidden();
+    }
     let pb = ProgressBar::new_spinner();
     pb.set_style(
         ProgressStyle::default_spinner()
@@ -67,8 +96,63 @@ pub fn create_scan_progress() -> ProgressBar {
     pb
 }
 
+/// Create a spinner shown while waiting on a single task
+pub fn create_spinner(message: &str) -> ProgressBar {
+    if is_quiet() {
+        return ProgressBar::hidden();
+    }
+    let pb = ProgressBar::new_spinner();
+    pb.set_style(
+        ProgressStyle::default_spinner()
+            .tick_chars("⠁⠂⠄⡀⢀⠠⠐⠈ ")
+            .template("{spinner} {wide_msg}")
+            .unwrap(),
+    );
+    pb.set_message(message.to_string());
+    pb.enable_steady_tick(Duration::from_millis(100));
+    pb
+}
+
+/// Progress reporter drawing an `indicatif` spinner while indexing
+pub struct IndicatifReporter {
+    progress: ProgressBar,
+}
+
+impl IndicatifReporter {
+    /// Create a reporter and start its spinner
+    pub fn new() -> Self {
+        Self {
+            progress: create_scan_progress(),
+        }
+    }
+}
+
+impl Default for IndicatifReporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProgressReporter for IndicatifReporter {
+    fn on_scan_start(&self) {
+        self.progress.set_message("Looking for projects...");
+    }
+
+    fn on_project(&self, name: &str) {
+        self.progress.inc(1);
+        self.progress.set_message(format!("{}{}", FOLDER, name));
+    }
+
+    fn on_finish(&self, _count: usize) {
+        self.progress.finish_and_clear();
+    }
+}
+
 /// Create a progress bar for project processing
 pub fn create_process_progress(total: u64) -> ProgressBar {
+    if is_quiet() {
+        return ProgressBar::hidden();
+    }
     let pb = ProgressBar::new(total);
     pb.set_style(
         ProgressStyle::default_bar()
@@ -82,6 +166,9 @@ pub fn create_process_progress(total: u64) -> ProgressBar {
 
 /// Print a section header
 pub fn print_section(emoji: &str, text: &str) {
+    if is_quiet() {
+        return;
+    }
     println!(
         "\n{} {}\n{}",
         style(emoji).bold(),
@@ -101,6 +188,9 @@ pub fn print_success(msg: &str) {
 
 /// Print an info message
 pub fn print_info(msg: &str) {
+    if is_quiet() {
+        return;
+    }
     println!(
         "{} {}",
         style(format!("{} Info:", CLOCK)).blue().bold(),
@@ -122,6 +212,39 @@ pub fn print_error(msg: &str) {
     println!("{} {}", style("✖ Error:").red().bold(), style(msg).red());
 }
 
+/// Check whether both stdin and stdout are attached to a terminal
+///
+/// Interactive prompts are only shown when this returns `true`.
+pub fn is_interactive() -> bool {
+    std::io::stdin().is_terminal() && std::io::stdout().is_terminal()
+}
+
+/// Ask the user a yes/no question, defaulting to "no"
+pub fn prompt_confirm(question: &str) -> bool {
+    Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(question)
+        .default(false)
+        .interact()
+        .unwrap_or(false)
+}
+
+/// Ask the user to pick one entry from a list with the arrow keys
+///
+/// Returns the index of the chosen entry, or `None` if the user cancels with
+/// Esc or `q`. Nothing is asked with `--quiet`, which returns `None`.
+pub fn prompt_select(prompt: &str, items: &[String]) -> Option<usize> {
+    if is_quiet() {
+        return None;
+    }
+    Select::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .items(items)
+        .default(0)
+        .interact_opt()
+        .ok()
+        .flatten()
+}
+
 /// Print detailed project information
 pub fn print_project_details(
     name: &str,
@@ -129,7 +252,11 @@ pub fn print_project_details(
     status: &str,
     tags: &[String],
     path: &str,
+    last_modified: DateTime<Utc>,
 ) {
+    if is_quiet() {
+        return;
+    }
     println!("\n{} {}", FOLDER, style(name).bold().underlined());
     println!("   {} Category: {}", CHART, style(category).cyan());
     println!(
@@ -137,6 +264,7 @@ pub fn print_project_details(
         GEAR,
         match status {
             "active" => style(status).green(),
+            "stale" => style(status).blue(),
             "archived" => style(status).yellow(),
             _ => style(status).dim(),
         }
@@ -150,14 +278,33 @@ pub fn print_project_details(
             style(tags.join(", ")).cyan().to_string()
         }
     );
+    println!(
+        "   {} Modified: {}",
+        CLOCK,
+        style(format_relative_time(last_modified, Utc::now())).cyan()
+    );
     println!("   {} Path: {}", LOOKING_GLASS, style(path).dim());
 }
 
+/// Print how well a project matched a fuzzy search, below its details
+pub fn print_match_score(score: f64) {
+    if is_quiet() {
+        return;
+    }
+    println!(
+        "   {} Score: {}",
+        SPARKLES,
+        style(format!("{:.2}", score)).cyan()
+    );
+}
+
 /// Print project statistics with categories
 pub fn print_detailed_stats(
     total_projects: usize,
     active_projects: usize,
+    stale_projects: usize,
     archived_projects: usize,
+    unknown_projects: usize,
     projects_by_category: &HashMap<String, usize>,
     total_tags: usize,
 ) {
@@ -175,11 +322,23 @@ pub fn print_detailed_stats(
         ROCKET,
         style(active_projects).green().bold()
     );
+    println!(
+        "{} Stale Projects: {}",
+        SNOWFLAKE,
+        style(stale_projects).blue().bold()
+    );
     println!(
         "{} Archived Projects: {}",
         CONSTRUCTION,
         style(archived_projects).yellow().bold()
     );
+    if unknown_projects > 0 {
+        println!(
+            "{} Unknown Status: {}",
+            FOLDER,
+            style(unknown_projects).dim().bold()
+        );
+    }
     println!("{} Total Tags: {}", TAG, style(total_tags).cyan().bold());
 
     // Category breakdown
@@ -195,6 +354,291 @@ pub fn print_detailed_stats(
     }
 }
 
+/// Print the number of projects of each status per category
+///
+/// Each row holds a category and its active, stale, archived and unknown
+/// project counts, printed in the given order.
+pub fn print_category_status(categories: &[(&str, [usize; 4])]) {
+    println!("\n{}", style("Status by Category").bold());
+    println!("{}", style("─".repeat(30)).dim());
+    for (category, [active, stale, archived, unknown]) in categories {
+        let mut line = format!(
+            "{} {}: {} active, {} stale, {} archived",
+            FOLDER,
+            style(category).cyan(),
+            style(active).green().bold(),
+            style(stale).blue().bold(),
+            style(archived).yellow().bold()
+        );
+        if *unknown > 0 {
+            line.push_str(&format!(", {} unknown", style(unknown).dim().bold()));
+        }
+        println!("{}", line);
+    }
+}
+
+/// Print how well projects are covered by tags
+pub fn print_tag_coverage(distinct_tags: usize, average_tags: f64, untagged_projects: usize) {
+    println!("\n{}", style("Tag Coverage").bold());
+    println!("{}", style("─".repeat(30)).dim());
+    println!(
+        "{} Distinct Tags: {}",
+        TAG,
+        style(distinct_tags).cyan().bold()
+    );
+    println!(
+        "{} Average Tags per Project: {}",
+        CHART,
+        style(format!("{:.1}", average_tags)).cyan().bold()
+    );
+    let untagged = style(untagged_projects).bold();
+    println!(
+        "{} Projects Without Tags: {}",
+        CONSTRUCTION,
+        if untagged_projects > 0 {
+            untagged.yellow()
+        } else {
+            untagged.green()
+        }
+    );
+}
+
+/// Print how many projects appear to contain tests
+pub fn print_test_presence(projects_with_tests: usize, total_projects: usize) {
+    println!("\n{}", style("Tests").bold());
+    println!("{}", style("─".repeat(30)).dim());
+    let with_tests = style(projects_with_tests).bold();
+    println!(
+        "{} {} of {} projects have tests",
+        CHART,
+        if projects_with_tests < total_projects {
+            with_tests.yellow()
+        } else {
+            with_tests.green()
+        },
+        style(total_projects).bold()
+    );
+}
+
+/// Print how many projects changed within the last `days` days
+pub fn print_recent_activity(recent_projects: usize, dated_projects: usize, days: i64) {
+    println!("\n{}", style("Recent Activity").bold());
+    println!("{}", style("─".repeat(30)).dim());
+    println!(
+        "{} {} of {} projects changed in the last {} days",
+        CLOCK,
+        style(recent_projects).bold().green(),
+        style(dated_projects).bold(),
+        days
+    );
+}
+
+/// Print how many projects show badges in their README
+pub fn print_readme_badges(projects_with_badges: usize, total_projects: usize) {
+    println!("\n{}", style("Documentation").bold());
+    println!("{}", style("─".repeat(30)).dim());
+    let with_badges = style(projects_with_badges).bold();
+    println!(
+        "{} {} of {} projects have README badges",
+        CHART,
+        if projects_with_badges < total_projects {
+            with_badges.yellow()
+        } else {
+            with_badges.green()
+        },
+        style(total_projects).bold()
+    );
+}
+
+/// Print the changes between two indexes
+pub fn print_index_diff(diff: &IndexDiff) {
+    if diff.is_empty() {
+        println!("{} No changes", SPARKLES);
+        return;
+    }
+
+    for project in &diff.added {
+        println!(
+            "{} {} {}",
+            style("+").green().bold(),
+            style(&project.name).green(),
+            style(project.path.display()).dim()
+        );
+    }
+    for project in &diff.removed {
+        println!(
+            "{} {} {}",
+            style("-").red().bold(),
+            style(&project.name).red(),
+            style(project.path.display()).dim()
+        );
+    }
+    for change in &diff.changed {
+        println!(
+            "{} {} {}",
+            style("~").yellow().bold(),
+            style(&change.name).yellow(),
+            style(change.path.display()).dim()
+        );
+        if let Some((old, new)) = &change.renamed {
+            println!("    name: {} → {}", old, new);
+        }
+        if let Some((old, new)) = &change.category {
+            println!("    category: {} → {}", old, new);
+        }
+        if let Some((old, new)) = &change.status {
+            println!(
+                "    status: {} → {}",
+                format!("{:?}", old).to_lowercase(),
+                format!("{:?}", new).to_lowercase()
+            );
+        }
+        for tag in &change.added_tags {
+            println!("    {} {}", style("+").green(), tag);
+        }
+        for tag in &change.removed_tags {
+            println!("    {} {}", style("-").red(), tag);
+        }
+    }
+
+    println!(
+        "\n{} added, {} removed, {} changed",
+        style(diff.added.len()).green().bold(),
+        style(diff.removed.len()).red().bold(),
+        style(diff.changed.len()).yellow().bold()
+    );
+}
+
+/// Print the tags generated by several models in side-by-side columns
+///
+/// A model whose request failed shows `(failed)` instead of tags. Tags
+/// generated by every model are highlighted.
+pub fn print_tag_comparison(results: &[(String, Option<Vec<String>>)]) {
+    const FAILED: &str = "(failed)";
+
+    let widths: Vec<usize> = results
+        .iter()
+        .map(|(model, tags)| {
+            let tags = tags.iter().flatten().map(|tag| tag.chars().count());
+            tags.chain([model.chars().count(), FAILED.len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let is_common = |tag: &String| {
+        results
+            .iter()
+            .all(|(_, tags)| tags.as_ref().is_some_and(|tags| tags.contains(tag)))
+    };
+
+    let header: Vec<String> = results
+        .iter()
+        .zip(&widths)
+        .map(|((model, _), &width)| style(format!("{:<width$}", model)).bold().to_string())
+        .collect();
+    println!("{}", header.join("  "));
+    let rule: Vec<String> = widths.iter().map(|width| "─".repeat(*width)).collect();
+    println!("{}", style(rule.join("  ")).dim());
+
+    let rows = results
+        .iter()
+        .map(|(_, tags)| tags.as_ref().map_or(1, Vec::len))
+        .max()
+        .unwrap_or(0);
+    for row in 0..rows {
+        let cells: Vec<String> = results
+            .iter()
+            .zip(&widths)
+            .map(|((_, tags), &width)| match tags {
+                Some(tags) => match tags.get(row) {
+                    Some(tag) if is_common(tag) => {
+                        style(format!("{:<width$}", tag)).green().to_string()
+                    }
+                    Some(tag) => format!("{:<width$}", tag),
+                    None => " ".repeat(width),
+                },
+                None if row == 0 => style(format!("{:<width$}", FAILED)).red().to_string(),
+                None => " ".repeat(width),
+            })
+            .collect();
+        println!("{}", cells.join("  ").trim_end());
+    }
+}
+
+/// Print a chart of counts per period, in the given order
+pub fn print_timeline(entries: &[(String, usize)]) {
+    const BAR_WIDTH: usize = 30;
+
+    println!("\n{}", style("Activity Timeline").bold());
+    println!("{}", style("─".repeat(30)).dim());
+    let max = entries.iter().map(|(_, count)| *count).max().unwrap_or(0);
+    for (period, count) in entries {
+        let width = (count * BAR_WIDTH).checked_div(max).unwrap_or(0);
+        println!(
+            "{} {} {} {}",
+            CLOCK,
+            style(period).cyan(),
+            style("▇".repeat(width.max(usize::from(*count > 0)))).green(),
+            style(count).bold()
+        );
+    }
+}
+
+/// Format a count with thousands separators, e.g. `1,240`
+pub fn format_count(count: u64) -> String {
+    let digits = count.to_string();
+    let mut formatted = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+    formatted
+}
+
+/// Format how long before `now` a time is, e.g. `3 days ago`
+///
+/// The largest whole unit is used, from minutes up to years; times less
+/// than a minute ago, or in the future, are `just now`.
+pub fn format_relative_time(time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    const UNITS: &[(&str, i64)] = &[
+        ("year", 365 * 24 * 3600),
+        ("month", 30 * 24 * 3600),
+        ("week", 7 * 24 * 3600),
+        ("day", 24 * 3600),
+        ("hour", 3600),
+        ("minute", 60),
+    ];
+
+    let seconds = (now - time).num_seconds();
+    UNITS
+        .iter()
+        .find(|(_, unit)| seconds >= *unit)
+        .map(|(name, unit)| {
+            let count = seconds / unit;
+            format!(
+                "{} {}{} ago",
+                count,
+                name,
+                if count == 1 { "" } else { "s" }
+            )
+        })
+        .unwrap_or_else(|| "just now".to_string())
+}
+
+/// Print a titled count breakdown, largest entries first
+pub fn print_breakdown(title: &str, counts: &HashMap<String, usize>) {
+    let mut entries: Vec<(&String, &usize)> = counts.iter().collect();
+    entries.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+
+    println!("\n{}", style(title).bold());
+    println!("{}", style("─".repeat(30)).dim());
+    for (name, count) in entries {
+        println!("{} {}: {}", CHART, style(name).cyan(), style(count).bold());
+    }
+}
+
 /// Print configuration details
 pub fn print_config_details(projects_dir: &str, index_file: &str, enable_ollama: bool) {
     println!("\n{}", style("Configuration Details").bold());
@@ -215,3 +659,31 @@ pub fn print_config_details(projects_dir: &str, index_file: &str, enable_ollama:
         }
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_relative_time() {
+        let now = Utc::now();
+        let ago =
+            |seconds: i64| format_relative_time(now - chrono::Duration::seconds(seconds), now);
+
+        assert_eq!(ago(-60), "just now");
+        assert_eq!(ago(0), "just now");
+        assert_eq!(ago(59), "just now");
+        assert_eq!(ago(60), "1 minute ago");
+        assert_eq!(ago(3599), "59 minutes ago");
+        assert_eq!(ago(3600), "1 hour ago");
+        assert_eq!(ago(24 * 3600 - 1), "23 hours ago");
+        assert_eq!(ago(24 * 3600), "1 day ago");
+        assert_eq!(ago(7 * 24 * 3600 - 1), "6 days ago");
+        assert_eq!(ago(7 * 24 * 3600), "1 week ago");
+        assert_eq!(ago(30 * 24 * 3600 - 1), "4 weeks ago");
+        assert_eq!(ago(30 * 24 * 3600), "1 month ago");
+        assert_eq!(ago(365 * 24 * 3600 - 1), "12 months ago");
+        assert_eq!(ago(365 * 24 * 3600), "1 year ago");
+        assert_eq!(ago(3 * 365 * 24 * 3600), "3 years ago");
+    }
+}
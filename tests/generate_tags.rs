@@ -0,0 +1,48 @@
+//! End-to-end tests of the `generate-tags` command
+
+use std::fs;
+use std::net::TcpListener;
+use std::process::Command;
+use tempfile::tempdir;
+
+/// URL of a local port nothing listens on
+fn unreachable_url() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    drop(listener);
+    format!("http://127.0.0.1:{}", port)
+}
+
+#[test]
+fn test_generate_tags_falls_back_when_ollama_is_unreachable() {
+    let temp_dir = tempdir().unwrap();
+    let project = temp_dir.path().join("tool");
+    fs::create_dir(&project).unwrap();
+    let output = temp_dir.path().join("tags.json");
+
+    let result = Command::new(env!("CARGO_BIN_EXE_projets-indexer"))
+        .current_dir(temp_dir.path())
+        .args([
+            "--quiet",
+            "--ollama-url",
+            &unreachable_url(),
+            "generate-tags",
+        ])
+        .arg("--project-dir")
+        .arg(&project)
+        .arg("--output")
+        .arg(&output)
+        .output()
+        .unwrap();
+
+    let stdout = String::from_utf8_lossy(&result.stdout);
+    assert!(
+        result.status.success(),
+        "generate-tags failed: {}{}",
+        stdout,
+        String::from_utf8_lossy(&result.stderr)
+    );
+    assert!(stdout.contains("Ollama is unreachable"), "{}", stdout);
+    let tags: Vec<String> = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    assert_eq!(tags, vec!["rust", "cli"]);
+}